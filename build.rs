@@ -1,3 +1,49 @@
+//! Captures the resolved versions of Bevy and the physics crates from the
+//! `Cargo.lock` next to the manifest, so the HUD can show what a run was built with.
+
+/// Lock file package name → environment variable exposed to `src/versions.rs`.
+const TRACKED: [(&str, &str); 5] = [
+    ("bevy", "AVR_VERSION_BEVY"),
+    ("avian2d", "AVR_VERSION_AVIAN2D"),
+    ("avian3d", "AVR_VERSION_AVIAN3D"),
+    ("bevy_rapier2d", "AVR_VERSION_RAPIER2D"),
+    ("bevy_rapier3d", "AVR_VERSION_RAPIER3D"),
+];
+
 fn main() {
-    // No build-time config needed — physics backend is runtime-switchable.
+    // The alternate manifests in bevy16/ and bevy17/ point here too; each has its own lock file.
+    let manifest_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_default();
+    let lock_path = std::path::Path::new(&manifest_dir).join("Cargo.lock");
+    println!("cargo:rerun-if-changed={}", lock_path.display());
+    let Ok(lock) = std::fs::read_to_string(&lock_path) else {
+        return;
+    };
+
+    for package in lock.split("[[package]]") {
+        let field = |key: &str| {
+            package.lines().find_map(|line| {
+                line.strip_prefix(key)?
+                    .trim_start()
+                    .strip_prefix('=')?
+                    .trim()
+                    .strip_prefix('"')?
+                    .strip_suffix('"')
+                    .map(str::to_string)
+            })
+        };
+        let (Some(name), Some(version)) = (field("name"), field("version")) else {
+            continue;
+        };
+        let Some((_, var)) = TRACKED.iter().find(|(tracked, _)| *tracked == name) else {
+            continue;
+        };
+        // Git dependencies report the crate version of the branch, so add the commit.
+        let commit = field("source")
+            .and_then(|source| Some(source.rsplit_once('#')?.1.get(..7)?.to_string()));
+        let version = match commit {
+            Some(commit) => format!("{version} (git {commit})"),
+            None => version,
+        };
+        println!("cargo:rustc-env={var}={version}");
+    }
 }
@@ -8,9 +8,20 @@
 //! Key API differences normalised here:
 //! - Avian `rectangle(w,h)` takes full extents; Rapier `cuboid(hx,hy)` takes half-extents.
 //! - Avian `circle(r)` / `sphere(r)` vs Rapier `ball(r)`.
+//! - Avian `capsule(r, length)` takes the full segment length; Rapier `capsule_y(half, r)`
+//!   takes half of it, radius last.
 //! - Avian `RigidBody::Static` vs Rapier `RigidBody::Fixed`.
+//! - Avian defaults to `CoefficientCombine::Average` for restitution; Rapier's
+//!   combine rule is set explicitly to `CoefficientCombineRule::Average` so both match.
+//! - Friction combine defaults differ between engines, so both use an explicit
+//!   `Multiply` rule for balls and walls.
 
-use bevy::prelude::*;
+use bevy::{ecs::schedule::InternedScheduleLabel, prelude::*};
+use serde::{Deserialize, Serialize};
+use std::num::NonZeroUsize;
+use std::time::Duration;
+
+use crate::cli;
 
 // Bevy 0.16 called this `StateScoped`; 0.17+ renamed it to `DespawnOnExit`.
 // Cargo16.toml enables `legacy_state_scoped` by default to activate this shim.
@@ -19,14 +30,29 @@ use bevy::prelude::DespawnOnExit;
 #[cfg(feature = "legacy_state_scoped")]
 use bevy::prelude::StateScoped as DespawnOnExit;
 
-/// Pixels per meter — passed to every physics plugin so unit conversion matches.
-pub const LENGTH_UNIT: f32 = 10.0;
+// Bevy 0.17 renamed buffered events to messages, and Avian 0.4 renamed
+// `CollisionStarted` to `CollisionStart` along with it.
+#[cfg(not(feature = "legacy_state_scoped"))]
+use avian2d::prelude::CollisionStart as Avian2dCollisionStart;
+#[cfg(feature = "legacy_state_scoped")]
+use avian2d::prelude::CollisionStarted as Avian2dCollisionStart;
+#[cfg(not(feature = "legacy_state_scoped"))]
+use avian3d::prelude::CollisionStart as Avian3dCollisionStart;
+#[cfg(feature = "legacy_state_scoped")]
+use avian3d::prelude::CollisionStarted as Avian3dCollisionStart;
+#[cfg(feature = "legacy_state_scoped")]
+use bevy::prelude::EventReader as MessageReader;
+#[cfg(not(feature = "legacy_state_scoped"))]
+use bevy::prelude::MessageReader;
 
 /// Gravitational acceleration in m/s².
-pub const GRAVITY: f32 = 9.81 * LENGTH_UNIT;
+pub const GRAVITY: f32 = 9.81;
 
-/// Z depth of the 3D pool (full extent). Balls spawn within ±POOL_DEPTH/2.
-/// Matches WIDTH in walls.rs (1920) so the pool floor is square.
+/// Timestep (seconds) both engines advance by per `FixedUpdate` tick under `--fixed-dt`.
+pub const FIXED_DT: f32 = 1.0 / 60.0;
+
+/// Default Z depth of the 3D pool (full extent), overridden with `--depth`.
+/// Matches the default width in walls.rs (1920) so the pool floor is square.
 pub const POOL_DEPTH: f32 = 1920.0;
 
 // ── Physics mode ─────────────────────────────────────────────────────────────
@@ -41,6 +67,14 @@ pub enum PhysicsMode {
 }
 
 impl PhysicsMode {
+    /// Every mode, in cycle order.
+    pub const ALL: [PhysicsMode; 4] = [
+        PhysicsMode::Avian2d,
+        PhysicsMode::Avian3d,
+        PhysicsMode::Rapier2d,
+        PhysicsMode::Rapier3d,
+    ];
+
     pub fn label(self) -> &'static str {
         match self {
             PhysicsMode::Avian2d => "Avian 2D",
@@ -50,6 +84,43 @@ impl PhysicsMode {
         }
     }
 
+    /// The mode's identifying colour, used for its balls and its perf graph curve:
+    /// Avian blue, Rapier orange, the 2D variant brighter than the 3D one.
+    pub fn color(self) -> Color {
+        match self {
+            PhysicsMode::Avian2d => Color::srgb(0.3, 0.8, 1.0),
+            PhysicsMode::Avian3d => Color::srgb(0.2, 0.4, 1.0),
+            PhysicsMode::Rapier2d => Color::srgb(1.0, 0.7, 0.2),
+            PhysicsMode::Rapier3d => Color::srgb(0.9, 0.4, 0.1),
+        }
+    }
+
+    /// The other engine in the same dimension, shown beside this one in split screen.
+    pub fn counterpart(self) -> Self {
+        match self {
+            PhysicsMode::Avian2d => PhysicsMode::Rapier2d,
+            PhysicsMode::Avian3d => PhysicsMode::Rapier3d,
+            PhysicsMode::Rapier2d => PhysicsMode::Avian2d,
+            PhysicsMode::Rapier3d => PhysicsMode::Avian3d,
+        }
+    }
+
+    /// The starting mode from `--mode`, or [`PhysicsMode::default`] without it.
+    fn from_args() -> Self {
+        cli::value("--mode")
+            .and_then(|raw| {
+                let mode = raw.parse().ok();
+                if mode.is_none() {
+                    warn!(
+                        "Ignoring invalid value for --mode: {raw:?} \
+                         (expected avian2d, avian3d, rapier2d or rapier3d)"
+                    );
+                }
+                mode
+            })
+            .unwrap_or_default()
+    }
+
     /// Cycle to the next mode: Avian2D → Avian3D → Rapier2D → Rapier3D → Avian2D.
     pub fn next(self) -> Self {
         match self {
@@ -61,102 +132,1213 @@ impl PhysicsMode {
     }
 }
 
+/// Parses the `--mode` names: the label without the space, in any case.
+impl std::str::FromStr for PhysicsMode {
+    type Err = ();
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        PhysicsMode::ALL
+            .into_iter()
+            .find(|mode| {
+                mode.label()
+                    .replace(' ', "")
+                    .eq_ignore_ascii_case(name.trim())
+            })
+            .ok_or(())
+    }
+}
+
+/// The mode whose engine simulates a wall or ball. Usually the active mode; in
+/// split screen the counterpart's pool and balls share the world with it.
+#[derive(Component, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BodyOwner(pub PhysicsMode);
+
 // ── Plugin registration ──────────────────────────────────────────────────────
 
+/// Whether `--fixed-dt` was given. Both engines then advance by exactly [`FIXED_DT`]
+/// per `FixedUpdate` tick and the timed spawner runs there too, so a given seed
+/// produces the same simulation tick for tick regardless of frame rate.
+pub fn fixed_dt() -> bool {
+    cli::flag("--fixed-dt")
+}
+
+/// Pixels per meter, from `--length-unit` (default 10) — passed to every physics
+/// plugin so unit conversion matches. The pool, and with it the spawn ranges, is
+/// scaled by [`Self::scale`] so it keeps its size in meters; gravity in px/s² grows
+/// with the unit too. The ball radius stays in pixels, so each engine's tolerances
+/// shrink relative to a ball.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct LengthUnit(pub f32);
+
+impl LengthUnit {
+    pub fn from_args() -> Self {
+        match cli::parsed::<f32>("--length-unit") {
+            Some(unit) if unit > 0.0 && unit.is_finite() => Self(unit),
+            Some(unit) => {
+                warn!("Ignoring non-positive --length-unit {unit}");
+                Self::default()
+            }
+            None => Self::default(),
+        }
+    }
+
+    /// [`GRAVITY`] in pixels per second squared.
+    pub fn gravity(self) -> f32 {
+        GRAVITY * self.0
+    }
+
+    /// This unit relative to the default one: the factor pool sizes are scaled by.
+    pub fn scale(self) -> f32 {
+        self.0 / Self::default().0
+    }
+}
+
+impl Default for LengthUnit {
+    fn default() -> Self {
+        Self(10.0)
+    }
+}
+
+/// Schedule Rapier steps in: `FixedUpdate` under `--fixed-dt`, else its default `PostUpdate`.
+pub fn rapier_schedule() -> InternedScheduleLabel {
+    if fixed_dt() {
+        FixedUpdate.intern()
+    } else {
+        PostUpdate.intern()
+    }
+}
+
 pub fn plugin(app: &mut App) {
-    app.init_state::<PhysicsMode>();
+    app.insert_state(PhysicsMode::from_args());
     // Bevy 0.16 requires this call to register the StateScoped cleanup systems.
     // In 0.17+, DespawnOnExit registers itself automatically.
     #[cfg(feature = "legacy_state_scoped")]
     app.enable_state_scoped_entities::<PhysicsMode>();
 
+    // Avian already steps in the fixed schedule; `--fixed-dt` only pins its rate.
+    // Rapier's timestep mode is inserted before its plugin, which otherwise warns
+    // about running in `FixedUpdate` with the default variable timestep.
+    if fixed_dt() {
+        app.insert_resource(Time::<Fixed>::from_seconds(FIXED_DT as f64));
+        app.insert_resource(bevy_rapier2d::plugin::TimestepMode::Fixed {
+            dt: FIXED_DT,
+            substeps: 1,
+        });
+        app.insert_resource(bevy_rapier3d::plugin::TimestepMode::Fixed {
+            dt: FIXED_DT,
+            substeps: 1,
+        });
+    }
+
     // Register all four physics plugins — idle ones just have no entities to process.
-    app.add_plugins(avian2d::PhysicsPlugins::default().with_length_unit(LENGTH_UNIT));
-    // Disable PhysicsInterpolationPlugin on avian3d to avoid a duplicate-plugin panic:
-    // both avian2d and avian3d unconditionally add TransformInterpolationPlugin through it.
+    // Parsed once here; `spawner::plugin` scales the `PoolConfig` by the resource.
+    let unit = LengthUnit::from_args();
+    app.insert_resource(unit);
+    // Both avian2d and avian3d unconditionally add TransformInterpolationPlugin
+    // through their PhysicsInterpolationPlugin, which panics as a duplicate. Both are
+    // disabled and the shared plugin is added once; it only eases entities that carry
+    // `TransformInterpolation`, which `interpolate` puts on Avian balls.
+    app.add_plugins(
+        avian2d::PhysicsPlugins::default()
+            .with_length_unit(unit.0)
+            .build()
+            .disable::<avian2d::interpolation::PhysicsInterpolationPlugin>(),
+    );
     app.add_plugins(
         avian3d::PhysicsPlugins::default()
-            .with_length_unit(LENGTH_UNIT)
+            .with_length_unit(unit.0)
             .build()
             .disable::<avian3d::interpolation::PhysicsInterpolationPlugin>(),
     );
+    app.add_plugins(avian2d::interpolation::TransformInterpolationPlugin::default());
     app.add_plugins(
         bevy_rapier2d::plugin::RapierPhysicsPlugin::<bevy_rapier2d::plugin::NoUserData>::default()
-            .with_length_unit(LENGTH_UNIT),
+            .with_length_unit(unit.0)
+            .in_schedule(rapier_schedule()),
     );
     app.add_plugins(
         bevy_rapier3d::plugin::RapierPhysicsPlugin::<bevy_rapier3d::plugin::NoUserData>::default()
-            .with_length_unit(LENGTH_UNIT),
+            .with_length_unit(unit.0)
+            .in_schedule(rapier_schedule()),
     );
 
-    // Avian gravity is in m/s²; Vec2/Vec3 NEG_Y * 9.81.
-    app.insert_resource(avian2d::prelude::Gravity(Vec2::NEG_Y * GRAVITY));
-    app.insert_resource(avian3d::prelude::Gravity(Vec3::NEG_Y * GRAVITY));
+    // Avian gravity is in world units (pixels) per second squared.
+    app.insert_resource(avian2d::prelude::Gravity(Vec2::NEG_Y * unit.gravity()));
+    app.insert_resource(avian3d::prelude::Gravity(Vec3::NEG_Y * unit.gravity()));
 
     // Rapier's RapierConfiguration::new(length_unit) defaults gravity to
-    // -9.81 * length_unit, which is 10× too strong with LENGTH_UNIT=10.
+    // -9.81 * length_unit, which knows nothing of GravityScale and GravityAngle;
+    // apply_gravity overwrites it with the exact vector Avian gets, at any unit.
     // RapierConfiguration is a Component (not a Resource) in newer bevy_rapier,
-    // so we patch it via startup systems after the plugin inserts it.
-    app.add_systems(Startup, (set_rapier2d_gravity, set_rapier3d_gravity));
+    // so apply_gravity patches it on the context entities. The resource starts out
+    // "changed", so this also sets the initial gravity on the first frame.
+    app.init_resource::<GravityScale>();
+    app.init_resource::<GravityAngle>();
+    app.add_systems(
+        Update,
+        apply_gravity.run_if(resource_changed::<GravityScale>.or(resource_changed::<GravityAngle>)),
+    );
+
+    // Like gravity, substeps are pushed into every engine whenever the resource changes.
+    app.init_resource::<Substeps>();
+    app.add_systems(Update, apply_substeps.run_if(resource_changed::<Substeps>));
+    app.init_resource::<RapierIterations>();
+    app.add_systems(
+        Update,
+        apply_rapier_iterations.run_if(resource_changed::<RapierIterations>),
+    );
+
+    app.init_resource::<SleepStats>();
+    app.add_systems(Update, update_sleep_stats);
+
+    app.init_resource::<PileTop>();
+    app.init_resource::<InFlight>();
+    app.add_systems(Update, update_pile_line);
+
+    app.init_resource::<ContactCount>();
+    app.add_systems(Update, update_contact_count);
+
+    app.insert_resource(CollisionEvents(cli::flag("--new-contacts")));
+    app.init_resource::<NewContactRate>();
+    app.add_systems(
+        Update,
+        update_new_contact_rate.run_if(|events: Res<CollisionEvents>| events.0),
+    );
+
+    app.init_resource::<KineticEnergy>();
+    app.add_systems(Update, update_energy);
+
+    app.init_resource::<PenetrationStats>();
+    app.add_systems(Update, update_penetration_stats);
+
+    app.init_resource::<FloorForce>();
+    app.add_systems(Update, update_floor_force.after(update_energy));
+}
+
+// ── Gravity ──────────────────────────────────────────────────────────────────
+
+/// Runtime multiplier applied to [`LengthUnit::gravity`] in all four engines.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct GravityScale(pub f32);
+
+impl GravityScale {
+    pub const MIN: f32 = 0.1;
+    pub const MAX: f32 = 10.0;
+}
+
+impl Default for GravityScale {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+/// Tilt of the gravity vector in degrees, rotating it counter-clockwise in the XY
+/// plane (positive pushes balls towards +X). 3D rotates about Z only, matching 2D.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Default)]
+pub struct GravityAngle(pub f32);
+
+impl GravityAngle {
+    /// Gravity in px/s² times `scale`, pointing straight down, rotated by this angle.
+    pub fn gravity(self, scale: GravityScale, unit: LengthUnit) -> Vec2 {
+        Vec2::from_angle(self.0.to_radians()).rotate(Vec2::NEG_Y * unit.gravity() * scale.0)
+    }
+}
+
+/// Writes the scaled, tilted gravity into every engine. Avian keeps gravity in a
+/// resource, Rapier on the `RapierConfiguration` component, so both are patched
+/// with the same value.
+fn apply_gravity(
+    scale: Res<GravityScale>,
+    angle: Res<GravityAngle>,
+    unit: Res<LengthUnit>,
+    mut avian2d_gravity: ResMut<avian2d::prelude::Gravity>,
+    mut avian3d_gravity: ResMut<avian3d::prelude::Gravity>,
+    mut rapier2d_config: Query<&mut bevy_rapier2d::plugin::RapierConfiguration>,
+    mut rapier3d_config: Query<&mut bevy_rapier3d::plugin::RapierConfiguration>,
+) {
+    let gravity = angle.gravity(*scale, *unit);
+    avian2d_gravity.0 = gravity;
+    avian3d_gravity.0 = gravity.extend(0.0);
+    for mut config in &mut rapier2d_config {
+        config.gravity = bevy_rapier2d::math::Vect::new(gravity.x, gravity.y);
+    }
+    for mut config in &mut rapier3d_config {
+        config.gravity = bevy_rapier3d::math::Vect::new(gravity.x, gravity.y, 0.0);
+    }
+}
+
+// ── Solver substeps ──────────────────────────────────────────────────────────
+
+/// Solver substeps applied to both engines. `None` keeps each engine's native
+/// default. Avian exposes this as the `SubstepCount` resource; Rapier's TGS solver
+/// runs one substep per `IntegrationParameters::num_solver_iterations`, stored on
+/// the `RapierContextSimulation` component.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Substeps(pub Option<usize>);
+
+impl Substeps {
+    pub const MAX: usize = 64;
+
+    /// The engine's own default substep count for `mode`.
+    pub fn native(mode: PhysicsMode) -> usize {
+        match mode {
+            PhysicsMode::Avian2d => avian2d::prelude::SubstepCount::default().0 as usize,
+            PhysicsMode::Avian3d => avian3d::prelude::SubstepCount::default().0 as usize,
+            PhysicsMode::Rapier2d => rapier2d_native_iterations().get(),
+            PhysicsMode::Rapier3d => rapier3d_native_iterations().get(),
+        }
+    }
+}
+
+fn rapier2d_native_iterations() -> NonZeroUsize {
+    bevy_rapier2d::rapier::dynamics::IntegrationParameters::default().num_solver_iterations
+}
+
+fn rapier3d_native_iterations() -> NonZeroUsize {
+    bevy_rapier3d::rapier::dynamics::IntegrationParameters::default().num_solver_iterations
+}
+
+/// Writes [`Substeps`] into every engine, restoring native defaults for `None`.
+fn apply_substeps(
+    substeps: Res<Substeps>,
+    mut avian2d_substeps: ResMut<avian2d::prelude::SubstepCount>,
+    mut avian3d_substeps: ResMut<avian3d::prelude::SubstepCount>,
+    mut rapier2d_sim: Query<&mut bevy_rapier2d::plugin::context::RapierContextSimulation>,
+    mut rapier3d_sim: Query<&mut bevy_rapier3d::plugin::context::RapierContextSimulation>,
+) {
+    let count = substeps.0.and_then(NonZeroUsize::new);
+
+    avian2d_substeps.0 = count.map_or(avian2d::prelude::SubstepCount::default().0, |n| {
+        n.get() as u32
+    });
+    avian3d_substeps.0 = count.map_or(avian3d::prelude::SubstepCount::default().0, |n| {
+        n.get() as u32
+    });
+    for mut sim in &mut rapier2d_sim {
+        sim.integration_parameters.num_solver_iterations =
+            count.unwrap_or_else(rapier2d_native_iterations);
+    }
+    for mut sim in &mut rapier3d_sim {
+        sim.integration_parameters.num_solver_iterations =
+            count.unwrap_or_else(rapier3d_native_iterations);
+    }
+}
+
+/// Rapier's inner solver loops, run inside each of its [`Substeps`] solver
+/// iterations: PGS velocity iterations and position stabilization iterations.
+/// Raising them makes Rapier stiffer at a CPU cost. Avian's XPBD solver has no
+/// counterpart, so they only apply to the Rapier modes.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RapierIterations {
+    pub velocity: usize,
+    pub stabilization: usize,
+}
+
+impl Default for RapierIterations {
+    fn default() -> Self {
+        let native = bevy_rapier3d::rapier::dynamics::IntegrationParameters::default();
+        Self {
+            velocity: native.num_internal_pgs_iterations,
+            stabilization: native.num_internal_stabilization_iterations,
+        }
+    }
+}
+
+impl RapierIterations {
+    pub const MAX: usize = 16;
+}
+
+/// Writes [`RapierIterations`] into both Rapier contexts.
+fn apply_rapier_iterations(
+    iterations: Res<RapierIterations>,
+    mut rapier2d_sim: Query<&mut bevy_rapier2d::plugin::context::RapierContextSimulation>,
+    mut rapier3d_sim: Query<&mut bevy_rapier3d::plugin::context::RapierContextSimulation>,
+) {
+    for mut sim in &mut rapier2d_sim {
+        let params = &mut sim.integration_parameters;
+        params.num_internal_pgs_iterations = iterations.velocity;
+        params.num_internal_stabilization_iterations = iterations.stabilization;
+    }
+    for mut sim in &mut rapier3d_sim {
+        let params = &mut sim.integration_parameters;
+        params.num_internal_pgs_iterations = iterations.velocity;
+        params.num_internal_stabilization_iterations = iterations.stabilization;
+    }
+}
+
+// ── Sleep stats ──────────────────────────────────────────────────────────────
+
+/// Dynamic bodies in the active mode, split by sleep state. Settled piles going to
+/// sleep is what lets FPS recover, and the engines differ in how eagerly they sleep.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SleepStats {
+    pub awake: usize,
+    pub sleeping: usize,
+}
+
+impl SleepStats {
+    fn count(sleeping: impl Iterator<Item = bool>) -> Self {
+        sleeping.fold(Self::default(), |mut stats, asleep| {
+            if asleep {
+                stats.sleeping += 1;
+            } else {
+                stats.awake += 1;
+            }
+            stats
+        })
+    }
+}
+
+/// Avian marks sleeping bodies with the `Sleeping` component; Rapier keeps the
+/// state on its own rigid bodies, read here from the context's `RapierRigidBodySet`.
+fn update_sleep_stats(
+    state: Res<State<PhysicsMode>>,
+    mut stats: ResMut<SleepStats>,
+    avian2d_bodies: Query<(
+        &avian2d::prelude::RigidBody,
+        Has<avian2d::prelude::Sleeping>,
+    )>,
+    avian3d_bodies: Query<(
+        &avian3d::prelude::RigidBody,
+        Has<avian3d::prelude::Sleeping>,
+    )>,
+    rapier2d_sets: Query<&bevy_rapier2d::plugin::context::RapierRigidBodySet>,
+    rapier3d_sets: Query<&bevy_rapier3d::plugin::context::RapierRigidBodySet>,
+) {
+    let counted = match state.get() {
+        PhysicsMode::Avian2d => SleepStats::count(
+            avian2d_bodies
+                .iter()
+                .filter(|(body, _)| body.is_dynamic())
+                .map(|(_, asleep)| asleep),
+        ),
+        PhysicsMode::Avian3d => SleepStats::count(
+            avian3d_bodies
+                .iter()
+                .filter(|(body, _)| body.is_dynamic())
+                .map(|(_, asleep)| asleep),
+        ),
+        PhysicsMode::Rapier2d => SleepStats::count(rapier2d_sets.iter().flat_map(|set| {
+            set.bodies
+                .iter()
+                .filter(|(_, body)| body.is_dynamic())
+                .map(|(_, body)| body.is_sleeping())
+        })),
+        PhysicsMode::Rapier3d => SleepStats::count(rapier3d_sets.iter().flat_map(|set| {
+            set.bodies
+                .iter()
+                .filter(|(_, body)| body.is_dynamic())
+                .map(|(_, body)| body.is_sleeping())
+        })),
+    };
+    // Avoid change detection churn so the HUD only rewrites text when counts move.
+    stats.set_if_neq(counted);
+}
+
+// ── Pile line ────────────────────────────────────────────────────────────────
+
+/// Height of the highest sleeping dynamic body in the active mode, i.e. the top of
+/// the settled pile; `None` until something sleeps.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Default)]
+pub struct PileTop(pub Option<f32>);
+
+impl PileTop {
+    fn find(bodies: &[(f32, bool)]) -> Self {
+        Self(
+            bodies
+                .iter()
+                .filter(|(_, asleep)| *asleep)
+                .map(|(y, _)| *y)
+                .reduce(f32::max),
+        )
+    }
+}
+
+/// Awake dynamic bodies above the [`PileTop`] in the active mode: the balls still
+/// falling or bouncing rather than resting on the pile. Read by the spawner to
+/// top up to `--target-in-flight`.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct InFlight(pub usize);
+
+impl InFlight {
+    /// Until something sleeps, every awake body counts.
+    fn count(bodies: &[(f32, bool)], top: PileTop) -> Self {
+        let pile_line = top.0.unwrap_or(f32::NEG_INFINITY);
+        Self(
+            bodies
+                .iter()
+                .filter(|(y, asleep)| !asleep && *y > pile_line)
+                .count(),
+        )
+    }
+}
+
+/// Same sources as [`update_sleep_stats`], paired with each body's height.
+fn update_pile_line(
+    state: Res<State<PhysicsMode>>,
+    mut pile_top: ResMut<PileTop>,
+    mut in_flight: ResMut<InFlight>,
+    avian2d_bodies: Query<(
+        &avian2d::prelude::RigidBody,
+        &Transform,
+        Has<avian2d::prelude::Sleeping>,
+    )>,
+    avian3d_bodies: Query<(
+        &avian3d::prelude::RigidBody,
+        &Transform,
+        Has<avian3d::prelude::Sleeping>,
+    )>,
+    rapier2d_sets: Query<&bevy_rapier2d::plugin::context::RapierRigidBodySet>,
+    rapier3d_sets: Query<&bevy_rapier3d::plugin::context::RapierRigidBodySet>,
+) {
+    let bodies: Vec<(f32, bool)> = match state.get() {
+        PhysicsMode::Avian2d => avian2d_bodies
+            .iter()
+            .filter(|(body, ..)| body.is_dynamic())
+            .map(|(_, transform, asleep)| (transform.translation.y, asleep))
+            .collect(),
+        PhysicsMode::Avian3d => avian3d_bodies
+            .iter()
+            .filter(|(body, ..)| body.is_dynamic())
+            .map(|(_, transform, asleep)| (transform.translation.y, asleep))
+            .collect(),
+        PhysicsMode::Rapier2d => rapier2d_sets
+            .iter()
+            .flat_map(|set| {
+                set.bodies
+                    .iter()
+                    .filter(|(_, body)| body.is_dynamic())
+                    .map(|(_, body)| (body.translation().y, body.is_sleeping()))
+            })
+            .collect(),
+        PhysicsMode::Rapier3d => rapier3d_sets
+            .iter()
+            .flat_map(|set| {
+                set.bodies
+                    .iter()
+                    .filter(|(_, body)| body.is_dynamic())
+                    .map(|(_, body)| (body.translation().y, body.is_sleeping()))
+            })
+            .collect(),
+    };
+    let top = PileTop::find(&bodies);
+    pile_top.set_if_neq(top);
+    in_flight.set_if_neq(InFlight::count(&bodies, top));
+}
+
+// ── Contact count ────────────────────────────────────────────────────────────
+
+/// Touching narrow-phase contact pairs in the active mode. Solver cost follows this
+/// rather than the ball count: a settled pile has far more contacts than balls.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ContactCount(pub usize);
+
+/// Avian exposes its contact graph through the `Collisions` system param; Rapier
+/// keeps contact pairs in the narrow phase of each `RapierContextSimulation`.
+/// Pairs whose AABBs overlap without touching are not counted.
+fn update_contact_count(
+    state: Res<State<PhysicsMode>>,
+    mut count: ResMut<ContactCount>,
+    avian2d_collisions: avian2d::prelude::Collisions,
+    avian3d_collisions: avian3d::prelude::Collisions,
+    rapier2d_sims: Query<&bevy_rapier2d::plugin::context::RapierContextSimulation>,
+    rapier3d_sims: Query<&bevy_rapier3d::plugin::context::RapierContextSimulation>,
+) {
+    let touching = match state.get() {
+        PhysicsMode::Avian2d => avian2d_collisions
+            .iter()
+            .filter(|pair| pair.is_touching())
+            .count(),
+        PhysicsMode::Avian3d => avian3d_collisions
+            .iter()
+            .filter(|pair| pair.is_touching())
+            .count(),
+        PhysicsMode::Rapier2d => rapier2d_sims
+            .iter()
+            .flat_map(|sim| sim.narrow_phase.contact_pairs())
+            .filter(|pair| pair.has_any_active_contact)
+            .count(),
+        PhysicsMode::Rapier3d => rapier3d_sims
+            .iter()
+            .flat_map(|sim| sim.narrow_phase.contact_pairs())
+            .filter(|pair| pair.has_any_active_contact)
+            .count(),
+    };
+    count.set_if_neq(ContactCount(touching));
+}
+
+// ── New contact rate ─────────────────────────────────────────────────────────
+
+/// Whether balls opt into collision events, from `--new-contacts`. Both engines charge
+/// for building the events, which would skew every other measurement, so they only
+/// feed [`NewContactRate`] when asked for.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct CollisionEvents(pub bool);
+
+/// Collisions started per second in the active mode, counted over one-second
+/// windows. High while balls are falling and bouncing, low once the pile settles,
+/// so it tracks broad-phase churn where [`ContactCount`] tracks solver load.
+#[derive(Resource)]
+pub struct NewContactRate {
+    /// Collisions started during the last complete window.
+    pub per_second: usize,
+    counted: usize,
+    window: Timer,
+}
+
+impl Default for NewContactRate {
+    fn default() -> Self {
+        Self {
+            per_second: 0,
+            counted: 0,
+            window: Timer::from_seconds(1.0, TimerMode::Repeating),
+        }
+    }
+}
+
+impl NewContactRate {
+    /// Adds `started` collisions and, once the window has elapsed, publishes the
+    /// count for it and starts a fresh one.
+    fn record(&mut self, started: usize, delta: Duration) {
+        self.counted += started;
+        self.window.tick(delta);
+        if self.window.just_finished() {
+            self.per_second = self.counted;
+            self.counted = 0;
+        }
+    }
+}
+
+/// Balls opt into collision events when spawned under [`CollisionEvents`] (see
+/// [`spawn_ball`]); every reader is drained each frame so events from a previous
+/// mode never leak into the next.
+fn update_new_contact_rate(
+    state: Res<State<PhysicsMode>>,
+    time: Res<Time<Real>>,
+    mut rate: ResMut<NewContactRate>,
+    mut avian2d_started: MessageReader<Avian2dCollisionStart>,
+    mut avian3d_started: MessageReader<Avian3dCollisionStart>,
+    mut rapier2d_events: MessageReader<bevy_rapier2d::prelude::CollisionEvent>,
+    mut rapier3d_events: MessageReader<bevy_rapier3d::prelude::CollisionEvent>,
+) {
+    let avian2d = avian2d_started.read().count();
+    let avian3d = avian3d_started.read().count();
+    let rapier2d = rapier2d_events
+        .read()
+        .filter(|event| matches!(event, bevy_rapier2d::prelude::CollisionEvent::Started(..)))
+        .count();
+    let rapier3d = rapier3d_events
+        .read()
+        .filter(|event| matches!(event, bevy_rapier3d::prelude::CollisionEvent::Started(..)))
+        .count();
+    let started = match state.get() {
+        PhysicsMode::Avian2d => avian2d,
+        PhysicsMode::Avian3d => avian3d,
+        PhysicsMode::Rapier2d => rapier2d,
+        PhysicsMode::Rapier3d => rapier3d,
+    };
+    rate.record(started, time.delta());
+}
+
+// ── Kinetic energy ───────────────────────────────────────────────────────────
+
+/// Summed translational kinetic energy (½·m·v²) of every ball in the active mode,
+/// in each engine's own mass units times px²/s². Once spawning stops a pile should
+/// only lose energy; a value that creeps up is a solver instability.
+#[derive(Resource)]
+pub struct KineticEnergy {
+    pub total: f32,
+    /// Summed mass of the same balls.
+    mass: f32,
+    balls: usize,
+    /// Energy and ball count as of one second ago, for the trend.
+    previous: Option<(f32, usize)>,
+    window: Timer,
+}
+
+impl Default for KineticEnergy {
+    fn default() -> Self {
+        Self {
+            total: 0.0,
+            mass: 0.0,
+            balls: 0,
+            previous: None,
+            window: Timer::from_seconds(1.0, TimerMode::Repeating),
+        }
+    }
+}
+
+impl KineticEnergy {
+    /// Change over the last second, once a full second has been seen.
+    pub fn change_per_second(&self) -> Option<f32> {
+        self.previous.map(|(previous, _)| self.total - previous)
+    }
+
+    /// Energy rose over the last second although no ball was added or removed,
+    /// i.e. the solver created it.
+    pub fn is_gaining(&self) -> bool {
+        self.previous
+            .is_some_and(|(previous, balls)| self.total > previous && self.balls == balls)
+    }
+
+    /// Energy per unit mass, i.e. half the mass-weighted mean squared speed in px²/s².
+    /// Unlike [`KineticEnergy::total`] it means the same in every engine and at
+    /// every density and ball count. Zero without balls.
+    pub fn per_unit_mass(&self) -> f32 {
+        if self.mass > 0.0 {
+            self.total / self.mass
+        } else {
+            0.0
+        }
+    }
+
+    fn record(&mut self, total: f32, mass: f32, balls: usize, delta: Duration) {
+        self.window.tick(delta);
+        if self.window.just_finished() {
+            self.previous = Some((self.total, self.balls));
+        }
+        self.total = total;
+        self.mass = mass;
+        self.balls = balls;
+    }
+}
+
+/// Avian keeps the mass in `ComputedMass`; Rapier writes it to `ReadMassProperties`
+/// (which every ball carries). Summed in `f64` so a large pile doesn't lose the
+/// small contributions.
+fn update_energy(
+    state: Res<State<PhysicsMode>>,
+    time: Res<Time<Real>>,
+    mut energy: ResMut<KineticEnergy>,
+    avian2d_balls: Query<
+        (
+            &avian2d::prelude::ComputedMass,
+            &avian2d::prelude::LinearVelocity,
+        ),
+        With<crate::spawner::Ball>,
+    >,
+    avian3d_balls: Query<
+        (
+            &avian3d::prelude::ComputedMass,
+            &avian3d::prelude::LinearVelocity,
+        ),
+        With<crate::spawner::Ball>,
+    >,
+    rapier2d_balls: Query<
+        (
+            &bevy_rapier2d::prelude::ReadMassProperties,
+            &bevy_rapier2d::prelude::Velocity,
+        ),
+        With<crate::spawner::Ball>,
+    >,
+    rapier3d_balls: Query<
+        (
+            &bevy_rapier3d::prelude::ReadMassProperties,
+            &bevy_rapier3d::prelude::Velocity,
+        ),
+        With<crate::spawner::Ball>,
+    >,
+) {
+    // (energy, mass) of one ball.
+    let kinetic = |mass: f32, speed_squared: f32| {
+        let mass = f64::from(mass);
+        (0.5 * mass * f64::from(speed_squared), mass)
+    };
+    let sum = |(energy, mass): (f64, f64), (e, m): (f64, f64)| (energy + e, mass + m);
+    let balls = match state.get() {
+        PhysicsMode::Avian2d => avian2d_balls.iter().len(),
+        PhysicsMode::Avian3d => avian3d_balls.iter().len(),
+        PhysicsMode::Rapier2d => rapier2d_balls.iter().len(),
+        PhysicsMode::Rapier3d => rapier3d_balls.iter().len(),
+    };
+    let (total, mass) = match state.get() {
+        PhysicsMode::Avian2d => avian2d_balls
+            .iter()
+            .map(|(mass, v)| kinetic(mass.value(), v.0.length_squared()))
+            .fold((0.0, 0.0), sum),
+        PhysicsMode::Avian3d => avian3d_balls
+            .iter()
+            .map(|(mass, v)| kinetic(mass.value(), v.0.length_squared()))
+            .fold((0.0, 0.0), sum),
+        PhysicsMode::Rapier2d => rapier2d_balls
+            .iter()
+            .map(|(mass, v)| kinetic(mass.mass, v.linvel.length_squared()))
+            .fold((0.0, 0.0), sum),
+        PhysicsMode::Rapier3d => rapier3d_balls
+            .iter()
+            .map(|(mass, v)| kinetic(mass.mass, v.linvel.length_squared()))
+            .fold((0.0, 0.0), sum),
+    };
+    if state.is_changed() {
+        // The previous mode's energy says nothing about this one's trend.
+        *energy = KineticEnergy::default();
+    }
+    energy.record(total as f32, mass as f32, balls, time.delta());
+}
+
+// ── Penetration depth ────────────────────────────────────────────────────────
+
+/// Deepest overlap (px) among the active mode's current contact points. At the same
+/// ball count a larger value means a softer solver, a quality difference that FPS hides.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Default)]
+pub struct PenetrationStats {
+    pub max: f32,
+}
+
+/// Avian stores `penetration` (positive when overlapping) on each manifold point;
+/// Rapier stores the signed `dist` (negative when overlapping). Speculative
+/// contacts that are still apart count as zero.
+fn update_penetration_stats(
+    state: Res<State<PhysicsMode>>,
+    mut stats: ResMut<PenetrationStats>,
+    avian2d_collisions: avian2d::prelude::Collisions,
+    avian3d_collisions: avian3d::prelude::Collisions,
+    rapier2d_sims: Query<&bevy_rapier2d::plugin::context::RapierContextSimulation>,
+    rapier3d_sims: Query<&bevy_rapier3d::plugin::context::RapierContextSimulation>,
+) {
+    let max = match state.get() {
+        PhysicsMode::Avian2d => avian2d_collisions
+            .iter()
+            .flat_map(|pair| &pair.manifolds)
+            .flat_map(|manifold| &manifold.points)
+            .map(|point| point.penetration)
+            .fold(0.0, f32::max),
+        PhysicsMode::Avian3d => avian3d_collisions
+            .iter()
+            .flat_map(|pair| &pair.manifolds)
+            .flat_map(|manifold| &manifold.points)
+            .map(|point| point.penetration)
+            .fold(0.0, f32::max),
+        PhysicsMode::Rapier2d => rapier2d_sims
+            .iter()
+            .flat_map(|sim| sim.narrow_phase.contact_pairs())
+            .flat_map(|pair| &pair.manifolds)
+            .flat_map(|manifold| &manifold.points)
+            .map(|point| -point.dist)
+            .fold(0.0, f32::max),
+        PhysicsMode::Rapier3d => rapier3d_sims
+            .iter()
+            .flat_map(|sim| sim.narrow_phase.contact_pairs())
+            .flat_map(|pair| &pair.manifolds)
+            .flat_map(|manifold| &manifold.points)
+            .map(|point| -point.dist)
+            .fold(0.0, f32::max),
+    };
+    stats.set_if_neq(PenetrationStats { max });
+}
+
+// ── Floor force ──────────────────────────────────────────────────────────────
+
+/// How hard the active mode's pile presses on the floor. Balls that rest on the
+/// floor through the pile pass their weight down to it, so a solver that lets
+/// contacts go soft reports less force for the same pile.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Default)]
+pub struct FloorForce {
+    /// Summed normal force on the floor segments.
+    pub total: f32,
+    /// Weight of every ball in the pool, falling or not, under the current gravity.
+    pub weight: f32,
+}
+
+impl FloorForce {
+    /// The force as a fraction of the weight, once there are balls.
+    pub fn share(self) -> Option<f32> {
+        (self.weight > 0.0).then(|| self.total / self.weight)
+    }
+}
+
+/// Both engines report the normal impulse each contact point applied over the last
+/// step, divided here by that step's length: Avian steps by the fixed timestep,
+/// Rapier by the `dt` in its integration parameters.
+fn update_floor_force(
+    state: Res<State<PhysicsMode>>,
+    fixed: Res<Time<Fixed>>,
+    energy: Res<KineticEnergy>,
+    gravity: Res<GravityScale>,
+    unit: Res<LengthUnit>,
+    mut force: ResMut<FloorForce>,
+    floors: Query<Entity, With<crate::walls::Floor>>,
+    avian2d_collisions: avian2d::prelude::Collisions,
+    avian3d_collisions: avian3d::prelude::Collisions,
+    rapier2d_floors: Query<
+        &bevy_rapier2d::prelude::RapierColliderHandle,
+        With<crate::walls::Floor>,
+    >,
+    rapier3d_floors: Query<
+        &bevy_rapier3d::prelude::RapierColliderHandle,
+        With<crate::walls::Floor>,
+    >,
+    rapier2d_sims: Query<&bevy_rapier2d::plugin::context::RapierContextSimulation>,
+    rapier3d_sims: Query<&bevy_rapier3d::plugin::context::RapierContextSimulation>,
+) {
+    let avian_dt = fixed.timestep().as_secs_f32();
+    let total = match state.get() {
+        PhysicsMode::Avian2d => {
+            floors
+                .iter()
+                .flat_map(|floor| avian2d_collisions.collisions_with(floor))
+                .flat_map(|pair| &pair.manifolds)
+                .flat_map(|manifold| &manifold.points)
+                .map(|point| point.normal_impulse)
+                .sum::<f32>()
+                / avian_dt
+        }
+        PhysicsMode::Avian3d => {
+            floors
+                .iter()
+                .flat_map(|floor| avian3d_collisions.collisions_with(floor))
+                .flat_map(|pair| &pair.manifolds)
+                .flat_map(|manifold| &manifold.points)
+                .map(|point| point.normal_impulse)
+                .sum::<f32>()
+                / avian_dt
+        }
+        PhysicsMode::Rapier2d => rapier2d_sims
+            .iter()
+            .map(|sim| {
+                rapier2d_floors
+                    .iter()
+                    .flat_map(|floor| sim.narrow_phase.contact_pairs_with(floor.0))
+                    .flat_map(|pair| &pair.manifolds)
+                    .flat_map(|manifold| &manifold.points)
+                    .map(|point| point.data.impulse)
+                    .sum::<f32>()
+                    / sim.integration_parameters.dt
+            })
+            .sum(),
+        PhysicsMode::Rapier3d => rapier3d_sims
+            .iter()
+            .map(|sim| {
+                rapier3d_floors
+                    .iter()
+                    .flat_map(|floor| sim.narrow_phase.contact_pairs_with(floor.0))
+                    .flat_map(|pair| &pair.manifolds)
+                    .flat_map(|manifold| &manifold.points)
+                    .map(|point| point.data.impulse)
+                    .sum::<f32>()
+                    / sim.integration_parameters.dt
+            })
+            .sum(),
+    };
+    force.set_if_neq(FloorForce {
+        total,
+        weight: energy.mass * unit.gravity() * gravity.0,
+    });
+}
+
+// ── Ball shape ───────────────────────────────────────────────────────────────
+
+/// Collider + mesh shape used for newly spawned balls. All shapes are sized from
+/// the ball radius so they occupy a similar footprint. Also stored on every ball,
+/// so a snapshot can respawn it with the same shape.
+#[derive(
+    Resource, Component, Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize,
+)]
+pub enum SpawnShape {
+    /// Circle in 2D, sphere in 3D.
+    #[default]
+    Circle,
+    /// Square / cube with side `2 * radius`.
+    Box,
+    /// Capsule with the given radius and a straight segment of `2 * radius`.
+    Capsule,
+    /// Regular polygon with this many sides and circumradius `radius`; in 3D, a prism
+    /// of it `2 * radius` deep. More vertices make contact generation dearer while
+    /// the footprint stays that of a circle, scaling per-body collision cost alone.
+    Ngon(u8),
+}
+
+impl SpawnShape {
+    pub fn label(self) -> String {
+        match self {
+            SpawnShape::Circle => "Circle".to_string(),
+            SpawnShape::Box => "Box".to_string(),
+            SpawnShape::Capsule => "Capsule".to_string(),
+            SpawnShape::Ngon(sides) => format!("Ngon({sides})"),
+        }
+    }
+
+    /// The same shape with a polygon's side count clamped to the meshes that exist,
+    /// for shapes read back from a file.
+    pub fn clamped(self) -> Self {
+        match self {
+            SpawnShape::Ngon(sides) => SpawnShape::Ngon(NgonSides::clamp(sides)),
+            shape => shape,
+        }
+    }
+
+    /// Cycle to the next shape: Circle → Box → Capsule → Ngon → Circle, the polygon
+    /// getting `sides` sides.
+    pub fn next(self, sides: NgonSides) -> Self {
+        match self {
+            SpawnShape::Circle => SpawnShape::Box,
+            SpawnShape::Box => SpawnShape::Capsule,
+            SpawnShape::Capsule => SpawnShape::Ngon(sides.0),
+            SpawnShape::Ngon(_) => SpawnShape::Circle,
+        }
+    }
 }
 
-fn set_rapier2d_gravity(mut rapier_config: Query<&mut bevy_rapier2d::plugin::RapierConfiguration>) {
-    rapier_config.single_mut().unwrap().gravity = bevy_rapier2d::math::Vect::new(0.0, -GRAVITY);
+/// Sides of the [`SpawnShape::Ngon`] balls the shape cycle selects, from
+/// `--ngon-sides`; clamped to [`NgonSides::MIN`]..=[`NgonSides::MAX`].
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NgonSides(pub u8);
+
+impl Default for NgonSides {
+    fn default() -> Self {
+        Self(6)
+    }
 }
 
-fn set_rapier3d_gravity(mut rapier_config: Query<&mut bevy_rapier3d::plugin::RapierConfiguration>) {
-    rapier_config.single_mut().unwrap().gravity =
-        bevy_rapier3d::math::Vect::new(0.0, -GRAVITY, 0.0);
+impl NgonSides {
+    pub const MIN: u8 = 3;
+    /// A mesh is built for every side count up to here, so it stays small.
+    pub const MAX: u8 = 32;
+
+    pub fn from_args() -> Self {
+        cli::parsed("--ngon-sides").map_or_else(Self::default, |sides| Self(Self::clamp(sides)))
+    }
+
+    fn clamp(sides: u8) -> u8 {
+        sides.clamp(Self::MIN, Self::MAX)
+    }
+}
+
+/// Corners of a regular polygon with circumradius `radius`, as Bevy's
+/// `RegularPolygon` mesh places them.
+fn ngon_vertices(sides: u8, radius: f32) -> Vec<Vec2> {
+    RegularPolygon::new(radius, u32::from(sides))
+        .vertices(0.0)
+        .into_iter()
+        .collect()
+}
+
+/// The polygon's corners at either end of a prism `2 * radius` deep.
+fn ngon_prism_vertices(sides: u8, radius: f32) -> Vec<Vec3> {
+    ngon_vertices(sides, radius)
+        .into_iter()
+        .flat_map(|corner| [corner.extend(-radius), corner.extend(radius)])
+        .collect()
+}
+
+fn avian2d_collider(shape: SpawnShape, radius: f32) -> avian2d::prelude::Collider {
+    use avian2d::prelude::Collider;
+    match shape {
+        SpawnShape::Circle => Collider::circle(radius),
+        SpawnShape::Box => Collider::rectangle(2.0 * radius, 2.0 * radius),
+        SpawnShape::Capsule => Collider::capsule(radius, 2.0 * radius),
+        SpawnShape::Ngon(sides) => Collider::regular_polygon(radius, u32::from(sides)),
+    }
+}
+
+fn avian3d_collider(shape: SpawnShape, radius: f32) -> avian3d::prelude::Collider {
+    use avian3d::prelude::Collider;
+    match shape {
+        SpawnShape::Circle => Collider::sphere(radius),
+        SpawnShape::Box => Collider::cuboid(2.0 * radius, 2.0 * radius, 2.0 * radius),
+        SpawnShape::Capsule => Collider::capsule(radius, 2.0 * radius),
+        SpawnShape::Ngon(sides) => Collider::convex_hull(ngon_prism_vertices(sides, radius))
+            .unwrap_or_else(|| Collider::sphere(radius)),
+    }
+}
+
+fn rapier2d_collider(shape: SpawnShape, radius: f32) -> bevy_rapier2d::prelude::Collider {
+    use bevy_rapier2d::prelude::Collider;
+    match shape {
+        SpawnShape::Circle => Collider::ball(radius),
+        SpawnShape::Box => Collider::cuboid(radius, radius),
+        SpawnShape::Capsule => Collider::capsule_y(radius, radius),
+        SpawnShape::Ngon(sides) => Collider::convex_hull(&ngon_vertices(sides, radius))
+            .unwrap_or_else(|| Collider::ball(radius)),
+    }
+}
+
+fn rapier3d_collider(shape: SpawnShape, radius: f32) -> bevy_rapier3d::prelude::Collider {
+    use bevy_rapier3d::prelude::Collider;
+    match shape {
+        SpawnShape::Circle => Collider::ball(radius),
+        SpawnShape::Box => Collider::cuboid(radius, radius, radius),
+        SpawnShape::Capsule => Collider::capsule_y(radius, radius),
+        SpawnShape::Ngon(sides) => Collider::convex_hull(&ngon_prism_vertices(sides, radius))
+            .unwrap_or_else(|| Collider::ball(radius)),
+    }
 }
 
 // ── Shared ball assets ───────────────────────────────────────────────────────
 
+/// One mesh handle per [`SpawnShape`], and per side count for [`SpawnShape::Ngon`].
+pub struct ShapeMeshes {
+    pub circle: Handle<Mesh>,
+    pub cuboid: Handle<Mesh>,
+    pub capsule: Handle<Mesh>,
+    /// Indexed by sides minus [`NgonSides::MIN`].
+    pub ngons: Vec<Handle<Mesh>>,
+}
+
+impl ShapeMeshes {
+    pub fn get(&self, shape: SpawnShape) -> &Handle<Mesh> {
+        match shape {
+            SpawnShape::Circle => &self.circle,
+            SpawnShape::Box => &self.cuboid,
+            SpawnShape::Capsule => &self.capsule,
+            SpawnShape::Ngon(sides) => {
+                &self.ngons[usize::from(NgonSides::clamp(sides) - NgonSides::MIN)]
+            }
+        }
+    }
+}
+
 /// Pre-created mesh and material handles shared by every ball entity.
 /// Holding a single set of handles lets Bevy batch/instance all ball draw calls
 /// instead of issuing one draw call per unique asset.
 #[derive(Resource)]
 pub struct BallAssets {
-    pub mesh2d: Handle<Mesh>,
+    /// Radius the shared meshes are built at; other sizes are scaled from it.
+    pub radius: f32,
+    pub meshes2d: ShapeMeshes,
     pub mat2d: Handle<ColorMaterial>,
-    pub mesh3d: Handle<Mesh>,
+    pub meshes3d: ShapeMeshes,
     pub mat3d: Handle<StandardMaterial>,
 }
 
+// ── Contact materials ────────────────────────────────────────────────────────
+
+/// Contact coefficients shared by balls and walls. Combine rules are set
+/// explicitly in every backend so both engines mix coefficients the same way:
+/// restitution is averaged, friction is multiplied.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Surface {
+    pub restitution: f32,
+    pub friction: f32,
+}
+
+/// Velocity damping coefficients for balls (per second). Avian's `LinearDamping` /
+/// `AngularDamping` and Rapier's `Damping` both scale velocity by `1 / (1 + dt * c)`
+/// each step, so equal values dissipate motion at the same rate. Zero by default.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Damping {
+    pub linear: f32,
+    pub angular: f32,
+}
+
+fn avian2d_surface(surface: Surface) -> impl Bundle {
+    use avian2d::prelude::{CoefficientCombine, Friction, Restitution};
+    (
+        Restitution::new(surface.restitution).with_combine_rule(CoefficientCombine::Average),
+        Friction::new(surface.friction).with_combine_rule(CoefficientCombine::Multiply),
+    )
+}
+
+fn avian3d_surface(surface: Surface) -> impl Bundle {
+    use avian3d::prelude::{CoefficientCombine, Friction, Restitution};
+    (
+        Restitution::new(surface.restitution).with_combine_rule(CoefficientCombine::Average),
+        Friction::new(surface.friction).with_combine_rule(CoefficientCombine::Multiply),
+    )
+}
+
+fn rapier2d_surface(surface: Surface) -> impl Bundle {
+    use bevy_rapier2d::prelude::{CoefficientCombineRule, Friction, Restitution};
+    (
+        Restitution {
+            coefficient: surface.restitution,
+            combine_rule: CoefficientCombineRule::Average,
+        },
+        Friction {
+            coefficient: surface.friction,
+            combine_rule: CoefficientCombineRule::Multiply,
+        },
+    )
+}
+
+fn rapier3d_surface(surface: Surface) -> impl Bundle {
+    use bevy_rapier3d::prelude::{CoefficientCombineRule, Friction, Restitution};
+    (
+        Restitution {
+            coefficient: surface.restitution,
+            combine_rule: CoefficientCombineRule::Average,
+        },
+        Friction {
+            coefficient: surface.friction,
+            combine_rule: CoefficientCombineRule::Multiply,
+        },
+    )
+}
+
 // ── Spawn helpers ────────────────────────────────────────────────────────────
 
+/// Per-ball settings, read from the runtime config at spawn time.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BallParams {
+    pub radius: f32,
+    pub shape: SpawnShape,
+    pub surface: Surface,
+    pub damping: Damping,
+    /// Continuous collision detection: Avian `SweptCcd`, Rapier `Ccd::enabled()`.
+    pub ccd: bool,
+    /// Whether the ball gets a mesh and material; without them it is physics only.
+    pub render: bool,
+    /// Initial angular velocity about the Z axis, in rad/s.
+    pub spin: f32,
+    /// Initial linear velocity, in px/s; `z` is ignored in 2D.
+    pub velocity: Vec3,
+    /// Collider density; each engine derives the ball's mass from it.
+    pub density: f32,
+    /// Whether the ball reports collision events, for [`NewContactRate`].
+    pub collision_events: bool,
+}
+
+/// The part of `rotation` about the Z axis: a tilt out of the plane would skew a 2D
+/// sprite and has no meaning to a 2D collider.
+fn z_rotation(rotation: Quat) -> Quat {
+    let (z, _, _) = rotation.to_euler(EulerRot::ZYX);
+    Quat::from_rotation_z(z)
+}
+
 /// Spawn a static wall with the correct backend components.
 /// `size` is full pixel extents: (width, height, depth). Depth is only used in 3D modes.
+/// `rotation` turns the wall about its centre; both engines build a static body's
+/// pose, collider included, from its `Transform`. 2D modes keep only its turn about Z.
 /// The entity is tagged [`DespawnOnExit`] so it is automatically despawned
 /// when the state transitions away from `mode`.
+/// `surface` is combined with the ball's using the rules documented on [`Surface`].
+/// Returns the wall entity so callers can tag it.
 pub fn spawn_wall(
     commands: &mut Commands,
     meshes: &mut Assets<Mesh>,
     materials: &mut Assets<StandardMaterial>,
     mode: PhysicsMode,
     position: Vec3,
+    rotation: Quat,
     size: Vec3,
     color: Color,
-) {
+    surface: Surface,
+) -> Entity {
     let (width, height, depth) = (size.x, size.y, size.z);
+    let transform = Transform::from_translation(position).with_rotation(rotation);
+    let planar = transform.with_rotation(z_rotation(rotation));
     let sprite = (
         Sprite {
             color,
             custom_size: Some(Vec2::new(width, height)),
             ..default()
         },
-        Transform::from_translation(position),
+        planar,
     );
 
     match mode {
-        PhysicsMode::Avian2d => {
-            commands.spawn((
+        PhysicsMode::Avian2d => commands
+            .spawn((
                 Name::new("Wall"),
                 DespawnOnExit(mode),
+                BodyOwner(mode),
+                crate::walls::Wall,
                 sprite,
                 avian2d::prelude::RigidBody::Static,
                 avian2d::prelude::Collider::rectangle(width, height),
-            ));
-        }
+                avian2d_surface(surface),
+            ))
+            .id(),
         PhysicsMode::Avian3d => {
             let alpha = color.to_srgba().alpha;
             let alpha_mode = if alpha < 1.0 {
@@ -170,25 +1352,33 @@ pub fn spawn_wall(
                 alpha_mode,
                 ..default()
             });
-            commands.spawn((
-                Name::new("Wall"),
-                DespawnOnExit(mode),
-                Mesh3d(mesh),
-                MeshMaterial3d(mat),
-                Transform::from_translation(position),
-                avian3d::prelude::RigidBody::Static,
-                avian3d::prelude::Collider::cuboid(width, height, depth),
-            ));
+            commands
+                .spawn((
+                    Name::new("Wall"),
+                    DespawnOnExit(mode),
+                    BodyOwner(mode),
+                    crate::walls::Wall,
+                    Mesh3d(mesh),
+                    MeshMaterial3d(mat),
+                    transform,
+                    avian3d::prelude::RigidBody::Static,
+                    avian3d::prelude::Collider::cuboid(width, height, depth),
+                    avian3d_surface(surface),
+                ))
+                .id()
         }
-        PhysicsMode::Rapier2d => {
-            commands.spawn((
+        PhysicsMode::Rapier2d => commands
+            .spawn((
                 Name::new("Wall"),
                 DespawnOnExit(mode),
+                BodyOwner(mode),
+                crate::walls::Wall,
                 sprite,
                 bevy_rapier2d::prelude::RigidBody::Fixed,
                 bevy_rapier2d::prelude::Collider::cuboid(width / 2.0, height / 2.0),
-            ));
-        }
+                rapier2d_surface(surface),
+            ))
+            .id(),
         PhysicsMode::Rapier3d => {
             let alpha = color.to_srgba().alpha;
             let alpha_mode = if alpha < 1.0 {
@@ -202,85 +1392,430 @@ pub fn spawn_wall(
                 alpha_mode,
                 ..default()
             });
-            commands.spawn((
-                Name::new("Wall"),
-                DespawnOnExit(mode),
-                Mesh3d(mesh),
-                MeshMaterial3d(mat),
-                Transform::from_translation(position),
-                bevy_rapier3d::prelude::RigidBody::Fixed,
-                bevy_rapier3d::prelude::Collider::cuboid(width / 2.0, height / 2.0, depth / 2.0),
-            ));
+            commands
+                .spawn((
+                    Name::new("Wall"),
+                    DespawnOnExit(mode),
+                    BodyOwner(mode),
+                    crate::walls::Wall,
+                    Mesh3d(mesh),
+                    MeshMaterial3d(mat),
+                    transform,
+                    bevy_rapier3d::prelude::RigidBody::Fixed,
+                    bevy_rapier3d::prelude::Collider::cuboid(
+                        width / 2.0,
+                        height / 2.0,
+                        depth / 2.0,
+                    ),
+                    rapier3d_surface(surface),
+                ))
+                .id()
         }
     }
 }
 
-/// Spawn a dynamic ball with the correct backend components.
+/// Spawn a static sensor box tagged `SensorZone`: balls pass through it, and each
+/// engine only reports which of them overlap it. Drawn as a translucent box.
+pub fn spawn_sensor_zone(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+    mode: PhysicsMode,
+    position: Vec3,
+    size: Vec3,
+    color: Color,
+) -> Entity {
+    let (width, height, depth) = (size.x, size.y, size.z);
+    let sprite = (
+        Sprite {
+            color,
+            custom_size: Some(Vec2::new(width, height)),
+            ..default()
+        },
+        Transform::from_translation(position),
+    );
+    let mut zone = commands.spawn((
+        Name::new("Sensor Zone"),
+        DespawnOnExit(mode),
+        BodyOwner(mode),
+        crate::sensor_zone::SensorZone,
+    ));
+    if matches!(mode, PhysicsMode::Avian3d | PhysicsMode::Rapier3d) {
+        zone.insert((
+            Mesh3d(meshes.add(Cuboid::new(width, height, depth))),
+            MeshMaterial3d(materials.add(StandardMaterial {
+                base_color: color,
+                alpha_mode: AlphaMode::Blend,
+                ..default()
+            })),
+            Transform::from_translation(position),
+        ));
+    } else {
+        zone.insert(sprite);
+    }
+    match mode {
+        PhysicsMode::Avian2d => zone.insert((
+            avian2d::prelude::RigidBody::Static,
+            avian2d::prelude::Collider::rectangle(width, height),
+            avian2d::prelude::Sensor,
+            avian2d::prelude::CollidingEntities::default(),
+        )),
+        PhysicsMode::Avian3d => zone.insert((
+            avian3d::prelude::RigidBody::Static,
+            avian3d::prelude::Collider::cuboid(width, height, depth),
+            avian3d::prelude::Sensor,
+            avian3d::prelude::CollidingEntities::default(),
+        )),
+        PhysicsMode::Rapier2d => zone.insert((
+            bevy_rapier2d::prelude::RigidBody::Fixed,
+            bevy_rapier2d::prelude::Collider::cuboid(width / 2.0, height / 2.0),
+            bevy_rapier2d::prelude::Sensor,
+        )),
+        PhysicsMode::Rapier3d => zone.insert((
+            bevy_rapier3d::prelude::RigidBody::Fixed,
+            bevy_rapier3d::prelude::Collider::cuboid(width / 2.0, height / 2.0, depth / 2.0),
+            bevy_rapier3d::prelude::Sensor,
+        )),
+    };
+    zone.id()
+}
+
+/// Spawn a static vertical cylinder that rounds off an inside corner of the 3D pool.
+/// `position` is the cylinder's centre; it is tagged like a wall ([`DespawnOnExit`],
+/// `Wall`). 2D modes have no depth seams, so nothing is spawned for them.
+pub fn spawn_corner_post(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+    mode: PhysicsMode,
+    position: Vec3,
+    radius: f32,
+    height: f32,
+    color: Color,
+    surface: Surface,
+) {
+    let mut post = match mode {
+        PhysicsMode::Avian2d | PhysicsMode::Rapier2d => return,
+        PhysicsMode::Avian3d => commands.spawn((
+            avian3d::prelude::RigidBody::Static,
+            avian3d::prelude::Collider::cylinder(radius, height),
+            avian3d_surface(surface),
+        )),
+        PhysicsMode::Rapier3d => commands.spawn((
+            bevy_rapier3d::prelude::RigidBody::Fixed,
+            bevy_rapier3d::prelude::Collider::cylinder(height / 2.0, radius),
+            rapier3d_surface(surface),
+        )),
+    };
+    post.insert((
+        Name::new("Corner Post"),
+        DespawnOnExit(mode),
+        BodyOwner(mode),
+        crate::walls::Wall,
+        Mesh3d(meshes.add(Cylinder::new(radius, height))),
+        MeshMaterial3d(materials.add(color)),
+        Transform::from_translation(position),
+    ));
+}
+
+/// Spawn a dynamic ball described by `params` with the correct backend components.
 /// Tagged [`DespawnOnExit`] so it is automatically despawned on state exit.
 ///
 /// `assets` holds pre-created, shared handles — all balls reference the same
-/// mesh and material assets, enabling GPU instancing/batching.
+/// mesh and material assets, enabling GPU instancing/batching. A ball whose
+/// radius differs from [`BallAssets::radius`] gets a uniform `Transform` scale
+/// instead of its own mesh; its collider is built at the mesh radius and scaled
+/// by the engines from that transform, so it always matches the visual. With
+/// [`BallParams::render`] off the ball gets neither mesh nor material.
+/// Returns the ball entity so callers can adjust it, e.g. restore a velocity.
 pub fn spawn_ball(
     commands: &mut Commands,
     mode: PhysicsMode,
     position: Vec3,
-    radius: f32,
+    params: BallParams,
     assets: &BallAssets,
-) {
-    let BallAssets {
-        mesh2d,
-        mat2d,
-        mesh3d,
-        mat3d,
-    } = assets;
-    match mode {
+) -> Entity {
+    let mesh_radius = assets.radius;
+    let BallParams {
+        radius,
+        shape,
+        surface,
+        damping,
+        ccd,
+        render,
+        spin,
+        velocity,
+        density,
+        collision_events,
+    } = params;
+    let transform =
+        Transform::from_translation(position).with_scale(Vec3::splat(radius / mesh_radius));
+    let mut ball = match mode {
         PhysicsMode::Avian2d => {
-            commands.spawn((
+            let mut ball = commands.spawn((
                 Name::new("Ball"),
                 DespawnOnExit(mode),
+                BodyOwner(mode),
                 crate::spawner::Ball,
-                Mesh2d(mesh2d.clone()),
-                MeshMaterial2d(mat2d.clone()),
-                Transform::from_translation(position),
+                shape,
+                transform,
                 avian2d::prelude::RigidBody::Dynamic,
-                avian2d::prelude::Collider::circle(radius),
+                avian2d_collider(shape, mesh_radius),
+                avian2d_surface(surface),
+                avian2d::prelude::LinearDamping(damping.linear),
+                avian2d::prelude::AngularDamping(damping.angular),
+                avian2d::prelude::LinearVelocity(velocity.truncate()),
+                avian2d::prelude::AngularVelocity(spin),
             ));
+            if ccd {
+                ball.insert(avian2d::prelude::SweptCcd::default());
+            }
+            ball
         }
         PhysicsMode::Avian3d => {
-            commands.spawn((
+            let mut ball = commands.spawn((
                 Name::new("Ball"),
                 DespawnOnExit(mode),
+                BodyOwner(mode),
                 crate::spawner::Ball,
-                Mesh3d(mesh3d.clone()),
-                MeshMaterial3d(mat3d.clone()),
-                Transform::from_translation(position),
+                shape,
+                transform,
                 avian3d::prelude::RigidBody::Dynamic,
-                avian3d::prelude::Collider::sphere(radius),
+                avian3d_collider(shape, mesh_radius),
+                avian3d_surface(surface),
+                avian3d::prelude::LinearDamping(damping.linear),
+                avian3d::prelude::AngularDamping(damping.angular),
+                avian3d::prelude::LinearVelocity(velocity),
+                avian3d::prelude::AngularVelocity(Vec3::Z * spin),
             ));
+            if ccd {
+                ball.insert(avian3d::prelude::SweptCcd::default());
+            }
+            ball
         }
         PhysicsMode::Rapier2d => {
-            commands.spawn((
+            let mut ball = commands.spawn((
                 Name::new("Ball"),
                 DespawnOnExit(mode),
+                BodyOwner(mode),
                 crate::spawner::Ball,
-                Mesh2d(mesh2d.clone()),
-                MeshMaterial2d(mat2d.clone()),
-                Transform::from_translation(position),
+                shape,
+                transform,
                 bevy_rapier2d::prelude::RigidBody::Dynamic,
-                bevy_rapier2d::prelude::Collider::ball(radius),
+                bevy_rapier2d::prelude::ReadMassProperties::default(),
+                // Written back every step so the speed heat-map can read it.
+                bevy_rapier2d::prelude::Velocity {
+                    linvel: velocity.truncate(),
+                    angvel: spin,
+                },
+                rapier2d_collider(shape, mesh_radius),
+                rapier2d_surface(surface),
+                bevy_rapier2d::prelude::Damping {
+                    linear_damping: damping.linear,
+                    angular_damping: damping.angular,
+                },
             ));
+            if ccd {
+                ball.insert(bevy_rapier2d::prelude::Ccd::enabled());
+            }
+            ball
         }
         PhysicsMode::Rapier3d => {
-            commands.spawn((
+            let mut ball = commands.spawn((
                 Name::new("Ball"),
                 DespawnOnExit(mode),
+                BodyOwner(mode),
                 crate::spawner::Ball,
-                Mesh3d(mesh3d.clone()),
-                MeshMaterial3d(mat3d.clone()),
-                Transform::from_translation(position),
+                shape,
+                transform,
                 bevy_rapier3d::prelude::RigidBody::Dynamic,
-                bevy_rapier3d::prelude::Collider::ball(radius),
+                bevy_rapier3d::prelude::ReadMassProperties::default(),
+                // Written back every step so the speed heat-map can read it.
+                bevy_rapier3d::prelude::Velocity {
+                    linvel: velocity,
+                    angvel: Vec3::Z * spin,
+                },
+                rapier3d_collider(shape, mesh_radius),
+                rapier3d_surface(surface),
+                bevy_rapier3d::prelude::Damping {
+                    linear_damping: damping.linear,
+                    angular_damping: damping.angular,
+                },
+            ));
+            if ccd {
+                ball.insert(bevy_rapier3d::prelude::Ccd::enabled());
+            }
+            ball
+        }
+    };
+    insert_ball_density(&mut ball, mode, density);
+    if collision_events {
+        insert_collision_events(&mut ball, mode);
+    }
+    if render {
+        insert_ball_visuals(&mut ball, mode, shape, assets);
+    }
+    ball.id()
+}
+
+/// Sets a ball's collider density in `mode`'s engine. Both engines recompute the
+/// body's mass and inertia when the component changes, so this also works on balls
+/// that are already simulating.
+pub fn insert_ball_density(ball: &mut EntityCommands, mode: PhysicsMode, density: f32) {
+    match mode {
+        PhysicsMode::Avian2d => {
+            ball.insert(avian2d::prelude::ColliderDensity(density));
+        }
+        PhysicsMode::Avian3d => {
+            ball.insert(avian3d::prelude::ColliderDensity(density));
+        }
+        PhysicsMode::Rapier2d => {
+            ball.insert(bevy_rapier2d::prelude::ColliderMassProperties::Density(
+                density,
+            ));
+        }
+        PhysicsMode::Rapier3d => {
+            ball.insert(bevy_rapier3d::prelude::ColliderMassProperties::Density(
+                density,
+            ));
+        }
+    }
+}
+
+/// Makes a ball report collision events in `mode`'s engine.
+fn insert_collision_events(ball: &mut EntityCommands, mode: PhysicsMode) {
+    match mode {
+        PhysicsMode::Avian2d => {
+            ball.insert(avian2d::prelude::CollisionEventsEnabled);
+        }
+        PhysicsMode::Avian3d => {
+            ball.insert(avian3d::prelude::CollisionEventsEnabled);
+        }
+        PhysicsMode::Rapier2d => {
+            ball.insert(bevy_rapier2d::prelude::ActiveEvents::COLLISION_EVENTS);
+        }
+        PhysicsMode::Rapier3d => {
+            ball.insert(bevy_rapier3d::prelude::ActiveEvents::COLLISION_EVENTS);
+        }
+    }
+}
+
+/// Sets a ball's linear velocity in `mode`'s engine, e.g. to drop a moved ball afresh.
+/// Rapier's `Velocity` also holds the angular part, which this zeroes.
+pub fn insert_ball_velocity(ball: &mut EntityCommands, mode: PhysicsMode, velocity: Vec3) {
+    match mode {
+        PhysicsMode::Avian2d => {
+            ball.insert(avian2d::prelude::LinearVelocity(velocity.truncate()));
+        }
+        PhysicsMode::Avian3d => {
+            ball.insert(avian3d::prelude::LinearVelocity(velocity));
+        }
+        PhysicsMode::Rapier2d => {
+            ball.insert(bevy_rapier2d::prelude::Velocity::linear(
+                velocity.truncate(),
             ));
         }
+        PhysicsMode::Rapier3d => {
+            ball.insert(bevy_rapier3d::prelude::Velocity::linear(velocity));
+        }
+    }
+}
+
+/// Joins two chain links `length` apart, `links` being the upper link then the lower.
+/// Avian gets a distance joint of fixed length, an entity of its own; Rapier pins the
+/// links together at the point between them (revolute in 2D, spherical in 3D), the
+/// joint sitting on the lower link. Either way it is tagged [`ChainJoint`].
+///
+/// [`ChainJoint`]: crate::chain::ChainJoint
+pub fn spawn_chain_joint(
+    commands: &mut Commands,
+    mode: PhysicsMode,
+    links: [Entity; 2],
+    length: f32,
+) {
+    let [upper, lower] = links;
+    let marker = crate::chain::ChainJoint { links };
+    let half = length / 2.0;
+    match mode {
+        PhysicsMode::Avian2d => {
+            commands.spawn((
+                Name::new("Chain Joint"),
+                marker,
+                DespawnOnExit(mode),
+                BodyOwner(mode),
+                avian2d::prelude::DistanceJoint::new(upper, lower).with_limits(length, length),
+            ));
+        }
+        PhysicsMode::Avian3d => {
+            commands.spawn((
+                Name::new("Chain Joint"),
+                marker,
+                DespawnOnExit(mode),
+                BodyOwner(mode),
+                avian3d::prelude::DistanceJoint::new(upper, lower).with_limits(length, length),
+            ));
+        }
+        PhysicsMode::Rapier2d => {
+            let joint = bevy_rapier2d::prelude::RevoluteJointBuilder::new()
+                .local_anchor1(Vec2::new(0.0, -half))
+                .local_anchor2(Vec2::new(0.0, half));
+            commands.entity(lower).insert((
+                marker,
+                bevy_rapier2d::prelude::ImpulseJoint::new(upper, joint),
+            ));
+        }
+        PhysicsMode::Rapier3d => {
+            let joint = bevy_rapier3d::prelude::SphericalJointBuilder::new()
+                .local_anchor1(Vec3::new(0.0, -half, 0.0))
+                .local_anchor2(Vec3::new(0.0, half, 0.0));
+            commands.entity(lower).insert((
+                marker,
+                bevy_rapier3d::prelude::ImpulseJoint::new(upper, joint),
+            ));
+        }
+    }
+}
+
+/// Undoes [`spawn_chain_joint`] on `joint`, the entity carrying its [`ChainJoint`].
+///
+/// [`ChainJoint`]: crate::chain::ChainJoint
+pub fn remove_chain_joint(commands: &mut Commands, mode: PhysicsMode, joint: Entity) {
+    match mode {
+        PhysicsMode::Avian2d | PhysicsMode::Avian3d => {
+            commands.entity(joint).try_despawn();
+        }
+        PhysicsMode::Rapier2d => {
+            commands.entity(joint).try_remove::<(
+                crate::chain::ChainJoint,
+                bevy_rapier2d::prelude::ImpulseJoint,
+            )>();
+        }
+        PhysicsMode::Rapier3d => {
+            commands.entity(joint).try_remove::<(
+                crate::chain::ChainJoint,
+                bevy_rapier3d::prelude::ImpulseJoint,
+            )>();
+        }
+    }
+}
+
+/// Adds the shared mesh and material for `shape` to a ball, in the 2D or 3D flavour
+/// `mode` renders with. Balls spawned with rendering off have neither.
+pub fn insert_ball_visuals(
+    ball: &mut EntityCommands,
+    mode: PhysicsMode,
+    shape: SpawnShape,
+    assets: &BallAssets,
+) {
+    if matches!(mode, PhysicsMode::Avian3d | PhysicsMode::Rapier3d) {
+        ball.insert((
+            Mesh3d(assets.meshes3d.get(shape).clone()),
+            MeshMaterial3d(assets.mat3d.clone()),
+        ));
+    } else {
+        ball.insert((
+            Mesh2d(assets.meshes2d.get(shape).clone()),
+            MeshMaterial2d(assets.mat2d.clone()),
+        ));
     }
 }
@@ -0,0 +1,383 @@
+//! Benchmark results — keeps the last [`PerfStats`] of every mode in
+//! [`BenchmarkHistory`] and appends one CSV row of milestones per mode run.
+//!
+//! The file path is read from the `AVR_CSV` environment variable (default `bench.csv`).
+//! Both are written on every `OnExit(PhysicsMode::*)`, which runs before the next
+//! mode's `OnEnter` resets the stats and ball count. The mode still running when the
+//! app exits never reaches `OnExit`, so [`AppExit`] records it the same way, prints
+//! a plain-text summary of every mode's milestones to stdout, and then flushes and
+//! closes the log.
+//!
+//! A run during which another engine ran alongside (split screen, the ghost overlay or
+//! the four windows) shared the frame time, so it is left out of both; see
+//! [`split::solo_run`].
+
+use bevy::prelude::*;
+use std::collections::HashMap;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Write};
+use std::path::Path;
+
+// Bevy 0.16 sent `AppExit` through `EventReader`; 0.17+ renamed buffered events to messages.
+#[cfg(feature = "legacy_state_scoped")]
+use bevy::prelude::EventReader as MessageReader;
+
+use crate::backend::PhysicsMode;
+use crate::spawner::BallCount;
+use crate::split::{self, PartnerRan};
+use crate::{FpsThresholds, PerfStats};
+
+/// Environment variable that overrides the CSV output path.
+const CSV_PATH_ENV: &str = "AVR_CSV";
+const DEFAULT_CSV_PATH: &str = "bench.csv";
+
+/// The CSV header for the milestones `stats` records. `--thresholds` changes the
+/// columns, so a log should only collect runs with the same thresholds.
+fn csv_header(stats: &PerfStats) -> String {
+    let mut header = "mode".to_string();
+    for (name, _) in stats.named() {
+        header += ",";
+        header += &name;
+    }
+    header + ",final_ball_count"
+}
+
+/// Open handle to the CSV benchmark log.
+/// Only inserted if the file could be opened (e.g. never on the web build).
+#[derive(Resource)]
+pub struct BenchmarkLog {
+    file: File,
+}
+
+/// Most recent milestones per mode, so the comparison survives mode switches.
+#[derive(Resource, Default)]
+pub(crate) struct BenchmarkHistory(HashMap<PhysicsMode, PerfStats>);
+
+impl BenchmarkHistory {
+    pub(crate) fn get(&self, mode: PhysicsMode) -> Option<&PerfStats> {
+        self.0.get(&mode)
+    }
+}
+
+pub fn plugin(app: &mut App) {
+    // Also registered here so the headless app, which has no FPS HUD, has stats to log.
+    let thresholds = FpsThresholds::from_args();
+    app.insert_resource(PerfStats::new(&thresholds.0));
+    app.insert_resource(thresholds);
+    app.init_resource::<BenchmarkHistory>();
+    app.add_systems(Startup, open_benchmark_log);
+    for mode in PhysicsMode::ALL {
+        app.add_systems(
+            OnExit(mode),
+            (record_history(mode), write_benchmark_row(mode)).run_if(split::solo_run),
+        );
+    }
+    app.add_systems(Last, finish_benchmark);
+}
+
+fn open_benchmark_log(mut commands: Commands, stats: Res<PerfStats>) {
+    if let Some(file) = open_csv(CSV_PATH_ENV, DEFAULT_CSV_PATH, &csv_header(&stats)) {
+        commands.insert_resource(BenchmarkLog { file });
+    }
+}
+
+/// Opens the CSV at `$path_env` (or `default_path`) like [`open_csv_at`].
+pub(crate) fn open_csv(path_env: &str, default_path: &str, header: &str) -> Option<File> {
+    let path = std::env::var(path_env).unwrap_or_else(|_| default_path.to_string());
+    open_csv_at(&path, header)
+}
+
+/// Opens the CSV at `path` in append mode and writes `header` if the file is new
+/// (empty). A file with another header, e.g. from before a column was added, is
+/// first [moved aside](move_aside) so rows of two layouts never mix. Returns `None`,
+/// with a warning, if the file cannot be opened.
+pub(crate) fn open_csv_at(path: &str, header: &str) -> Option<File> {
+    if existing_header(path).is_some_and(|existing| existing != header) && !move_aside(path) {
+        return None;
+    }
+    let mut file = match OpenOptions::new().create(true).append(true).open(path) {
+        Ok(file) => file,
+        Err(err) => {
+            warn!("CSV log disabled: could not open {path}: {err}");
+            return None;
+        }
+    };
+
+    let is_new = file.metadata().map(|m| m.len() == 0).unwrap_or(false);
+    if is_new && let Err(err) = writeln!(file, "{header}") {
+        warn!("Failed to write CSV header to {path}: {err}");
+    }
+    Some(file)
+}
+
+/// First line of the file at `path`, or `None` if it is missing, unreadable or empty.
+fn existing_header(path: &str) -> Option<String> {
+    let mut line = String::new();
+    BufReader::new(File::open(path).ok()?)
+        .read_line(&mut line)
+        .ok()?;
+    let line = line.trim_end_matches(['\r', '\n']);
+    (!line.is_empty()).then(|| line.to_string())
+}
+
+/// Renames the CSV at `path` to the first free `<path>.old`, `<path>.old.1`, …, so
+/// a log moved aside earlier is never overwritten. Returns `false`, with a warning,
+/// if it can't be moved; the log is then disabled rather than appended to.
+fn move_aside(path: &str) -> bool {
+    let mut old = format!("{path}.old");
+    let mut n = 1;
+    while Path::new(&old).exists() {
+        old = format!("{path}.old.{n}");
+        n += 1;
+    }
+    match std::fs::rename(path, &old) {
+        Ok(()) => {
+            warn!("{path} had a different CSV header; moved it to {old} and starting afresh");
+            true
+        }
+        Err(err) => {
+            warn!("CSV log disabled: could not move {path} aside: {err}");
+            false
+        }
+    }
+}
+
+/// Builds the `OnExit(mode)` history recorder; see [`write_benchmark_row`] for
+/// why the mode is captured rather than read from `State`.
+fn record_history(mode: PhysicsMode) -> impl FnMut(ResMut<BenchmarkHistory>, Res<PerfStats>) {
+    move |mut history, stats| {
+        history.0.insert(mode, stats.clone());
+    }
+}
+
+/// Builds the `OnExit(mode)` row writer. `State<PhysicsMode>` already holds the
+/// *next* mode while `OnExit` runs, so the exited mode is captured here instead.
+fn write_benchmark_row(
+    mode: PhysicsMode,
+) -> impl FnMut(Option<ResMut<BenchmarkLog>>, Res<PerfStats>, Res<BallCount>) {
+    move |log, stats, ball_count| {
+        let Some(mut log) = log else { return };
+        let row = csv_row(mode, &stats, ball_count.0);
+        if let Err(err) = writeln!(log.file, "{row}") {
+            warn!("Failed to write benchmark log row: {err}");
+        }
+    }
+}
+
+/// On [`AppExit`], from `Escape`, closing the window or the end of a headless run,
+/// records the active mode like its `OnExit` would (unless it was shared), prints the
+/// [`exit_summary`] and closes the log. `File` writes are unbuffered, so syncing is all the flushing left to do.
+fn finish_benchmark(
+    mut commands: Commands,
+    mut exits: MessageReader<AppExit>,
+    state: Res<State<PhysicsMode>>,
+    mut history: ResMut<BenchmarkHistory>,
+    stats: Res<PerfStats>,
+    ball_count: Res<BallCount>,
+    log: Option<ResMut<BenchmarkLog>>,
+    ran: Option<Res<PartnerRan>>,
+) {
+    if exits.read().count() == 0 {
+        return;
+    }
+
+    let mode = *state.get();
+    let solo = split::solo_run(ran);
+    if solo {
+        history.0.insert(mode, stats.clone());
+    }
+    println!("{}", exit_summary(&history, &stats));
+
+    let Some(mut log) = log else { return };
+    if solo && let Err(err) = writeln!(log.file, "{}", csv_row(mode, &stats, ball_count.0)) {
+        warn!("Failed to write benchmark log row: {err}");
+    }
+    if let Err(err) = log.file.sync_all() {
+        warn!("Failed to flush benchmark log: {err}");
+    }
+    // Dropping the handle closes the file, and stops a second exit writing the row again.
+    commands.remove_resource::<BenchmarkLog>();
+}
+
+/// Formats one CSV row. Milestones that were never reached become empty cells.
+fn csv_row(mode: PhysicsMode, stats: &PerfStats, final_ball_count: usize) -> String {
+    let mut row = mode.label().to_string();
+    for (_, balls) in stats.named() {
+        row += ",";
+        row += &balls.map_or_else(String::new, |n| n.to_string());
+    }
+    format!("{row},{final_ball_count}")
+}
+
+/// Side-by-side comparison of every mode's recorded milestones for the HUD: the
+/// first drop below the highest of `thresholds` and the average below the lowest.
+/// Modes that have not finished a run yet show `-`.
+pub(crate) fn history_table(history: &BenchmarkHistory, thresholds: &[f32]) -> String {
+    let cell = |opt: Option<usize>| opt.map_or_else(|| "-".to_string(), |n| n.to_string());
+    let label = |prefix: &str, threshold: Option<&f32>| {
+        threshold.map_or_else(|| "-".to_string(), |fps| format!("{prefix} <{fps}"))
+    };
+    let mut table = format!(
+        "{:<10} {:>10} {:>10}",
+        "Mode",
+        label("First", thresholds.first()),
+        label("Avg", thresholds.last())
+    );
+    for mode in PhysicsMode::ALL {
+        let stats = history.get(mode);
+        table += &format!(
+            "\n{:<10} {:>10} {:>10}",
+            mode.label(),
+            cell(stats.and_then(|s| s.first_below_highest())),
+            cell(stats.and_then(|s| s.avg_below_lowest())),
+        );
+    }
+    table
+}
+
+/// The mode with the highest value, `tie` if several share it, `-` if none has one.
+/// Every milestone is a ball count, so higher is better.
+fn winner(values: &[Option<usize>]) -> &'static str {
+    let Some(best) = values.iter().flatten().max() else {
+        return "-";
+    };
+    let mut leaders = PhysicsMode::ALL
+        .iter()
+        .zip(values)
+        .filter(|(_, value)| **value == Some(*best));
+    match (leaders.next(), leaders.next()) {
+        (Some((mode, _)), None) => mode.label(),
+        _ => "tie",
+    }
+}
+
+/// Plain-text table printed on exit, ready to paste into an issue: one row per
+/// milestone `stats` records, one column per mode, and the mode that won the row.
+/// Modes that never ran show `-`.
+fn exit_summary(history: &BenchmarkHistory, stats: &PerfStats) -> String {
+    let cell = |opt: Option<usize>| opt.map_or_else(|| "-".to_string(), |n| n.to_string());
+    let mut table = format!("{:<18}", "Metric");
+    for mode in PhysicsMode::ALL {
+        table += &format!(" {:>10}", mode.label());
+    }
+    table += "  Best";
+    for (row, (name, _)) in stats.named().into_iter().enumerate() {
+        let values = PhysicsMode::ALL.map(|mode| {
+            history
+                .get(mode)
+                .and_then(|stats| stats.named().get(row).and_then(|(_, balls)| *balls))
+        });
+        table += &format!("\n{name:<18}");
+        for value in values {
+            table += &format!(" {:>10}", cell(value));
+        }
+        table += &format!("  {}", winner(&values));
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn csv_row_without_milestones_has_empty_cells() {
+        let row = csv_row(PhysicsMode::Rapier3d, &PerfStats::new(&[50.0, 15.0]), 42);
+        assert_eq!(row, "Rapier 3D,,,,,,,42");
+    }
+
+    #[test]
+    fn csv_row_with_milestones() {
+        let mut stats = PerfStats::new(&[50.0, 15.0]);
+        stats.below[0].first = Some(1000);
+        stats.below[0].avg = Some(1200);
+        stats.below[1].avg = Some(5000);
+        stats.p99_above_33 = Some(4500);
+        stats.balls_at_target = Some(900);
+        let row = csv_row(PhysicsMode::Avian2d, &stats, 5100);
+        assert_eq!(row, "Avian 2D,1000,1200,,5000,4500,900,5100");
+    }
+
+    #[test]
+    fn history_table_lists_every_mode() {
+        let mut history = BenchmarkHistory::default();
+        let mut stats = PerfStats::new(&[50.0, 15.0]);
+        stats.below[0].first = Some(3000);
+        stats.below[1].avg = Some(9000);
+        history.0.insert(PhysicsMode::Rapier2d, stats);
+        let table = history_table(&history, &[50.0, 15.0]);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines.len(), 1 + PhysicsMode::ALL.len());
+        assert_eq!(
+            lines[3].split_whitespace().collect::<Vec<_>>(),
+            ["Rapier", "2D", "3000", "9000"]
+        );
+        assert_eq!(
+            lines[1].split_whitespace().collect::<Vec<_>>(),
+            ["Avian", "2D", "-", "-"]
+        );
+    }
+
+    #[test]
+    fn exit_summary_names_the_winner_of_each_metric() {
+        let mut history = BenchmarkHistory::default();
+        for (mode, first) in [(PhysicsMode::Avian2d, 3000), (PhysicsMode::Rapier2d, 4000)] {
+            let mut stats = PerfStats::new(&[50.0, 15.0]);
+            stats.below[0].first = Some(first);
+            stats.balls_at_target = Some(900);
+            history.0.insert(mode, stats);
+        }
+        let summary = exit_summary(&history, &PerfStats::new(&[50.0, 15.0]));
+        let row = |name: &str| {
+            summary
+                .lines()
+                .find(|line| line.starts_with(name))
+                .unwrap()
+                .split_whitespace()
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            row("first_below_50"),
+            ["first_below_50", "3000", "-", "4000", "-", "Rapier", "2D"]
+        );
+        assert_eq!(row("balls_at_target").last(), Some(&"tie"));
+        assert_eq!(row("avg_below_15").last(), Some(&"-"));
+    }
+
+    #[test]
+    fn csv_row_matches_header_column_count() {
+        let stats = PerfStats::new(&[50.0, 30.0, 15.0]);
+        let row = csv_row(PhysicsMode::Avian3d, &stats, 0);
+        assert_eq!(
+            row.split(',').count(),
+            csv_header(&stats).split(',').count()
+        );
+        assert_eq!(
+            csv_header(&PerfStats::new(&[50.0, 15.0])),
+            "mode,first_below_50,avg_below_50,first_below_15,avg_below_15,p99_above_33,balls_at_target,final_ball_count"
+        );
+    }
+
+    #[test]
+    fn move_aside_never_overwrites_an_earlier_log() {
+        let dir = std::env::temp_dir().join(format!("avr-csv-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("bench.csv");
+        let path = path.to_str().unwrap();
+
+        std::fs::write(path, "mode,first_below_50\nAvian 2D,3000\n").unwrap();
+        assert_eq!(
+            existing_header(path).as_deref(),
+            Some("mode,first_below_50")
+        );
+        assert!(move_aside(path));
+        std::fs::write(path, "mode,first_below_30\n").unwrap();
+        assert!(move_aside(path));
+
+        assert_eq!(existing_header(path), None);
+        let read = |suffix: &str| std::fs::read_to_string(format!("{path}{suffix}")).unwrap();
+        assert_eq!(read(".old"), "mode,first_below_50\nAvian 2D,3000\n");
+        assert_eq!(read(".old.1"), "mode,first_below_30\n");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,48 @@
+//! Chain joints — the [`SpawnPattern::Chain`] pattern drops balls joined into
+//! short ropes (see [`backend::spawn_chain_joint`]). This module counts the live
+//! joints of the active mode for the HUD.
+//!
+//! A drain or the clip check can remove a single link; the joints it took part in
+//! are removed with it, so the count is always of joints actually being solved.
+//!
+//! [`SpawnPattern::Chain`]: crate::spawner::SpawnPattern::Chain
+
+use bevy::prelude::*;
+
+use crate::backend::{self, BodyOwner, PhysicsMode};
+use crate::spawner::Ball;
+
+/// Tags a chain joint: the joint entity in Avian, the lower link in Rapier.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct ChainJoint {
+    /// The upper link, then the lower.
+    pub links: [Entity; 2],
+}
+
+/// Chain joints in the active mode.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct JointCount(pub usize);
+
+pub fn plugin(app: &mut App) {
+    app.init_resource::<JointCount>();
+    app.add_systems(Update, update_chain_joints);
+}
+
+/// Removes joints that lost a link, then counts the rest.
+fn update_chain_joints(
+    mut commands: Commands,
+    state: Res<State<PhysicsMode>>,
+    mut count: ResMut<JointCount>,
+    joints: Query<(Entity, &ChainJoint, &BodyOwner)>,
+    balls: Query<(), With<Ball>>,
+) {
+    let mut live = 0;
+    for (entity, joint, owner) in &joints {
+        if !joint.links.iter().all(|&link| balls.contains(link)) {
+            backend::remove_chain_joint(&mut commands, owner.0, entity);
+        } else if owner.0 == *state.get() {
+            live += 1;
+        }
+    }
+    count.set_if_neq(JointCount(live));
+}
@@ -0,0 +1,76 @@
+//! Minimal command-line argument lookup — no parser dependency.
+//!
+//! Options are looked up by name where they are consumed, e.g. `cli::value("--seed")`
+//! accepts both `--seed 42` and `--seed=42`.
+
+/// Whether the named flag (e.g. `--headless`) is present.
+pub fn flag(name: &str) -> bool {
+    std::env::args().skip(1).any(|arg| arg == name)
+}
+
+/// Value of the named option, if present.
+pub fn value(name: &str) -> Option<String> {
+    value_in(std::env::args().skip(1), name)
+}
+
+/// Value of the named option parsed as `T`. Logs a warning and returns `None`
+/// if the option is present but unparsable.
+pub fn parsed<T: std::str::FromStr>(name: &str) -> Option<T> {
+    let raw = value(name)?;
+    match raw.parse() {
+        Ok(v) => Some(v),
+        Err(_) => {
+            bevy::log::warn!("Ignoring invalid value for {name}: {raw:?}");
+            None
+        }
+    }
+}
+
+fn value_in(mut args: impl Iterator<Item = String>, name: &str) -> Option<String> {
+    while let Some(arg) = args.next() {
+        if arg == name {
+            return args.next();
+        }
+        if let Some(v) = arg
+            .strip_prefix(name)
+            .and_then(|rest| rest.strip_prefix('='))
+        {
+            return Some(v.to_string());
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> impl Iterator<Item = String> {
+        list.iter()
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>()
+            .into_iter()
+    }
+
+    #[test]
+    fn value_separate_arg() {
+        assert_eq!(
+            value_in(args(&["--seed", "42"]), "--seed").as_deref(),
+            Some("42")
+        );
+    }
+
+    #[test]
+    fn value_equals_form() {
+        assert_eq!(
+            value_in(args(&["--foo", "--seed=7"]), "--seed").as_deref(),
+            Some("7")
+        );
+    }
+
+    #[test]
+    fn value_missing_or_prefix_only() {
+        assert_eq!(value_in(args(&["--seeds", "1"]), "--seed"), None);
+        assert_eq!(value_in(args(&["--seed"]), "--seed"), None);
+    }
+}
@@ -0,0 +1,221 @@
+//! Comparison table — `F3` shows the benchmark milestones of all four modes side
+//! by side in a UI grid: one row per metric, one column per mode, with the best
+//! value in each row highlighted.
+//!
+//! The ball-count milestones come from [`BenchmarkHistory`], so a mode's column
+//! fills in once it has been run and switched away from (or the app exits). The
+//! settled FPS is the average at rest from that mode's latest `Z` report.
+
+use bevy::prelude::*;
+
+use crate::PerfStats;
+use crate::backend::PhysicsMode;
+use crate::benchmark::BenchmarkHistory;
+use crate::keys::KeyBindings;
+use crate::settle::SettledFps;
+
+/// Rows of the table, all of which are better when higher. The FPS milestones
+/// hold the index of their threshold in [`PerfStats::below`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Metric {
+    FirstBelow(usize, f32),
+    AvgBelow(usize, f32),
+    SettledFps,
+}
+
+impl Metric {
+    /// First and average drop below every threshold, then the settled FPS.
+    fn all(thresholds: &[f32]) -> Vec<Metric> {
+        let mut metrics: Vec<Metric> = thresholds
+            .iter()
+            .enumerate()
+            .flat_map(|(index, &fps)| {
+                [Metric::FirstBelow(index, fps), Metric::AvgBelow(index, fps)]
+            })
+            .collect();
+        metrics.push(Metric::SettledFps);
+        metrics
+    }
+
+    fn label(self) -> String {
+        match self {
+            Metric::FirstBelow(_, fps) => format!("First <{fps} FPS"),
+            Metric::AvgBelow(_, fps) => format!("Avg <{fps} FPS"),
+            Metric::SettledFps => "Settled FPS".to_string(),
+        }
+    }
+
+    fn value(self, stats: Option<&PerfStats>, settled: Option<f32>) -> Option<f32> {
+        let milestone = |index: usize| stats.and_then(|s| s.below.get(index));
+        let balls = match self {
+            Metric::FirstBelow(index, _) => milestone(index).and_then(|m| m.first),
+            Metric::AvgBelow(index, _) => milestone(index).and_then(|m| m.avg),
+            Metric::SettledFps => return settled,
+        };
+        balls.map(|balls| balls as f32)
+    }
+}
+
+const CELL_COLOR: Color = Color::WHITE;
+const BEST_COLOR: Color = Color::srgb(0.4, 1.0, 0.4);
+const HEADER_COLOR: Color = Color::srgb(0.6, 0.6, 0.9);
+
+#[derive(Component)]
+struct ComparisonTable;
+
+/// A value cell: the metric of its row for the mode of its column.
+#[derive(Component)]
+struct ComparisonCell {
+    metric: Metric,
+    mode: PhysicsMode,
+}
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(Startup, spawn_comparison_table);
+    app.add_systems(
+        Update,
+        (
+            toggle_comparison_table,
+            update_comparison_table
+                .run_if(resource_changed::<BenchmarkHistory>.or(resource_changed::<SettledFps>)),
+        ),
+    );
+}
+
+fn spawn_comparison_table(mut commands: Commands, stats: Res<PerfStats>) {
+    let text = |text: String, color: Color| {
+        (
+            Text::new(text),
+            TextFont {
+                font_size: 18.0,
+                ..default()
+            },
+            TextColor(color),
+        )
+    };
+    commands
+        .spawn((
+            Name::new("Comparison Row"),
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Percent(40.0),
+                width: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+        ))
+        .with_children(|row| {
+            row.spawn((
+                Name::new("Comparison Table"),
+                ComparisonTable,
+                Node {
+                    display: Display::Grid,
+                    grid_template_columns: RepeatedGridTrack::auto(
+                        1 + PhysicsMode::ALL.len() as u16,
+                    ),
+                    column_gap: Val::Px(24.0),
+                    row_gap: Val::Px(4.0),
+                    padding: UiRect::all(Val::Px(10.0)),
+                    ..default()
+                },
+                BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.75)),
+                Visibility::Hidden,
+            ))
+            .with_children(|grid| {
+                grid.spawn((Node::default(), Text::new("")));
+                for mode in PhysicsMode::ALL {
+                    grid.spawn((
+                        Node {
+                            justify_self: JustifySelf::End,
+                            ..default()
+                        },
+                        text(mode.label().to_string(), HEADER_COLOR),
+                    ));
+                }
+                for metric in Metric::all(&stats.thresholds()) {
+                    grid.spawn((Node::default(), text(metric.label(), HEADER_COLOR)));
+                    for mode in PhysicsMode::ALL {
+                        grid.spawn((
+                            ComparisonCell { metric, mode },
+                            Node {
+                                justify_self: JustifySelf::End,
+                                ..default()
+                            },
+                            text("-".to_string(), CELL_COLOR),
+                        ));
+                    }
+                }
+            });
+        });
+}
+
+/// `F3` shows or hides the comparison table.
+fn toggle_comparison_table(
+    input: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut query: Query<&mut Visibility, With<ComparisonTable>>,
+) {
+    if !input.just_pressed(bindings.comparison) {
+        return;
+    }
+
+    for mut visibility in &mut query {
+        *visibility = match *visibility {
+            Visibility::Hidden => Visibility::Inherited,
+            _ => Visibility::Hidden,
+        };
+    }
+}
+
+/// Index of the highest value, if any mode has one.
+fn best(values: &[Option<f32>]) -> Option<usize> {
+    values
+        .iter()
+        .enumerate()
+        .filter_map(|(index, value)| Some((index, (*value)?)))
+        .max_by(|(_, a), (_, b)| a.total_cmp(b))
+        .map(|(index, _)| index)
+}
+
+fn update_comparison_table(
+    history: Res<BenchmarkHistory>,
+    settled: Res<SettledFps>,
+    mut cells: Query<(&ComparisonCell, &mut Text, &mut TextColor)>,
+) {
+    let value =
+        |metric: Metric, mode: PhysicsMode| metric.value(history.get(mode), settled.get(mode));
+    for (cell, mut text, mut color) in &mut cells {
+        let values = PhysicsMode::ALL.map(|mode| value(cell.metric, mode));
+        let column = PhysicsMode::ALL.iter().position(|&mode| mode == cell.mode);
+        **text =
+            value(cell.metric, cell.mode).map_or_else(|| "-".to_string(), |v| format!("{v:.0}"));
+        color.0 = if best(&values) == column {
+            BEST_COLOR
+        } else {
+            CELL_COLOR
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn best_picks_the_highest_known_value() {
+        assert_eq!(best(&[Some(3.0), None, Some(7.0), Some(5.0)]), Some(2));
+        assert_eq!(best(&[None, None]), None);
+    }
+
+    #[test]
+    fn metric_value_reads_ball_counts_and_settled_fps() {
+        let mut stats = PerfStats::new(&[50.0, 15.0]);
+        stats.below[1].avg = Some(9000);
+        let metrics = Metric::all(&stats.thresholds());
+        assert_eq!(metrics.len(), 5);
+        assert_eq!(metrics[3].label(), "Avg <15 FPS");
+        assert_eq!(metrics[3].value(Some(&stats), None), Some(9000.0));
+        assert_eq!(metrics[0].value(Some(&stats), None), None);
+        assert_eq!(Metric::SettledFps.value(None, Some(58.5)), Some(58.5));
+    }
+}
@@ -0,0 +1,192 @@
+//! Configuration readout — `Tab` shows a panel listing every setting that shapes
+//! the simulation in the active mode: ball shape and surface, CCD, damping, density,
+//! spawn jitter, launch speed and temperature, substeps, gravity, Rapier's solver iterations, the
+//! floor thickness, the length unit and the timestep.
+//!
+//! It makes a screenshot fully reproducible, and shows at a glance when two modes
+//! being compared were run with different settings.
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+
+use crate::backend::{
+    self, GravityAngle, GravityScale, LengthUnit, PhysicsMode, RapierIterations, Substeps,
+};
+use crate::keys::KeyBindings;
+use crate::spawner::{
+    BALL_RADIUS, BallConfig, InitialSpeed, RadiusRange, SpawnJitter, Temperature,
+};
+use crate::walls::FloorThickness;
+
+#[derive(Component)]
+struct ConfigPanel;
+
+#[derive(Component)]
+struct ConfigText;
+
+/// Every setting the panel lists.
+#[derive(SystemParam)]
+struct Settings<'w> {
+    ball: BallConfig<'w>,
+    radius: Res<'w, RadiusRange>,
+    jitter: Res<'w, SpawnJitter>,
+    speed: Res<'w, InitialSpeed>,
+    temperature: Res<'w, Temperature>,
+    substeps: Res<'w, Substeps>,
+    gravity: Res<'w, GravityScale>,
+    tilt: Res<'w, GravityAngle>,
+    unit: Res<'w, LengthUnit>,
+    iterations: Res<'w, RapierIterations>,
+    floor: Res<'w, FloorThickness>,
+}
+
+impl Settings<'_> {
+    fn rows(&self, mode: PhysicsMode) -> Vec<(&'static str, String)> {
+        let params = self.ball.params(BALL_RADIUS);
+        let RadiusRange(min, max) = *self.radius;
+        let substeps = match self.substeps.0 {
+            Some(n) => n.to_string(),
+            None => format!("native ({})", Substeps::native(mode)),
+        };
+        let iterations = match mode {
+            PhysicsMode::Rapier2d | PhysicsMode::Rapier3d => format!(
+                "{} velocity / {} stabilization",
+                self.iterations.velocity, self.iterations.stabilization
+            ),
+            PhysicsMode::Avian2d | PhysicsMode::Avian3d => "n/a".to_string(),
+        };
+        let timestep = if backend::fixed_dt() {
+            format!("fixed {:.2} ms", backend::FIXED_DT * 1000.0)
+        } else {
+            "variable".to_string()
+        };
+        vec![
+            ("Mode", mode.label().to_string()),
+            ("Shape", params.shape.label()),
+            ("Radius", format!("{min:.1} to {max:.1} px")),
+            ("Restitution", format!("{:.2}", params.surface.restitution)),
+            ("Friction", format!("{:.2}", params.surface.friction)),
+            ("CCD", if params.ccd { "on" } else { "off" }.to_string()),
+            (
+                "Damping",
+                format!(
+                    "{:.1} lin / {:.1} ang",
+                    params.damping.linear, params.damping.angular
+                ),
+            ),
+            ("Density", params.density.to_string()),
+            ("Spawn jitter", format!("±{:.2} px", self.jitter.0)),
+            ("Launch speed", format!("{:.0} px/s down", self.speed.0)),
+            ("Temperature", format!("{:.0} px/s", self.temperature.0)),
+            ("Substeps", substeps),
+            ("Rapier iters", iterations),
+            (
+                "Gravity",
+                format!("{:.2}x, tilt {:+.0}°", self.gravity.0, self.tilt.0),
+            ),
+            ("Floor", format!("{:.1} px thick", self.floor.0)),
+            ("Length unit", format!("{:.1} px/m", self.unit.0)),
+            ("Timestep", timestep),
+        ]
+    }
+}
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(Startup, spawn_config_panel);
+    app.add_systems(Update, (toggle_config_panel, update_config_panel).chain());
+}
+
+fn spawn_config_panel(mut commands: Commands) {
+    commands
+        .spawn((
+            Name::new("Config Panel"),
+            ConfigPanel,
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(275.0),
+                left: Val::Px(330.0),
+                padding: UiRect::all(Val::Px(8.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+            Visibility::Hidden,
+        ))
+        .with_children(|panel| {
+            panel.spawn((
+                Name::new("Config Table"),
+                ConfigText,
+                Node::default(),
+                Text::new(""),
+                TextFont {
+                    font_size: 16.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+        });
+}
+
+/// `Tab` shows or hides the configuration panel.
+fn toggle_config_panel(
+    input: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut query: Query<&mut Visibility, With<ConfigPanel>>,
+) {
+    if !input.just_pressed(bindings.config_panel) {
+        return;
+    }
+
+    for mut visibility in &mut query {
+        *visibility = match *visibility {
+            Visibility::Hidden => Visibility::Inherited,
+            _ => Visibility::Hidden,
+        };
+    }
+}
+
+/// Rebuilds the table while the panel is shown; most of the settings change from
+/// several different input systems, so it is simpler to compare than to track them.
+fn update_config_panel(
+    settings: Settings,
+    state: Res<State<PhysicsMode>>,
+    panels: Query<&Visibility, With<ConfigPanel>>,
+    mut query: Query<&mut Text, With<ConfigText>>,
+) {
+    if panels
+        .iter()
+        .all(|visibility| *visibility == Visibility::Hidden)
+    {
+        return;
+    }
+    let table = config_table(&settings.rows(*state.get()));
+    for mut text in &mut query {
+        if **text != table {
+            **text = table.clone();
+        }
+    }
+}
+
+/// One `name  value` line per row, with the values aligned.
+fn config_table(rows: &[(&str, String)]) -> String {
+    let width = rows.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+    rows.iter()
+        .map(|(name, value)| format!("{name:<width$}  {value}"))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn config_table_aligns_values() {
+        let rows = [
+            ("Shape", "Circle".to_string()),
+            ("Restitution", "0.50".to_string()),
+        ];
+        assert_eq!(
+            config_table(&rows),
+            "Shape        Circle\nRestitution  0.50"
+        );
+    }
+}
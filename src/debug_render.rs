@@ -0,0 +1,52 @@
+//! Debug collider rendering, toggled with `R` for the active backend.
+//!
+//! The engines' debug plugins are added once at startup, switched off. Avian draws
+//! through its `PhysicsGizmos` gizmo group, so toggling flips that group's
+//! `GizmoConfig::enabled`; Rapier exposes an `enabled` flag on its
+//! `DebugRenderContext` resource.
+
+use bevy::prelude::*;
+
+use crate::backend::PhysicsMode;
+
+/// Whether collider outlines are drawn for the active backend.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DebugRender(pub bool);
+
+pub fn plugin(app: &mut App) {
+    app.add_plugins((
+        avian2d::prelude::PhysicsDebugPlugin::default(),
+        avian3d::prelude::PhysicsDebugPlugin::default(),
+        bevy_rapier2d::render::RapierDebugRenderPlugin::default().disabled(),
+        bevy_rapier3d::render::RapierDebugRenderPlugin::default().disabled(),
+    ));
+    app.init_resource::<DebugRender>();
+    app.add_systems(
+        Update,
+        apply_debug_render.run_if(resource_changed::<DebugRender>.or(state_changed::<PhysicsMode>)),
+    );
+}
+
+/// Enables exactly one engine's debug drawing: the active mode's, and only while
+/// [`DebugRender`] is on.
+fn apply_debug_render(
+    debug: Res<DebugRender>,
+    state: Res<State<PhysicsMode>>,
+    mut gizmo_config: ResMut<GizmoConfigStore>,
+    mut rapier2d_debug: ResMut<bevy_rapier2d::render::DebugRenderContext>,
+    mut rapier3d_debug: ResMut<bevy_rapier3d::render::DebugRenderContext>,
+) {
+    let mode = *state.get();
+    let enabled_for = |backend: PhysicsMode| debug.0 && mode == backend;
+
+    gizmo_config
+        .config_mut::<avian2d::prelude::PhysicsGizmos>()
+        .0
+        .enabled = enabled_for(PhysicsMode::Avian2d);
+    gizmo_config
+        .config_mut::<avian3d::prelude::PhysicsGizmos>()
+        .0
+        .enabled = enabled_for(PhysicsMode::Avian3d);
+    rapier2d_debug.enabled = enabled_for(PhysicsMode::Rapier2d);
+    rapier3d_debug.enabled = enabled_for(PhysicsMode::Rapier3d);
+}
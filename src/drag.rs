@@ -0,0 +1,194 @@
+//! Mouse drag — press the left button on a ball to pick it up, move the mouse to
+//! carry it and release to throw it, for a hands-on feel of each engine's contact
+//! response. Pressing on empty space still spawns a ball (see `spawner`).
+//!
+//! The held ball is steered by overwriting its velocity every frame with a spring
+//! towards the cursor, which also cancels gravity while it is held. On release it
+//! gets the cursor's recent velocity. In 3D the ball moves on the depth plane it
+//! was picked at.
+
+use bevy::{prelude::*, window::PrimaryWindow};
+
+use crate::backend::PhysicsMode;
+use crate::perf_graph::PerfGraphCamera;
+use crate::spawner::{self, BALL_RADIUS, Ball};
+use crate::split::SplitPartner;
+
+/// Extra distance around a ball's radius that still picks it.
+const GRAB_MARGIN: f32 = 4.0;
+
+/// Velocity per pixel of offset between the held ball and the cursor (1/s).
+const DRAG_STIFFNESS: f32 = 20.0;
+
+/// Upper bound on the speed of a held or thrown ball, so a fast flick can't
+/// tunnel it straight through a wall.
+const MAX_DRAG_SPEED: f32 = 3000.0;
+
+/// Weight of the newest frame in the smoothed cursor velocity.
+const CURSOR_SMOOTHING: f32 = 0.3;
+
+/// The ball currently held with the mouse, if any.
+#[derive(Resource, Default)]
+pub struct DragState(Option<Grab>);
+
+impl DragState {
+    pub fn is_dragging(&self) -> bool {
+        self.0.is_some()
+    }
+}
+
+struct Grab {
+    ball: Entity,
+    /// Z of the plane the ball is carried on (0 in 2D).
+    depth: f32,
+    last_cursor: Vec3,
+    cursor_velocity: Vec3,
+}
+
+pub fn plugin(app: &mut App) {
+    app.init_resource::<DragState>();
+    app.add_systems(Update, drag_ball.before(spawner::handle_click_spawn));
+}
+
+/// The ball whose centre lies closest to the camera among those within pick range
+/// of `ray`, with the distance along the ray. The 2D camera's ray runs straight down
+/// -Z, so this reduces to a distance check in the XY plane there.
+fn pick(ray: Ray3d, balls: impl Iterator<Item = (Entity, Vec3, f32)>) -> Option<(Entity, f32)> {
+    balls
+        .filter_map(|(entity, center, radius)| {
+            let along = (center - ray.origin).dot(*ray.direction);
+            let off_ray = center.distance(ray.get_point(along));
+            (along > 0.0 && off_ray <= radius + GRAB_MARGIN).then_some((entity, along))
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1))
+}
+
+/// Velocity that pulls a ball at `position` towards `target`, capped at [`MAX_DRAG_SPEED`].
+fn spring_velocity(position: Vec3, target: Vec3) -> Vec3 {
+    ((target - position) * DRAG_STIFFNESS).clamp_length_max(MAX_DRAG_SPEED)
+}
+
+fn drag_ball(
+    mouse: Res<ButtonInput<MouseButton>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform), (Without<SplitPartner>, Without<PerfGraphCamera>)>,
+    time: Res<Time<Real>>,
+    state: Res<State<PhysicsMode>>,
+    mut drag: ResMut<DragState>,
+    balls: Query<(Entity, &Transform), With<Ball>>,
+    mut avian2d_balls: Query<&mut avian2d::prelude::LinearVelocity, With<Ball>>,
+    mut avian3d_balls: Query<&mut avian3d::prelude::LinearVelocity, With<Ball>>,
+    mut rapier2d_balls: Query<&mut bevy_rapier2d::prelude::Velocity, With<Ball>>,
+    mut rapier3d_balls: Query<&mut bevy_rapier3d::prelude::Velocity, With<Ball>>,
+) {
+    let mode = *state.get();
+    let ray = windows
+        .single()
+        .ok()
+        .and_then(Window::cursor_position)
+        .zip(cameras.single().ok())
+        .and_then(|(cursor, (camera, transform))| camera.viewport_to_world(transform, cursor).ok());
+
+    if mouse.just_pressed(MouseButton::Left) {
+        let picked = ray.and_then(|ray| {
+            let candidates = balls.iter().map(|(entity, transform)| {
+                (
+                    entity,
+                    transform.translation,
+                    BALL_RADIUS * transform.scale.x,
+                )
+            });
+            pick(ray, candidates).map(|(ball, along)| (ball, ray.get_point(along)))
+        });
+        drag.0 = picked.map(|(ball, point)| Grab {
+            ball,
+            depth: balls.get(ball).map_or(0.0, |(_, t)| t.translation.z),
+            last_cursor: point,
+            cursor_velocity: Vec3::ZERO,
+        });
+    }
+    let Some(grab) = drag.0.as_mut() else {
+        return;
+    };
+    let ball = grab.ball;
+    let Ok((_, transform)) = balls.get(ball) else {
+        // Despawned while held (clipped, drained or a mode switch).
+        drag.0 = None;
+        return;
+    };
+
+    // Follow the cursor on the grab plane; keep the last point if it left the window.
+    let cursor = ray
+        .and_then(|ray| {
+            let distance =
+                ray.intersect_plane(Vec3::Z * grab.depth, InfinitePlane3d::new(Vec3::Z))?;
+            Some(ray.get_point(distance))
+        })
+        .unwrap_or(grab.last_cursor);
+    let dt = time.delta_secs();
+    if dt > 0.0 {
+        let frame_velocity = (cursor - grab.last_cursor) / dt;
+        grab.cursor_velocity = grab.cursor_velocity.lerp(frame_velocity, CURSOR_SMOOTHING);
+    }
+    grab.last_cursor = cursor;
+
+    let velocity = if mouse.pressed(MouseButton::Left) {
+        spring_velocity(transform.translation, cursor)
+    } else {
+        let fling = grab.cursor_velocity.clamp_length_max(MAX_DRAG_SPEED);
+        drag.0 = None;
+        fling
+    };
+
+    match mode {
+        PhysicsMode::Avian2d => {
+            if let Ok(mut v) = avian2d_balls.get_mut(ball) {
+                v.0 = velocity.truncate();
+            }
+        }
+        PhysicsMode::Avian3d => {
+            if let Ok(mut v) = avian3d_balls.get_mut(ball) {
+                v.0 = velocity;
+            }
+        }
+        PhysicsMode::Rapier2d => {
+            if let Ok(mut v) = rapier2d_balls.get_mut(ball) {
+                v.linvel = velocity.truncate();
+            }
+        }
+        PhysicsMode::Rapier3d => {
+            if let Ok(mut v) = rapier3d_balls.get_mut(ball) {
+                v.linvel = velocity;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pick_prefers_the_ball_nearest_the_camera() {
+        let ray = Ray3d::new(Vec3::new(0.0, 0.0, 100.0), Dir3::NEG_Z);
+        let mut world = World::new();
+        let [near, far, aside] = [(); 3].map(|()| world.spawn_empty().id());
+        let balls = [
+            (far, Vec3::new(0.0, 0.0, -50.0), 6.0),
+            (near, Vec3::new(5.0, 0.0, 50.0), 6.0),
+            (aside, Vec3::new(20.0, 0.0, 90.0), 6.0),
+        ];
+        assert_eq!(pick(ray, balls.into_iter()), Some((near, 50.0)));
+        assert_eq!(pick(ray, balls[2..].iter().copied()), None);
+    }
+
+    #[test]
+    fn spring_velocity_is_capped() {
+        assert_eq!(
+            spring_velocity(Vec3::ZERO, Vec3::new(10.0, 0.0, 0.0)),
+            Vec3::new(10.0 * DRAG_STIFFNESS, 0.0, 0.0)
+        );
+        let far = spring_velocity(Vec3::ZERO, Vec3::new(1.0e6, 0.0, 0.0));
+        assert!((far.length() - MAX_DRAG_SPEED).abs() < 1e-3);
+    }
+}
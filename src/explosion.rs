@@ -0,0 +1,134 @@
+//! Right-click explosion — pushes every ball near the cursor radially outward, to
+//! compare how an impulse propagates through a settled pile in each engine.
+//!
+//! The push is defined as a velocity change so all four backends react identically:
+//! Rapier receives it as an `ExternalImpulse` scaled by each ball's mass, while Avian
+//! balls get the velocity change directly, since Avian's impulse API differs between
+//! the supported releases (`ExternalImpulse` in 0.3, the `Forces` helper from 0.4).
+
+use bevy::{prelude::*, window::PrimaryWindow};
+
+use crate::backend::PhysicsMode;
+use crate::perf_graph::PerfGraphCamera;
+use crate::spawner::{self, Ball};
+use crate::split::SplitPartner;
+
+/// Balls farther than this from the cursor are unaffected.
+const EXPLOSION_RADIUS: f32 = 200.0;
+
+/// Outward velocity change for a ball at the centre; falls off linearly to zero
+/// at [`EXPLOSION_RADIUS`].
+const EXPLOSION_SPEED: f32 = 600.0;
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(Update, handle_explosion);
+}
+
+/// Velocity change for a ball at `position` from an explosion at `center`,
+/// or `None` if it is out of range.
+fn explosion_delta_v(center: Vec3, position: Vec3) -> Option<Vec3> {
+    let offset = position - center;
+    let distance = offset.length();
+    if distance >= EXPLOSION_RADIUS {
+        return None;
+    }
+    // A ball exactly at the centre has no outward direction; push it straight up.
+    let direction = offset.try_normalize().unwrap_or(Vec3::Y);
+    Some(direction * EXPLOSION_SPEED * (1.0 - distance / EXPLOSION_RADIUS))
+}
+
+/// On right click, kicks every ball within [`EXPLOSION_RADIUS`] of the cursor away
+/// from it. In 2D the kick lies in the XY plane; in 3D it also has a Z component
+/// relative to the pool's mid-depth plane under the cursor.
+fn handle_explosion(
+    mut commands: Commands,
+    mouse: Res<ButtonInput<MouseButton>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform), (Without<SplitPartner>, Without<PerfGraphCamera>)>,
+    state: Res<State<PhysicsMode>>,
+    mut avian2d_balls: Query<(&Transform, &mut avian2d::prelude::LinearVelocity), With<Ball>>,
+    mut avian3d_balls: Query<(&Transform, &mut avian3d::prelude::LinearVelocity), With<Ball>>,
+    rapier2d_balls: Query<
+        (
+            Entity,
+            &Transform,
+            &bevy_rapier2d::prelude::ReadMassProperties,
+        ),
+        With<Ball>,
+    >,
+    rapier3d_balls: Query<
+        (
+            Entity,
+            &Transform,
+            &bevy_rapier3d::prelude::ReadMassProperties,
+        ),
+        With<Ball>,
+    >,
+) {
+    if !mouse.just_pressed(MouseButton::Right) {
+        return;
+    }
+    let mode = *state.get();
+    let Some(center) = spawner::cursor_world_position(mode, &windows, &cameras) else {
+        return;
+    };
+
+    match mode {
+        PhysicsMode::Avian2d => {
+            for (transform, mut velocity) in &mut avian2d_balls {
+                if let Some(delta_v) = explosion_delta_v(center, transform.translation) {
+                    velocity.0 += delta_v.truncate();
+                }
+            }
+        }
+        PhysicsMode::Avian3d => {
+            for (transform, mut velocity) in &mut avian3d_balls {
+                if let Some(delta_v) = explosion_delta_v(center, transform.translation) {
+                    velocity.0 += delta_v;
+                }
+            }
+        }
+        PhysicsMode::Rapier2d => {
+            for (entity, transform, mass) in &rapier2d_balls {
+                if let Some(delta_v) = explosion_delta_v(center, transform.translation) {
+                    commands
+                        .entity(entity)
+                        .insert(bevy_rapier2d::prelude::ExternalImpulse {
+                            impulse: delta_v.truncate() * mass.mass,
+                            ..default()
+                        });
+                }
+            }
+        }
+        PhysicsMode::Rapier3d => {
+            for (entity, transform, mass) in &rapier3d_balls {
+                if let Some(delta_v) = explosion_delta_v(center, transform.translation) {
+                    commands
+                        .entity(entity)
+                        .insert(bevy_rapier3d::prelude::ExternalImpulse {
+                            impulse: delta_v * mass.mass,
+                            ..default()
+                        });
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn explosion_falls_off_with_distance() {
+        let near = explosion_delta_v(Vec3::ZERO, Vec3::new(50.0, 0.0, 0.0)).unwrap();
+        let far = explosion_delta_v(Vec3::ZERO, Vec3::new(-150.0, 0.0, 0.0)).unwrap();
+
+        assert!(near.x > 0.0 && far.x < 0.0);
+        assert!(near.length() > far.length());
+        assert_eq!(
+            explosion_delta_v(Vec3::ZERO, Vec3::new(0.0, EXPLOSION_RADIUS, 0.0)),
+            None
+        );
+    }
+}
@@ -0,0 +1,225 @@
+//! Low-FPS guard — once the FPS, measured over [`WINDOW`]s of real time, has stayed
+//! below `--fps-floor` (default 5) for [`GRACE_PERIOD`], the simulation is paused and
+//! the spawner frozen, with an "AUTO-PAUSED (low FPS)" banner.
+//!
+//! Overshooting the spawn rate otherwise sinks the app to a frame every few seconds,
+//! where even a mode switch takes ages to register. Paused, frames are cheap again and
+//! every key works; `Space` resumes the pile as it is and lifts the freeze, a mode
+//! switch starts afresh. `--fps-floor 0` disables the guard.
+//!
+//! The FPS diagnostic's average covers the last 20 or so frames, which at a frame every
+//! few seconds is a minute of history, so the guard counts its own frames instead.
+
+use bevy::prelude::*;
+use std::time::Duration;
+
+use crate::backend::PhysicsMode;
+use crate::cli;
+use crate::spawner::SpawnFrozen;
+
+/// Default `--fps-floor`.
+const DEFAULT_FLOOR: f32 = 5.0;
+
+/// Real time the FPS is measured over.
+const WINDOW: Duration = Duration::from_secs(1);
+
+/// How long the FPS must stay below the floor before the guard trips.
+const GRACE_PERIOD: Duration = Duration::from_secs(2);
+
+/// Watches the FPS against the floor.
+#[derive(Resource, Debug)]
+struct FpsGuard {
+    floor: f32,
+    /// Frames counted and real time spent in the current window.
+    frames: u32,
+    window: Duration,
+    /// Real time the FPS has been below the floor, in whole windows.
+    low: Timer,
+    /// Set when the guard paused the simulation, until it is resumed.
+    tripped: bool,
+}
+
+impl FpsGuard {
+    fn new(floor: f32) -> Self {
+        Self {
+            floor,
+            frames: 0,
+            window: Duration::ZERO,
+            low: Timer::new(GRACE_PERIOD, TimerMode::Once),
+            tripped: false,
+        }
+    }
+
+    /// Forgets the current window and any low stretch.
+    fn restart(&mut self) {
+        self.frames = 0;
+        self.window = Duration::ZERO;
+        self.low.reset();
+    }
+
+    /// Feeds one frame's real-time `delta`; returns `true` on the frame the guard
+    /// trips. The FPS is judged once per [`WINDOW`], or per frame when a single frame
+    /// takes longer than that.
+    fn observe(&mut self, delta: Duration) -> bool {
+        if self.floor <= 0.0 {
+            return false;
+        }
+        self.frames += 1;
+        self.window += delta;
+        if self.window < WINDOW {
+            return false;
+        }
+        let fps = f64::from(self.frames) / self.window.as_secs_f64();
+        let window = std::mem::take(&mut self.window);
+        self.frames = 0;
+        if fps >= f64::from(self.floor) {
+            self.low.reset();
+            return false;
+        }
+        self.low.tick(window);
+        if self.low.elapsed() < self.low.duration() {
+            return false;
+        }
+        self.low.reset();
+        self.tripped = true;
+        true
+    }
+}
+
+#[derive(Component)]
+struct GuardBanner;
+
+pub fn plugin(app: &mut App) {
+    app.insert_resource(FpsGuard::new(
+        cli::parsed("--fps-floor").unwrap_or(DEFAULT_FLOOR),
+    ));
+    app.add_systems(Startup, spawn_guard_banner);
+    app.add_systems(Update, (watch_fps, update_guard_banner).chain());
+    for mode in PhysicsMode::ALL {
+        app.add_systems(OnEnter(mode), reset_guard);
+    }
+}
+
+/// A fresh mode starts with an empty pool, so any earlier low stretch no longer counts.
+fn reset_guard(mut guard: ResMut<FpsGuard>) {
+    guard.restart();
+    guard.tripped = false;
+}
+
+fn spawn_guard_banner(mut commands: Commands) {
+    commands
+        .spawn((
+            Name::new("FPS Guard Banner"),
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Percent(20.0),
+                width: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+        ))
+        .with_children(|row| {
+            row.spawn((
+                GuardBanner,
+                Text::new("AUTO-PAUSED (low FPS): spawning stopped, Space resumes"),
+                TextFont {
+                    font_size: 28.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(1.0, 0.3, 0.3)),
+                BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+                Visibility::Hidden,
+            ));
+        });
+}
+
+/// Judged on real time, since virtual time may be slowed down with `-`. While the
+/// simulation is paused there is nothing to guard against; once `Space` resumes it
+/// after a trip, spawning resumes too.
+fn watch_fps(
+    real: Res<Time<Real>>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+    mut guard: ResMut<FpsGuard>,
+    mut frozen: ResMut<SpawnFrozen>,
+) {
+    if virtual_time.is_paused() {
+        guard.restart();
+        return;
+    }
+    if guard.tripped {
+        guard.tripped = false;
+        frozen.0 = false;
+    }
+
+    if guard.observe(real.delta()) {
+        warn!(
+            "FPS below {} for {} s: pausing the simulation",
+            guard.floor,
+            GRACE_PERIOD.as_secs()
+        );
+        virtual_time.pause();
+        frozen.0 = true;
+    }
+}
+
+fn update_guard_banner(guard: Res<FpsGuard>, mut query: Query<&mut Visibility, With<GuardBanner>>) {
+    for mut visibility in &mut query {
+        visibility.set_if_neq(if guard.tripped {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trips_only_after_the_grace_period_below_the_floor() {
+        // 2 FPS, judged once per second.
+        let slow = Duration::from_millis(500);
+        let mut guard = FpsGuard::new(5.0);
+        assert!(!guard.observe(slow) && !guard.observe(slow));
+        // A recovered window restarts the count.
+        for _ in 0..100 {
+            assert!(!guard.observe(Duration::from_millis(10)));
+        }
+        for _ in 0..3 {
+            assert!(!guard.observe(slow));
+        }
+        assert!(guard.observe(slow));
+        assert!(guard.tripped);
+
+        // A frame longer than the window is judged on its own.
+        let mut stalled = FpsGuard::new(5.0);
+        assert!(!stalled.observe(Duration::from_secs(1)));
+        assert!(stalled.observe(Duration::from_secs(1)));
+
+        let mut off = FpsGuard::new(0.0);
+        assert!(!off.observe(Duration::from_secs(3)));
+        assert!(!off.observe(Duration::from_secs(3)));
+    }
+
+    #[test]
+    fn resuming_after_a_trip_lifts_the_freeze() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        let mut guard = FpsGuard::new(5.0);
+        guard.tripped = true;
+        app.insert_resource(guard)
+            .insert_resource(SpawnFrozen(true))
+            .add_systems(Update, watch_fps);
+
+        app.world_mut().resource_mut::<Time<Virtual>>().pause();
+        app.update();
+        assert!(app.world().resource::<SpawnFrozen>().0);
+
+        // `Space` unpauses virtual time.
+        app.world_mut().resource_mut::<Time<Virtual>>().unpause();
+        app.update();
+        assert!(!app.world().resource::<SpawnFrozen>().0);
+        assert!(!app.world().resource::<FpsGuard>().tripped);
+    }
+}
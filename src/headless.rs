@@ -0,0 +1,201 @@
+//! Headless benchmark — `--headless` runs every mode without a window and prints
+//! the ball count each backend reached before frames became too slow.
+//!
+//! For each [`PhysicsMode`] in cycle order, balls are spawned at a fixed rate until
+//! the rolling average frame time exceeds a budget (`--frame-budget-ms`, default 33.3).
+//! The ball count at that moment is recorded and the next mode is entered via
+//! [`NextState`]. After the last mode a results table is printed and the app exits.
+//!
+//! Frame timing comes from `Time<Real>` deltas because `FrameTimeDiagnosticsPlugin`
+//! depends on rendering. The app still uses `DefaultPlugins` (asset types, states,
+//! transforms) but with no window, no winit event loop and no GPU backend.
+
+use bevy::{
+    app::ScheduleRunnerPlugin,
+    prelude::*,
+    render::{RenderPlugin, settings::WgpuSettings},
+    window::ExitCondition,
+    winit::WinitPlugin,
+};
+use std::collections::VecDeque;
+use std::time::Duration;
+
+// Bevy 0.16 sent `AppExit` through `EventWriter`; 0.17+ renamed buffered events to messages.
+#[cfg(feature = "legacy_state_scoped")]
+use bevy::prelude::EventWriter as MessageWriter;
+
+use crate::backend::{self, PhysicsMode};
+use crate::obstacles::{self, ObstacleLayout};
+use crate::spawner::{self, BallConfig, BallCount, MaxBalls, SpawnRate};
+use crate::walls::{self, FloorThickness, MonoWalls, PoolConfig, RoundedWalls};
+use crate::{benchmark, cli, keys};
+
+/// Default frame-time budget (ms); exceeding it on average ends a mode's run.
+const DEFAULT_FRAME_BUDGET_MS: f32 = 1000.0 / 30.0;
+
+/// Number of recent frames averaged against the budget.
+const FRAME_WINDOW: usize = 60;
+
+/// Real-time delay after entering a mode before frame times are judged,
+/// so the transition frame doesn't end the run immediately.
+const WARMUP: Duration = Duration::from_millis(1000);
+
+/// Spawn rate in balls per second — higher than the interactive default so runs finish quickly.
+const SPAWN_RATE: f32 = 200.0;
+
+/// Per-mode measurement state and the results collected so far.
+#[derive(Resource)]
+struct HeadlessBench {
+    frame_budget: Duration,
+    frame_times: VecDeque<Duration>,
+    warmup: Timer,
+    results: Vec<HeadlessResult>,
+}
+
+struct HeadlessResult {
+    mode: PhysicsMode,
+    balls: usize,
+    capped: bool,
+}
+
+/// Builds the complete headless app.
+pub fn app() -> App {
+    let mut app = App::new();
+    app.add_plugins(
+        DefaultPlugins
+            .set(WindowPlugin {
+                primary_window: None,
+                exit_condition: ExitCondition::DontExit,
+                ..default()
+            })
+            .set(RenderPlugin {
+                render_creation: WgpuSettings {
+                    backends: None,
+                    ..default()
+                }
+                .into(),
+                ..default()
+            })
+            .disable::<WinitPlugin>(),
+    )
+    .add_plugins(ScheduleRunnerPlugin::run_loop(Duration::ZERO))
+    .add_plugins(keys::plugin)
+    .add_plugins(backend::plugin)
+    .add_plugins(spawner::plugin)
+    .add_plugins(obstacles::plugin)
+    .add_plugins(benchmark::plugin)
+    .add_plugins(plugin);
+    app
+}
+
+fn plugin(app: &mut App) {
+    let budget_ms = cli::parsed("--frame-budget-ms").unwrap_or(DEFAULT_FRAME_BUDGET_MS);
+    app.insert_resource(HeadlessBench {
+        frame_budget: Duration::from_secs_f32(budget_ms / 1000.0),
+        frame_times: VecDeque::with_capacity(FRAME_WINDOW),
+        warmup: Timer::new(WARMUP, TimerMode::Once),
+        results: Vec::new(),
+    });
+    app.insert_resource(SpawnRate(SPAWN_RATE));
+    app.insert_resource(RoundedWalls::from_args());
+    app.insert_resource(MonoWalls::from_args());
+    for mode in PhysicsMode::ALL {
+        app.add_systems(OnEnter(mode), enter_mode);
+    }
+    app.add_systems(Update, measure_frame_time);
+}
+
+fn enter_mode(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    state: Res<State<PhysicsMode>>,
+    ball_config: BallConfig,
+    pool: Res<PoolConfig>,
+    (rounded_walls, mono_walls): (Res<RoundedWalls>, Res<MonoWalls>),
+    obstacles: Res<ObstacleLayout>,
+    floor_thickness: Res<FloorThickness>,
+    mut ball_count: ResMut<BallCount>,
+    mut bench: ResMut<HeadlessBench>,
+) {
+    walls::spawn_walls(
+        &mut commands,
+        &mut meshes,
+        &mut materials,
+        *pool,
+        *state.get(),
+        ball_config.surface(),
+        false,
+        *floor_thickness,
+        false,
+        rounded_walls.0,
+        *mono_walls,
+        &obstacles,
+    );
+    ball_count.0 = 0;
+    bench.frame_times.clear();
+    bench.warmup.reset();
+    info!("Headless benchmark: running {}", state.get().label());
+}
+
+/// Tracks the rolling frame time and ends the current mode's run once it exceeds
+/// the budget (or the ball cap is reached).
+fn measure_frame_time(
+    time: Res<Time<Real>>,
+    state: Res<State<PhysicsMode>>,
+    ball_count: Res<BallCount>,
+    max_balls: Res<MaxBalls>,
+    mut bench: ResMut<HeadlessBench>,
+    mut next_state: ResMut<NextState<PhysicsMode>>,
+    mut exit: MessageWriter<AppExit>,
+) {
+    bench.warmup.tick(time.delta());
+    if bench.warmup.elapsed() < bench.warmup.duration() {
+        return;
+    }
+
+    if bench.frame_times.len() == FRAME_WINDOW {
+        bench.frame_times.pop_front();
+    }
+    bench.frame_times.push_back(time.delta());
+
+    let capped = max_balls.reached(ball_count.0);
+    let over_budget =
+        bench.frame_times.len() == FRAME_WINDOW && average(&bench.frame_times) > bench.frame_budget;
+    if !over_budget && !capped {
+        return;
+    }
+
+    let mode = *state.get();
+    bench.results.push(HeadlessResult {
+        mode,
+        balls: ball_count.0,
+        capped,
+    });
+
+    let next = mode.next();
+    if bench.results.iter().any(|r| r.mode == next) {
+        println!("{}", results_table(&bench));
+        exit.write(AppExit::Success);
+    } else {
+        next_state.set(next);
+    }
+}
+
+fn average(frame_times: &VecDeque<Duration>) -> Duration {
+    frame_times.iter().sum::<Duration>() / frame_times.len() as u32
+}
+
+fn results_table(bench: &HeadlessBench) -> String {
+    let mut table = format!(
+        "\nBalls when the {FRAME_WINDOW}-frame average exceeded {:.1} ms\n\n{:<12} {:>10}\n",
+        bench.frame_budget.as_secs_f32() * 1000.0,
+        "Mode",
+        "Balls",
+    );
+    for result in &bench.results {
+        let cap = if result.capped { " (cap)" } else { "" };
+        table += &format!("{:<12} {:>10}{cap}\n", result.mode.label(), result.balls);
+    }
+    table
+}
@@ -0,0 +1,135 @@
+//! Ball heatmap — `D` overlays the pool with a coarse grid of gizmo rectangles,
+//! each as opaque as the share of balls in its cell, to show where balls
+//! accumulate (e.g. the uneven piling under a single spawn stream).
+//!
+//! Balls are binned by their XY position every frame, so in 3D the grid shows the
+//! pile projected onto the back wall.
+
+use bevy::prelude::*;
+
+use crate::backend::PhysicsMode;
+use crate::keys::KeyBindings;
+use crate::spawner::Ball;
+use crate::split::SplitPartner;
+use crate::walls::PoolConfig;
+
+/// Cell edge length in pixels: 48 × 27 cells over the default pool. Scaled with the
+/// pool for `--length-unit`.
+const CELL_SIZE: f32 = 40.0;
+
+const HEAT_COLOR: Color = Color::srgb(1.0, 0.3, 0.1);
+
+/// Whether the heatmap is drawn. Toggled with `D`.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct DensityHeatmap(pub bool);
+
+pub fn plugin(app: &mut App) {
+    app.init_resource::<DensityHeatmap>();
+    app.add_systems(
+        Update,
+        (
+            toggle_heatmap,
+            draw_heatmap.run_if(|heatmap: Res<DensityHeatmap>| heatmap.0),
+        )
+            .chain(),
+    );
+}
+
+/// `D` shows or hides the heatmap. `Ctrl + D` pans the locked camera instead.
+fn toggle_heatmap(
+    input: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut heatmap: ResMut<DensityHeatmap>,
+) {
+    if input.just_pressed(bindings.heatmap)
+        && !input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight])
+    {
+        heatmap.0 = !heatmap.0;
+    }
+}
+
+/// Bottom-left corner of the pool, where the grid starts.
+fn grid_origin(pool: PoolConfig) -> Vec2 {
+    Vec2::new(-pool.width / 2.0, -pool.height / 2.0)
+}
+
+/// Ball counts per cell, row by row from the bottom-left corner of the pool.
+/// Balls outside the pool are left out.
+fn bin_positions(pool: PoolConfig, positions: impl Iterator<Item = Vec2>) -> (UVec2, Vec<u32>) {
+    let cell_size = CELL_SIZE * pool.scale;
+    let grid = UVec2::new(
+        (pool.width / cell_size).ceil() as u32,
+        (pool.height / cell_size).ceil() as u32,
+    );
+    let origin = grid_origin(pool);
+    let mut counts = vec![0; (grid.x * grid.y) as usize];
+    for position in positions {
+        let cell = ((position - origin) / cell_size).floor();
+        if cell.x < 0.0 || cell.y < 0.0 {
+            continue;
+        }
+        let cell = cell.as_uvec2();
+        if cell.x < grid.x && cell.y < grid.y {
+            counts[(cell.y * grid.x + cell.x) as usize] += 1;
+        }
+    }
+    (grid, counts)
+}
+
+/// Draws one rectangle per occupied cell, its alpha the cell's count relative to
+/// the fullest cell.
+fn draw_heatmap(
+    state: Res<State<PhysicsMode>>,
+    pool: Res<PoolConfig>,
+    mut gizmos: Gizmos,
+    balls: Query<&Transform, (With<Ball>, Without<SplitPartner>)>,
+) {
+    let (grid, counts) = bin_positions(*pool, balls.iter().map(|t| t.translation.truncate()));
+    let Some(&max) = counts.iter().max().filter(|&&max| max > 0) else {
+        return;
+    };
+
+    let is_3d = matches!(state.get(), PhysicsMode::Avian3d | PhysicsMode::Rapier3d);
+    let origin = grid_origin(*pool);
+    let cell_size = CELL_SIZE * pool.scale;
+    let size = Vec2::splat(cell_size);
+    for (index, &count) in counts.iter().enumerate() {
+        if count == 0 {
+            continue;
+        }
+        let cell = UVec2::new(index as u32 % grid.x, index as u32 / grid.x);
+        let center = origin + (cell.as_vec2() + 0.5) * cell_size;
+        let color = HEAT_COLOR.with_alpha(count as f32 / max as f32);
+        if is_3d {
+            gizmos.rect(center.extend(0.0), size, color);
+        } else {
+            gizmos.rect_2d(center, size, color);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bin_positions_counts_per_cell_and_skips_outside() {
+        let pool = PoolConfig::default();
+        let corner = grid_origin(pool);
+        let (grid, counts) = bin_positions(
+            pool,
+            [
+                corner + Vec2::splat(1.0),
+                corner + Vec2::splat(CELL_SIZE - 1.0),
+                corner + Vec2::new(CELL_SIZE + 1.0, 1.0),
+                corner - Vec2::splat(1.0),
+                Vec2::new(0.0, pool.height),
+            ]
+            .into_iter(),
+        );
+        assert_eq!(grid, UVec2::new(48, 27));
+        assert_eq!(counts[0], 2);
+        assert_eq!(counts[1], 1);
+        assert_eq!(counts.iter().sum::<u32>(), 3);
+    }
+}
@@ -0,0 +1,168 @@
+//! Transform interpolation — `F4` turns Avian's transform interpolation on or off
+//! for every Avian ball. Avian steps at a fixed rate, so without it balls move in
+//! visible jumps whenever the frame rate drifts from the tick rate; with it, each
+//! ball's transform is eased between its last two physics states every frame, which
+//! costs extra work per ball.
+//!
+//! The HUD shows the state next to the mean FPS seen with interpolation on and off
+//! in the active mode, so the cost reads directly. Toggle at a steady ball count
+//! (paused spawning or a cap) for a fair comparison; both means reset on a mode
+//! switch. Rapier steps once per frame by default and has nothing to interpolate.
+
+use avian2d::interpolation::TransformInterpolation;
+use bevy::{
+    diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin},
+    prelude::*,
+};
+
+use crate::backend::{BodyOwner, PhysicsMode};
+use crate::keys::KeyBindings;
+use crate::spawner::Ball;
+
+/// Whether Avian balls are interpolated. Off by default, like Avian itself.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct AvianInterpolation(pub bool);
+
+/// Mean FPS of the active mode with interpolation off (`[0]`) and on (`[1]`).
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Default)]
+pub struct InterpolationFps([(f64, u32); 2]);
+
+impl InterpolationFps {
+    fn record(&mut self, on: bool, fps: f64) {
+        let (sum, frames) = &mut self.0[on as usize];
+        *sum += fps;
+        *frames += 1;
+    }
+
+    pub fn mean(&self, on: bool) -> Option<f32> {
+        let (sum, frames) = self.0[on as usize];
+        (frames > 0).then(|| (sum / frames as f64) as f32)
+    }
+}
+
+pub fn plugin(app: &mut App) {
+    app.init_resource::<AvianInterpolation>();
+    app.init_resource::<InterpolationFps>();
+    app.add_systems(
+        Update,
+        (
+            toggle_interpolation,
+            apply_interpolation.run_if(resource_changed::<AvianInterpolation>),
+            interpolate_new_balls,
+            measure_interpolation_fps,
+        )
+            .chain(),
+    );
+}
+
+fn is_avian(mode: PhysicsMode) -> bool {
+    matches!(mode, PhysicsMode::Avian2d | PhysicsMode::Avian3d)
+}
+
+/// `F4` switches interpolation on or off.
+fn toggle_interpolation(
+    input: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut interpolation: ResMut<AvianInterpolation>,
+) {
+    if input.just_pressed(bindings.interpolation) {
+        interpolation.0 = !interpolation.0;
+    }
+}
+
+/// Adds or removes interpolation on every existing Avian ball.
+fn apply_interpolation(
+    mut commands: Commands,
+    interpolation: Res<AvianInterpolation>,
+    balls: Query<(Entity, &BodyOwner), With<Ball>>,
+) {
+    for (entity, owner) in &balls {
+        if !is_avian(owner.0) {
+            continue;
+        }
+        let mut ball = commands.entity(entity);
+        if interpolation.0 {
+            ball.insert(TransformInterpolation);
+        } else {
+            // The marker requires the per-channel interpolation components; they go too.
+            ball.remove_with_requires::<TransformInterpolation>();
+        }
+    }
+}
+
+fn interpolate_new_balls(
+    mut commands: Commands,
+    interpolation: Res<AvianInterpolation>,
+    balls: Query<(Entity, &BodyOwner), Added<Ball>>,
+) {
+    if !interpolation.0 {
+        return;
+    }
+    for (entity, owner) in &balls {
+        if is_avian(owner.0) {
+            commands.entity(entity).insert(TransformInterpolation);
+        }
+    }
+}
+
+fn measure_interpolation_fps(
+    vtime: Res<Time<Virtual>>,
+    diagnostics: Res<DiagnosticsStore>,
+    state: Res<State<PhysicsMode>>,
+    interpolation: Res<AvianInterpolation>,
+    mut fps: ResMut<InterpolationFps>,
+) {
+    if state.is_changed() {
+        *fps = InterpolationFps::default();
+    }
+    if vtime.is_paused() || !is_avian(*state.get()) {
+        return;
+    }
+    if let Some(value) = diagnostics
+        .get(&FrameTimeDiagnosticsPlugin::FPS)
+        .and_then(|d| d.value())
+    {
+        fps.record(interpolation.0, value);
+    }
+}
+
+/// The HUD line: the state and the mean FPS on each side of the toggle.
+pub fn interpolation_label(
+    mode: PhysicsMode,
+    interpolation: AvianInterpolation,
+    fps: &InterpolationFps,
+) -> String {
+    if !is_avian(mode) {
+        return "Interpolation: Avian only".to_string();
+    }
+    let mean = |on| {
+        fps.mean(on)
+            .map_or_else(|| "-".to_string(), |v| format!("{v:.0}"))
+    };
+    format!(
+        "Interpolation: {} (FPS {} on / {} off)",
+        if interpolation.0 { "on" } else { "off" },
+        mean(true),
+        mean(false)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn label_shows_the_mean_fps_of_each_state() {
+        let mut fps = InterpolationFps::default();
+        fps.record(true, 50.0);
+        fps.record(true, 60.0);
+        assert_eq!(
+            interpolation_label(PhysicsMode::Avian2d, AvianInterpolation(true), &fps),
+            "Interpolation: on (FPS 55 on / - off)"
+        );
+        assert_eq!(
+            interpolation_label(PhysicsMode::Rapier3d, AvianInterpolation(true), &fps),
+            "Interpolation: Avian only"
+        );
+    }
+}
@@ -0,0 +1,342 @@
+//! Key bindings — every keyboard action reads its key from [`KeyBindings`], which
+//! can be remapped in a RON file for non-QWERTY layouts or to avoid conflicts.
+//!
+//! The file is `keys.ron` in the working directory, or the path given with
+//! `--keys`. It only needs the actions to change; the rest keep their defaults,
+//! which are the layout in the README. Keys use Bevy's `KeyCode` names:
+//!
+//! ```ron
+//! (
+//!     next_mode: NumpadEnter,
+//!     shape: KeyW,
+//!     pan_up: ArrowUp,
+//! )
+//! ```
+//!
+//! Modifiers stay fixed: `Shift` and `Ctrl` select the same alternate actions on
+//! whatever keys the bindings name (e.g. `Shift` + `restitution_up` raises friction).
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::cli;
+
+const DEFAULT_PATH: &str = "keys.ron";
+
+/// The key for every keyboard action. Paired actions are named `*_down` / `*_up`
+/// for decrease / increase.
+#[derive(Resource, Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct KeyBindings {
+    pub next_mode: KeyCode,
+    /// Jump straight to Avian 2D / Avian 3D / Rapier 2D / Rapier 3D.
+    pub modes: [KeyCode; 4],
+    pub auto_cycle: KeyCode,
+    pub pause: KeyCode,
+    /// Stop the timed spawner only; physics keeps running.
+    pub spawn_pause: KeyCode,
+    pub step: KeyCode,
+    pub speed_down: KeyCode,
+    pub speed_up: KeyCode,
+    pub speed_reset: KeyCode,
+    pub spawn_rate_down: KeyCode,
+    pub spawn_rate_up: KeyCode,
+    /// Gravity scale; with `Shift` / `Ctrl`, Rapier's solver iterations.
+    pub gravity_down: KeyCode,
+    pub gravity_up: KeyCode,
+    pub tilt_left: KeyCode,
+    pub tilt_right: KeyCode,
+    /// Restitution; with `Shift`, friction.
+    pub restitution_down: KeyCode,
+    pub restitution_up: KeyCode,
+    /// Linear damping; with `Shift`, angular damping.
+    pub damping_down: KeyCode,
+    pub damping_up: KeyCode,
+    pub density_down: KeyCode,
+    pub density_up: KeyCode,
+    pub substeps_down: KeyCode,
+    pub substeps_up: KeyCode,
+    pub nozzles_down: KeyCode,
+    pub nozzles_up: KeyCode,
+    pub shape: KeyCode,
+    pub pattern: KeyCode,
+    /// With `Shift`, colour by mass instead.
+    pub color_by_speed: KeyCode,
+    pub color_by_sleep: KeyCode,
+    pub ccd: KeyCode,
+    pub render_balls: KeyCode,
+    pub floor_drain: KeyCode,
+    pub floor_thickness_down: KeyCode,
+    pub floor_thickness_up: KeyCode,
+    pub top_wall: KeyCode,
+    pub no_walls: KeyCode,
+    pub vsync: KeyCode,
+    pub fullscreen: KeyCode,
+    pub split_screen: KeyCode,
+    pub ghost: KeyCode,
+    pub four_windows: KeyCode,
+    /// With `Ctrl`.
+    pub camera_lock: KeyCode,
+    /// Camera pan while locked, with `Ctrl`.
+    pub pan_up: KeyCode,
+    pub pan_down: KeyCode,
+    pub pan_left: KeyCode,
+    pub pan_right: KeyCode,
+    pub history: KeyCode,
+    pub comparison: KeyCode,
+    pub interpolation: KeyCode,
+    pub config_panel: KeyCode,
+    pub heatmap: KeyCode,
+    pub stage_timing: KeyCode,
+    pub debug_colliders: KeyCode,
+    pub versions: KeyCode,
+    pub settle: KeyCode,
+    pub burst: KeyCode,
+    /// Fill to each bulk preset, with `Shift`.
+    pub bulk_fill: [KeyCode; 5],
+    pub snapshot_save: KeyCode,
+    pub snapshot_load: KeyCode,
+    /// Every runtime setting back to its startup value, on an empty pool.
+    pub reset: KeyCode,
+    pub quit: KeyCode,
+}
+
+impl Default for KeyBindings {
+    fn default() -> Self {
+        Self {
+            next_mode: KeyCode::Enter,
+            modes: [
+                KeyCode::Digit1,
+                KeyCode::Digit2,
+                KeyCode::Digit3,
+                KeyCode::Digit4,
+            ],
+            auto_cycle: KeyCode::KeyC,
+            pause: KeyCode::Space,
+            spawn_pause: KeyCode::F8,
+            step: KeyCode::KeyN,
+            speed_down: KeyCode::Minus,
+            speed_up: KeyCode::Equal,
+            speed_reset: KeyCode::Digit0,
+            spawn_rate_down: KeyCode::ArrowDown,
+            spawn_rate_up: KeyCode::ArrowUp,
+            gravity_down: KeyCode::BracketLeft,
+            gravity_up: KeyCode::BracketRight,
+            tilt_left: KeyCode::KeyQ,
+            tilt_right: KeyCode::KeyE,
+            restitution_down: KeyCode::Semicolon,
+            restitution_up: KeyCode::Quote,
+            damping_down: KeyCode::KeyK,
+            damping_up: KeyCode::KeyL,
+            density_down: KeyCode::KeyY,
+            density_up: KeyCode::KeyU,
+            substeps_down: KeyCode::Comma,
+            substeps_up: KeyCode::Period,
+            nozzles_down: KeyCode::KeyO,
+            nozzles_up: KeyCode::KeyP,
+            shape: KeyCode::KeyS,
+            pattern: KeyCode::KeyG,
+            color_by_speed: KeyCode::KeyV,
+            color_by_sleep: KeyCode::F7,
+            ccd: KeyCode::KeyX,
+            render_balls: KeyCode::KeyM,
+            floor_drain: KeyCode::KeyB,
+            floor_thickness_down: KeyCode::PageDown,
+            floor_thickness_up: KeyCode::PageUp,
+            top_wall: KeyCode::KeyT,
+            no_walls: KeyCode::KeyW,
+            vsync: KeyCode::KeyF,
+            fullscreen: KeyCode::F11,
+            split_screen: KeyCode::KeyJ,
+            ghost: KeyCode::F6,
+            four_windows: KeyCode::F12,
+            camera_lock: KeyCode::KeyL,
+            pan_up: KeyCode::KeyW,
+            pan_down: KeyCode::KeyS,
+            pan_left: KeyCode::KeyA,
+            pan_right: KeyCode::KeyD,
+            history: KeyCode::KeyH,
+            comparison: KeyCode::F3,
+            interpolation: KeyCode::F4,
+            config_panel: KeyCode::Tab,
+            heatmap: KeyCode::KeyD,
+            stage_timing: KeyCode::F10,
+            debug_colliders: KeyCode::KeyR,
+            versions: KeyCode::KeyI,
+            settle: KeyCode::KeyZ,
+            burst: KeyCode::F2,
+            bulk_fill: [
+                KeyCode::Digit1,
+                KeyCode::Digit2,
+                KeyCode::Digit3,
+                KeyCode::Digit4,
+                KeyCode::Digit5,
+            ],
+            snapshot_save: KeyCode::F5,
+            snapshot_load: KeyCode::F9,
+            reset: KeyCode::Backspace,
+            quit: KeyCode::Escape,
+        }
+    }
+}
+
+impl KeyBindings {
+    /// Bindings from `--keys` or `keys.ron`. A missing default file silently means
+    /// the default layout; an unreadable or invalid file logs a warning and falls
+    /// back to it too.
+    pub fn load() -> Self {
+        let explicit = cli::value("--keys");
+        let path = explicit.clone().unwrap_or_else(|| DEFAULT_PATH.to_string());
+        let text = match std::fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(err) if explicit.is_none() && err.kind() == std::io::ErrorKind::NotFound => {
+                return Self::default();
+            }
+            Err(err) => {
+                warn!("Ignoring key bindings {path}: {err}");
+                return Self::default();
+            }
+        };
+        match Self::parse(&text) {
+            Ok(bindings) => {
+                info!("Loaded key bindings from {path}");
+                bindings
+            }
+            Err(err) => {
+                warn!("Ignoring key bindings {path}: {err}");
+                Self::default()
+            }
+        }
+    }
+
+    fn parse(text: &str) -> ron::error::SpannedResult<Self> {
+        ron::from_str(text)
+    }
+
+    /// The HUD's controls line: every action with its key, by `KeyCode` name, so a
+    /// remapped layout shows its own keys.
+    pub fn help_line(&self) -> String {
+        let pair = |down: KeyCode, up: KeyCode| format!("{down:?} / {up:?}");
+        let list = |keys: &[KeyCode]| {
+            keys.iter()
+                .map(|key| format!("{key:?}"))
+                .collect::<Vec<_>>()
+                .join(" / ")
+        };
+        [
+            format!("Next mode: {:?}", self.next_mode),
+            format!("Avian 2D / 3D, Rapier 2D / 3D: {}", list(&self.modes)),
+            format!("Pause: {:?}", self.pause),
+            format!("Pause spawning: {:?}", self.spawn_pause),
+            format!("Step: {:?}", self.step),
+            format!(
+                "Spawn rate: {} (hold)",
+                pair(self.spawn_rate_down, self.spawn_rate_up)
+            ),
+            format!("Gravity: {}", pair(self.gravity_down, self.gravity_up)),
+            format!(
+                "Rapier velocity / stabilization iters: Shift / Ctrl + {}",
+                pair(self.gravity_down, self.gravity_up)
+            ),
+            format!("Tilt: {}", pair(self.tilt_left, self.tilt_right)),
+            format!(
+                "Restitution: {0}  |  Friction: Shift + {0}",
+                pair(self.restitution_down, self.restitution_up)
+            ),
+            format!(
+                "Damping: {} (Shift: angular)",
+                pair(self.damping_down, self.damping_up)
+            ),
+            format!("Density: {}", pair(self.density_down, self.density_up)),
+            format!("Substeps: {}", pair(self.substeps_down, self.substeps_up)),
+            format!("Shape: {:?}", self.shape),
+            format!("Pattern: {:?}", self.pattern),
+            format!("Nozzles: {}", pair(self.nozzles_down, self.nozzles_up)),
+            format!(
+                "Color by speed: {0:?} (mass: Shift + {0:?})",
+                self.color_by_speed
+            ),
+            format!("Color by sleep: {:?}", self.color_by_sleep),
+            format!("CCD: {:?}", self.ccd),
+            format!("Render balls: {:?}", self.render_balls),
+            format!("Floor drain: {:?}", self.floor_drain),
+            format!(
+                "Floor thickness: {}",
+                pair(self.floor_thickness_down, self.floor_thickness_up)
+            ),
+            format!("Top wall: {:?}", self.top_wall),
+            format!("No walls: {:?}", self.no_walls),
+            format!("VSync: {:?}", self.vsync),
+            format!("Fullscreen: {:?}", self.fullscreen),
+            format!("Split screen: {:?}", self.split_screen),
+            format!("Ghost: {:?}", self.ghost),
+            format!("Four windows: {:?}", self.four_windows),
+            format!(
+                "Lock camera: Ctrl + {:?} (pan Ctrl + {}, zoom: scroll)",
+                self.camera_lock,
+                list(&[self.pan_up, self.pan_left, self.pan_down, self.pan_right])
+            ),
+            format!(
+                "Speed: {} ({:?} resets)",
+                pair(self.speed_down, self.speed_up),
+                self.speed_reset
+            ),
+            format!("Auto-cycle: {:?}", self.auto_cycle),
+            format!("History: {:?}", self.history),
+            format!("Comparison: {:?}", self.comparison),
+            format!("Interpolation: {:?}", self.interpolation),
+            format!("Config: {:?}", self.config_panel),
+            format!("Heatmap: {:?}", self.heatmap),
+            format!("Stage timing: {:?}", self.stage_timing),
+            format!("Debug colliders: {:?}", self.debug_colliders),
+            "Spawn or drag / explode: left / right click".to_string(),
+            format!(
+                "Fill to 1k/2k/5k/10k/20k: Shift + {}",
+                list(&self.bulk_fill)
+            ),
+            format!("Burst: {:?}", self.burst),
+            format!("Settle and report: {:?}", self.settle),
+            format!("Versions: {:?}", self.versions),
+            format!(
+                "Snapshot save / load: {}",
+                pair(self.snapshot_save, self.snapshot_load)
+            ),
+            format!("Reset settings: {:?}", self.reset),
+            format!("Quit: {:?}", self.quit),
+        ]
+        .join("  |  ")
+    }
+}
+
+pub fn plugin(app: &mut App) {
+    app.insert_resource(KeyBindings::load());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_overrides_only_the_given_actions() {
+        let bindings =
+            KeyBindings::parse("(next_mode: Backspace, modes: [F1, F2, F3, F4])").unwrap();
+        assert_eq!(bindings.next_mode, KeyCode::Backspace);
+        assert_eq!(bindings.modes[3], KeyCode::F4);
+        assert_eq!(bindings.pause, KeyBindings::default().pause);
+    }
+
+    #[test]
+    fn help_line_names_the_bound_keys() {
+        let defaults = KeyBindings::default().help_line();
+        for key in ["Enter", "KeyW", "F2", "F11", "ArrowUp", "Digit4"] {
+            assert!(defaults.contains(key), "{key} missing from {defaults}");
+        }
+        let remapped = KeyBindings::parse("(burst: KeyQ)").unwrap().help_line();
+        assert!(remapped.contains("Burst: KeyQ"));
+    }
+
+    #[test]
+    fn parse_rejects_unknown_actions() {
+        assert!(KeyBindings::parse("(teleport: KeyQ)").is_err());
+    }
+}
@@ -2,48 +2,127 @@
 #![cfg_attr(not(feature = "dev"), windows_subsystem = "windows")]
 
 mod backend;
+mod benchmark;
+mod chain;
+mod cli;
+mod comparison;
+mod config_panel;
+mod debug_render;
+mod drag;
+mod explosion;
+mod fps_guard;
+mod headless;
+mod heatmap;
+mod interpolate;
+mod keys;
+mod obstacles;
+mod perf_graph;
+mod pile_height;
+mod replay;
+mod reset;
+mod screenshots;
+mod sensor_zone;
+mod serve;
+mod settle;
+mod snapshot;
 mod spawner;
+mod speed_color;
+mod split;
+mod stage_timing;
+mod step_time;
+mod sweep;
+mod trace;
+mod trails;
+mod versions;
 mod walls;
+mod window_title;
 
 use bevy::{
     diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin},
+    input::mouse::{AccumulatedMouseMotion, AccumulatedMouseScroll, MouseScrollUnit},
     prelude::*,
-    window::{PrimaryWindow, WindowResolution},
+    window::{
+        ExitCondition, Monitor, MonitorSelection, PresentMode, PrimaryMonitor, PrimaryWindow,
+        WindowMode, WindowResolution,
+    },
 };
 
+// Bevy 0.17 pluralised system set names (`TimeSystem` → `TimeSystems`).
+#[cfg(feature = "legacy_state_scoped")]
+use bevy::time::TimeSystem as TimeSystems;
+#[cfg(not(feature = "legacy_state_scoped"))]
+use bevy::time::TimeSystems;
+// Bevy 0.16 sent `AppExit` through `EventWriter`; 0.17+ renamed buffered events to messages.
+#[cfg(feature = "legacy_state_scoped")]
+use bevy::prelude::EventWriter as MessageWriter;
+
+use std::collections::VecDeque;
 use std::time::Duration;
 
-use crate::backend::PhysicsMode;
-use crate::spawner::{Ball, BallCount, BallsPerTick};
+use crate::backend::{
+    CollisionEvents, ContactCount, FloorForce, GravityAngle, GravityScale, KineticEnergy,
+    NewContactRate, NgonSides, PenetrationStats, PhysicsMode, RapierIterations, SleepStats,
+    SpawnShape, Substeps,
+};
+use crate::benchmark::BenchmarkHistory;
+use crate::chain::JointCount;
+use crate::debug_render::DebugRender;
+use crate::interpolate::{AvianInterpolation, InterpolationFps, interpolation_label};
+use crate::keys::KeyBindings;
+use crate::obstacles::ObstacleLayout;
+use crate::perf_graph::PerfGraphCamera;
+use crate::pile_height::PileHeight;
+use crate::sensor_zone::{SensorZones, ZoneOccupancy, zone_label};
+use crate::spawner::{
+    Ball, BallAngularDamping, BallCcd, BallConfig, BallCount, BallDensity, BallFriction,
+    BallLinearDamping, BallRestitution, InitialSpin, LastBurst, MaxBalls, NozzleCount, RenderBalls,
+    SizeMix, SpawnFrozen, SpawnPattern, SpawnPaused, SpawnRate,
+};
+use crate::speed_color::{ColorByMass, ColorBySleep, ColorBySpeed};
+use crate::split::SplitPartner;
+use crate::step_time::PhysicsStepTime;
+use crate::walls::{
+    FloorDrain, FloorThickness, MonoWalls, NoWalls, PoolConfig, RoundedWalls, TopWall, WallConfig,
+};
 
 // ── Auto-zoom constants ────────────────────────────────────────────────────────
 
-/// Physics pool dimensions in world units (must match walls.rs WIDTH / HEIGHT).
-const POOL_W: f32 = 1920.0;
-const POOL_H: f32 = 1080.0;
-
 /// 3D camera look-at target (world space).
 const CAM3D_LOOK_AT: Vec3 = Vec3::new(0.0, -200.0, 0.0);
 
 /// Offset from the look-at point to the reference camera position.
-/// Reference position is (0, 3000, 3200), designed for a 960 × 540 window.
+/// Reference position is (0, 3000, 3200), designed for the default pool in a
+/// 960 × 540 window.
 const CAM3D_REF_OFFSET: Vec3 = Vec3::new(0.0, 3200.0, 3200.0);
 const CAM3D_REF_W: f32 = 960.0;
 const CAM3D_REF_H: f32 = 540.0;
 
 /// Smallest orthographic scale that fits the full pool into a window of `width × height`.
-fn ortho_scale_for_window(width: f32, height: f32) -> f32 {
-    (POOL_W / width).max(POOL_H / height)
+fn ortho_scale_for_window(pool: PoolConfig, width: f32, height: f32) -> f32 {
+    (pool.width / width).max(pool.height / height)
 }
 
 /// 3D camera world position that fits the full pool into a window of `width × height`.
-/// Scales the camera's distance from the look-at point, keeping the view direction fixed.
-fn cam3d_pos_for_window(width: f32, height: f32) -> Vec3 {
-    let scale = (CAM3D_REF_W / width).max(CAM3D_REF_H / height);
-    CAM3D_LOOK_AT + CAM3D_REF_OFFSET * scale
+/// Scales the camera's distance from the look-at point, keeping the view direction
+/// fixed; a pool larger than the default one in any dimension pushes it back in step.
+fn cam3d_pos_for_window(pool: PoolConfig, width: f32, height: f32) -> Vec3 {
+    CAM3D_LOOK_AT + cam3d_offset_for_window(pool, width, height)
+}
+
+fn cam3d_offset_for_window(pool: PoolConfig, width: f32, height: f32) -> Vec3 {
+    let default = PoolConfig::default();
+    let pool_scale = (pool.width / default.width)
+        .max(pool.height / default.height)
+        .max(pool.depth / default.depth);
+    let scale = (CAM3D_REF_W / width).max(CAM3D_REF_H / height) * pool_scale;
+    CAM3D_REF_OFFSET * scale
 }
 
 fn main() -> AppExit {
+    if cli::flag("--headless") {
+        return headless::app().run();
+    }
+
     App::new()
         .add_plugins(
             DefaultPlugins.set(WindowPlugin {
@@ -56,16 +135,65 @@ fn main() -> AppExit {
                     ..default()
                 }
                 .into(),
+                // Closing the main window quits even while split.rs's partner windows are open.
+                exit_condition: ExitCondition::OnPrimaryClosed,
                 ..default()
             }),
         )
         .add_plugins(FrameTimeDiagnosticsPlugin::default())
+        .add_plugins(keys::plugin)
         .add_plugins(backend::plugin)
         .add_plugins(spawner::plugin)
-        .init_resource::<PerfStats>()
+        .add_plugins(obstacles::plugin)
+        .add_plugins(benchmark::plugin)
+        .add_plugins(chain::plugin)
+        .add_plugins(comparison::plugin)
+        .add_plugins(config_panel::plugin)
+        .add_plugins(explosion::plugin)
+        .add_plugins(fps_guard::plugin)
+        .add_plugins(heatmap::plugin)
+        .add_plugins(interpolate::plugin)
+        .add_plugins(debug_render::plugin)
+        .add_plugins(drag::plugin)
+        .add_plugins(replay::plugin)
+        .add_plugins(reset::plugin)
+        .add_plugins(screenshots::plugin)
+        .add_plugins(sensor_zone::plugin)
+        .add_plugins(serve::plugin)
+        .add_plugins(settle::plugin)
+        .add_plugins(snapshot::plugin)
+        .add_plugins(perf_graph::plugin)
+        .add_plugins(pile_height::plugin)
+        .add_plugins(speed_color::plugin)
+        .add_plugins(split::plugin)
+        .add_plugins(step_time::plugin)
+        .add_plugins(stage_timing::plugin)
+        .add_plugins(sweep::plugin)
+        .add_plugins(trace::plugin)
+        .add_plugins(trails::plugin)
+        .add_plugins(window_title::plugin)
         .init_resource::<WarmupTimer>()
         .init_resource::<ClippedBallCount>()
+        .init_resource::<ExplodedBallCount>()
+        .init_resource::<DrainedBallCount>()
+        .init_resource::<FloorDrain>()
+        .insert_resource(NoWalls::from_args())
+        .insert_resource(RoundedWalls::from_args())
+        .insert_resource(MonoWalls::from_args())
+        .init_resource::<StepOnce>()
+        .init_resource::<CameraLock>()
+        .init_resource::<Cam3dOrbit>()
+        .init_resource::<FrameTimeWindow>()
+        .insert_resource(TargetFps(
+            cli::parsed("--target-fps").filter(|fps: &f32| *fps > 0.0),
+        ))
+        .insert_resource(AutoCycle::new(
+            cli::parsed("--cycle-secs")
+                .filter(|secs: &f32| *secs > 0.0)
+                .map_or(DEFAULT_CYCLE_DWELL, Duration::from_secs_f32),
+        ))
         .add_systems(Startup, setup)
+        .add_systems(First, step_virtual_time.after(TimeSystems))
         // Per-mode OnEnter: camera, walls, ball-count reset, mode label update.
         .add_systems(
             OnEnter(PhysicsMode::Avian2d),
@@ -117,17 +245,80 @@ fn main() -> AppExit {
                 pause_simulation,
             ),
         )
+        // HUD readouts and per-frame bookkeeping.
         .add_systems(
             Update,
             (
                 tick_warmup_timer,
-                update_fps_display.after(tick_warmup_timer),
+                record_frame_time,
+                update_fps_display
+                    .after(tick_warmup_timer)
+                    .after(record_frame_time),
                 update_ball_counter,
+                update_sleep_counter,
+                update_contact_counter,
+                update_new_contact_rate,
+                update_spin_display,
+                update_energy_display,
+                update_penetration_display,
+                update_pile_height_display,
+                update_zone_display,
+                update_burst_spike,
+                update_target_fps_headline,
                 detect_clipped_balls,
+                update_out_of_bounds_counter,
+                drain_top_balls,
+                update_step_indicator,
+                update_history_panel,
+                fit_camera_to_pool,
+            ),
+        )
+        // Keyboard controls.
+        .add_systems(
+            Update,
+            (
                 toggle_pause,
+                handle_step,
                 handle_mode_switch,
-                handle_balls_per_tick,
-                fit_camera_to_pool,
+                auto_cycle_modes,
+                handle_spawn_rate,
+                handle_gravity,
+                handle_gravity_direction,
+                handle_surface,
+                handle_spawn_shape,
+                handle_spawn_pattern,
+                handle_color_by_speed,
+                handle_ccd,
+                handle_floor_drain,
+                handle_top_wall,
+                handle_substeps,
+                handle_time_scale,
+                toggle_history_panel,
+                toggle_version_line,
+                handle_debug_render,
+            ),
+        )
+        // More keyboard controls; a single system tuple holds at most 20 systems.
+        .add_systems(
+            Update,
+            (
+                handle_nozzles,
+                handle_present_mode,
+                handle_rapier_iterations,
+                handle_damping,
+                handle_density,
+                toggle_fullscreen.after(fit_camera_to_pool),
+                handle_camera_lock,
+                manual_camera.after(handle_camera_lock),
+                orbit_camera.after(handle_camera_lock),
+                (handle_no_walls, restore_walls).chain(),
+                handle_render_balls,
+                handle_quit,
+                update_joint_display,
+                update_floor_force_display,
+                update_interpolation_display,
+                update_pattern_display,
+                handle_floor_thickness,
             ),
         )
         .run()
@@ -135,8 +326,21 @@ fn main() -> AppExit {
 
 // ── Startup ───────────────────────────────────────────────────────────────────
 
-fn setup(mut commands: Commands, mut time: ResMut<Time<Virtual>>) {
-    time.pause();
+/// Starts paused like every mode switch, unless `--autorun` asks for the starting mode
+/// (see `--mode`) to run straight away.
+fn setup(
+    mut commands: Commands,
+    mut time: ResMut<Time<Virtual>>,
+    density: Res<BallDensity>,
+    bindings: Res<KeyBindings>,
+    floor_thickness: Res<FloorThickness>,
+    thresholds: Res<FpsThresholds>,
+) {
+    if cli::flag("--autorun") {
+        time.unpause();
+    } else {
+        time.pause();
+    }
 
     // HUD root — full-screen flex container; all HUD elements are children.
     commands
@@ -177,12 +381,13 @@ fn setup(mut commands: Commands, mut time: ResMut<Time<Virtual>>) {
                     TextColor(Color::WHITE),
                 ));
 
-                // Center: mode label
+                // Center: mode label with the step-mode indicator below it
                 top.spawn((
                     Name::new("Mode Container"),
                     Node {
                         flex_grow: 1.0,
-                        justify_content: JustifyContent::Center,
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
                         ..default()
                     },
                 ))
@@ -198,6 +403,39 @@ fn setup(mut commands: Commands, mut time: ResMut<Time<Virtual>>) {
                         },
                         TextColor(Color::srgb(0.8, 0.8, 0.2)),
                     ));
+                    center.spawn((
+                        Name::new("Step Indicator"),
+                        StepText,
+                        Node::default(),
+                        Text::new(""),
+                        TextFont {
+                            font_size: 16.0,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(0.9, 0.3, 0.3)),
+                    ));
+                    center.spawn((
+                        Name::new("Debug Render Indicator"),
+                        DebugRenderText,
+                        Node::default(),
+                        Text::new(""),
+                        TextFont {
+                            font_size: 16.0,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(0.3, 0.9, 0.9)),
+                    ));
+                    center.spawn((
+                        Name::new("Target FPS Headline"),
+                        TargetFpsText,
+                        Node::default(),
+                        Text::new(""),
+                        TextFont {
+                            font_size: 28.0,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(0.4, 1.0, 0.4)),
+                    ));
                 });
 
                 // Right: ball counter column
@@ -222,16 +460,115 @@ fn setup(mut commands: Commands, mut time: ResMut<Time<Virtual>>) {
                         TextColor(Color::WHITE),
                     ));
                     right.spawn((
-                        Name::new("Balls Per Tick Display"),
-                        BallsPerTickText,
+                        Name::new("Spawn Rate Display"),
+                        SpawnRateText,
                         Node::default(),
-                        Text::new("Balls/tick: 1"),
+                        Text::new(spawn_rate_label(SpawnRate::default())),
                         TextFont {
                             font_size: 20.0,
                             ..default()
                         },
                         TextColor(Color::srgb(0.5, 0.9, 0.5)),
                     ));
+                    right.spawn((
+                        Name::new("Sleep Counter"),
+                        SleepCounterText,
+                        Node::default(),
+                        Text::new(sleep_counter_label(SleepStats::default())),
+                        TextFont {
+                            font_size: 20.0,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(0.6, 0.6, 0.9)),
+                    ));
+                    right.spawn((
+                        Name::new("Contact Counter"),
+                        ContactCounterText,
+                        Node::default(),
+                        Text::new(contact_counter_label(ContactCount::default())),
+                        TextFont {
+                            font_size: 20.0,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(0.6, 0.6, 0.9)),
+                    ));
+                    right.spawn((
+                        Name::new("New Contact Rate"),
+                        NewContactRateText,
+                        Node::default(),
+                        Text::new(new_contact_rate_label(Some(0))),
+                        TextFont {
+                            font_size: 20.0,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(0.6, 0.6, 0.9)),
+                    ));
+                    right.spawn((
+                        Name::new("Kinetic Energy Display"),
+                        KineticEnergyText,
+                        Node::default(),
+                        Text::new(energy_label(0.0, None)),
+                        TextFont {
+                            font_size: 20.0,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(0.6, 0.6, 0.9)),
+                    ));
+                    right.spawn((
+                        Name::new("Penetration Display"),
+                        PenetrationText,
+                        Node::default(),
+                        Text::new(penetration_label(PenetrationStats::default())),
+                        TextFont {
+                            font_size: 20.0,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(0.6, 0.6, 0.9)),
+                    ));
+                    right.spawn((
+                        Name::new("Floor Force Display"),
+                        FloorForceText,
+                        Node::default(),
+                        Text::new(floor_force_label(FloorForce::default())),
+                        TextFont {
+                            font_size: 20.0,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(0.6, 0.6, 0.9)),
+                    ));
+                    right.spawn((
+                        Name::new("Pile Height Marks"),
+                        PileHeightText,
+                        Node::default(),
+                        Text::new(""),
+                        TextFont {
+                            font_size: 20.0,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(0.6, 0.6, 0.9)),
+                    ));
+                    right.spawn((
+                        Name::new("Sensor Zone Occupancy"),
+                        ZoneText,
+                        Node::default(),
+                        Text::new(""),
+                        TextFont {
+                            font_size: 20.0,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(0.6, 0.6, 0.9)),
+                    ));
+                    right.spawn((
+                        Name::new("Chain Joint Counter"),
+                        JointText,
+                        Node::default(),
+                        Text::new(""),
+                        TextFont {
+                            font_size: 20.0,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(0.6, 0.6, 0.9)),
+                    ));
                     right.spawn((
                         Name::new("Clipped Ball Counter"),
                         ClippedBallCounterText,
@@ -243,31 +580,396 @@ fn setup(mut commands: Commands, mut time: ResMut<Time<Virtual>>) {
                         },
                         TextColor(Color::srgb(0.9, 0.5, 0.2)),
                     ));
+                    right.spawn((
+                        Name::new("Exploded Ball Counter"),
+                        ExplodedBallCounterText,
+                        Node::default(),
+                        Text::new("Exploded balls: 0"),
+                        TextFont {
+                            font_size: 20.0,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(0.9, 0.3, 0.3)),
+                    ));
+                    right.spawn((
+                        Name::new("Burst Spike Display"),
+                        BurstSpikeText,
+                        Node::default(),
+                        Text::new(burst_spike_label(None)),
+                        TextFont {
+                            font_size: 20.0,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(0.6, 0.6, 0.9)),
+                    ));
+                    right.spawn((
+                        Name::new("Out Of Bounds Counter"),
+                        OutOfBoundsText,
+                        Node::default(),
+                        Text::new("Out of bounds: 0"),
+                        TextFont {
+                            font_size: 20.0,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(0.9, 0.5, 0.2)),
+                    ));
+                    right.spawn((
+                        Name::new("Drained Ball Counter"),
+                        DrainedBallCounterText,
+                        Node::default(),
+                        Text::new("Drained (top): 0"),
+                        TextFont {
+                            font_size: 20.0,
+                            ..default()
+                        },
+                        TextColor(Color::srgb(0.9, 0.5, 0.2)),
+                    ));
                 });
             });
 
-            // Bottom row: button instructions (center)
+            // Results history: all modes side by side, toggled with `H`.
+            // Absolutely positioned so it doesn't shift the top/bottom rows.
+            root.spawn((
+                Name::new("History Panel"),
+                HistoryPanel,
+                Node {
+                    position_type: PositionType::Absolute,
+                    top: Val::Px(275.0),
+                    left: Val::Px(10.0),
+                    padding: UiRect::all(Val::Px(8.0)),
+                    ..default()
+                },
+                BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+                Visibility::Hidden,
+            ))
+            .with_children(|panel| {
+                panel.spawn((
+                    Name::new("History Table"),
+                    HistoryText,
+                    Node::default(),
+                    Text::new(benchmark::history_table(
+                        &BenchmarkHistory::default(),
+                        &thresholds.0,
+                    )),
+                    TextFont {
+                        font_size: 16.0,
+                        ..default()
+                    },
+                    TextColor(Color::WHITE),
+                ));
+            });
+
+            // Bottom row: runtime settings above the button instructions (center)
             root.spawn((
-                Name::new("Button Instructions Container"),
+                Name::new("Bottom Container"),
                 Node {
                     width: Val::Percent(100.0),
-                    justify_content: JustifyContent::Center,
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
                     ..default()
                 },
             ))
             .with_children(|bottom| {
+                bottom
+                    .spawn((
+                        Name::new("Settings Row"),
+                        Node {
+                            flex_direction: FlexDirection::Row,
+                            column_gap: Val::Px(20.0),
+                            ..default()
+                        },
+                    ))
+                    .with_children(|settings| {
+                        settings.spawn((
+                            Name::new("Gravity Display"),
+                            GravityText,
+                            Node::default(),
+                            Text::new(gravity_label(GravityScale::default())),
+                            TextFont {
+                                font_size: 20.0,
+                                ..default()
+                            },
+                            TextColor(Color::srgb(0.5, 0.7, 0.9)),
+                        ));
+                        settings.spawn((
+                            Name::new("Gravity Tilt Display"),
+                            GravityAngleText,
+                            Node::default(),
+                            Text::new(gravity_angle_label(GravityAngle::default())),
+                            TextFont {
+                                font_size: 20.0,
+                                ..default()
+                            },
+                            TextColor(Color::srgb(0.5, 0.7, 0.9)),
+                        ));
+                        settings.spawn((
+                            Name::new("Restitution Display"),
+                            RestitutionText,
+                            Node::default(),
+                            Text::new(restitution_label(BallRestitution::default())),
+                            TextFont {
+                                font_size: 20.0,
+                                ..default()
+                            },
+                            TextColor(Color::srgb(0.5, 0.7, 0.9)),
+                        ));
+                        settings.spawn((
+                            Name::new("Friction Display"),
+                            FrictionText,
+                            Node::default(),
+                            Text::new(friction_label(BallFriction::default())),
+                            TextFont {
+                                font_size: 20.0,
+                                ..default()
+                            },
+                            TextColor(Color::srgb(0.5, 0.7, 0.9)),
+                        ));
+                        settings.spawn((
+                            Name::new("Damping Display"),
+                            DampingText,
+                            Node::default(),
+                            Text::new(damping_label(
+                                BallLinearDamping::default(),
+                                BallAngularDamping::default(),
+                            )),
+                            TextFont {
+                                font_size: 20.0,
+                                ..default()
+                            },
+                            TextColor(Color::srgb(0.5, 0.7, 0.9)),
+                        ));
+                        settings.spawn((
+                            Name::new("Density Display"),
+                            DensityText,
+                            Node::default(),
+                            Text::new(density_label(*density)),
+                            TextFont {
+                                font_size: 20.0,
+                                ..default()
+                            },
+                            TextColor(Color::srgb(0.5, 0.7, 0.9)),
+                        ));
+                        settings.spawn((
+                            Name::new("Shape Display"),
+                            ShapeText,
+                            Node::default(),
+                            Text::new(shape_label(SpawnShape::default())),
+                            TextFont {
+                                font_size: 20.0,
+                                ..default()
+                            },
+                            TextColor(Color::srgb(0.5, 0.7, 0.9)),
+                        ));
+                        settings.spawn((
+                            Name::new("Pattern Display"),
+                            PatternText,
+                            Node::default(),
+                            Text::new(pattern_label(
+                                SpawnPattern::default(),
+                                SizeMix::default(),
+                            )),
+                            TextFont {
+                                font_size: 20.0,
+                                ..default()
+                            },
+                            TextColor(Color::srgb(0.5, 0.7, 0.9)),
+                        ));
+                        settings.spawn((
+                            Name::new("Nozzle Display"),
+                            NozzleText,
+                            Node::default(),
+                            Text::new(nozzle_label(NozzleCount::default())),
+                            TextFont {
+                                font_size: 20.0,
+                                ..default()
+                            },
+                            TextColor(Color::srgb(0.5, 0.7, 0.9)),
+                        ));
+                        settings.spawn((
+                            Name::new("Ball Color Display"),
+                            ColorBySpeedText,
+                            Node::default(),
+                            Text::new(color_by_speed_label(
+                                ColorBySpeed::default(),
+                                ColorByMass::default(),
+                                ColorBySleep::default(),
+                            )),
+                            TextFont {
+                                font_size: 20.0,
+                                ..default()
+                            },
+                            TextColor(Color::srgb(0.5, 0.7, 0.9)),
+                        ));
+                        settings.spawn((
+                            Name::new("CCD Display"),
+                            CcdText,
+                            Node::default(),
+                            Text::new(ccd_label(BallCcd::default())),
+                            TextFont {
+                                font_size: 20.0,
+                                ..default()
+                            },
+                            TextColor(Color::srgb(0.5, 0.7, 0.9)),
+                        ));
+                        settings.spawn((
+                            Name::new("Render Balls Display"),
+                            RenderBallsText,
+                            Node::default(),
+                            Text::new(render_balls_label(RenderBalls::default())),
+                            TextFont {
+                                font_size: 20.0,
+                                ..default()
+                            },
+                            TextColor(Color::srgb(0.5, 0.7, 0.9)),
+                        ));
+                        settings.spawn((
+                            Name::new("Spin Display"),
+                            SpinText,
+                            Node::default(),
+                            Text::new(spin_label(InitialSpin::default())),
+                            TextFont {
+                                font_size: 20.0,
+                                ..default()
+                            },
+                            TextColor(Color::srgb(0.5, 0.7, 0.9)),
+                        ));
+                        settings.spawn((
+                            Name::new("Floor Drain Display"),
+                            FloorDrainText,
+                            Node::default(),
+                            Text::new(floor_drain_label(FloorDrain::default())),
+                            TextFont {
+                                font_size: 20.0,
+                                ..default()
+                            },
+                            TextColor(Color::srgb(0.5, 0.7, 0.9)),
+                        ));
+                        settings.spawn((
+                            Name::new("Floor Thickness Display"),
+                            FloorThicknessText,
+                            Node::default(),
+                            Text::new(floor_thickness_label(*floor_thickness)),
+                            TextFont {
+                                font_size: 20.0,
+                                ..default()
+                            },
+                            TextColor(Color::srgb(0.5, 0.7, 0.9)),
+                        ));
+                        settings.spawn((
+                            Name::new("Top Wall Display"),
+                            TopWallText,
+                            Node::default(),
+                            Text::new(top_wall_label(TopWall::default())),
+                            TextFont {
+                                font_size: 20.0,
+                                ..default()
+                            },
+                            TextColor(Color::srgb(0.5, 0.7, 0.9)),
+                        ));
+                        settings.spawn((
+                            Name::new("No Walls Display"),
+                            NoWallsText,
+                            Node::default(),
+                            Text::new(no_walls_label(NoWalls::default())),
+                            TextFont {
+                                font_size: 20.0,
+                                ..default()
+                            },
+                            TextColor(Color::srgb(0.5, 0.7, 0.9)),
+                        ));
+                        settings.spawn((
+                            Name::new("Present Mode Display"),
+                            PresentModeText,
+                            Node::default(),
+                            Text::new(present_mode_label(PresentMode::default())),
+                            TextFont {
+                                font_size: 20.0,
+                                ..default()
+                            },
+                            TextColor(Color::srgb(0.5, 0.7, 0.9)),
+                        ));
+                        settings.spawn((
+                            Name::new("Interpolation Display"),
+                            InterpolationText,
+                            Node::default(),
+                            Text::new(""),
+                            TextFont {
+                                font_size: 20.0,
+                                ..default()
+                            },
+                            TextColor(Color::srgb(0.5, 0.7, 0.9)),
+                        ));
+                        settings.spawn((
+                            Name::new("Substeps Display"),
+                            SubstepsText,
+                            Node::default(),
+                            Text::new(""),
+                            TextFont {
+                                font_size: 20.0,
+                                ..default()
+                            },
+                            TextColor(Color::srgb(0.5, 0.7, 0.9)),
+                        ));
+                        settings.spawn((
+                            Name::new("Rapier Iterations Display"),
+                            RapierIterationsText,
+                            Node::default(),
+                            Text::new(""),
+                            TextFont {
+                                font_size: 20.0,
+                                ..default()
+                            },
+                            TextColor(Color::srgb(0.5, 0.7, 0.9)),
+                        ));
+                        settings.spawn((
+                            Name::new("Time Scale Display"),
+                            TimeScaleText,
+                            Node::default(),
+                            Text::new(time_scale_label(1.0)),
+                            TextFont {
+                                font_size: 20.0,
+                                ..default()
+                            },
+                            TextColor(Color::srgb(0.5, 0.7, 0.9)),
+                        ));
+                        settings.spawn((
+                            Name::new("Auto-cycle Display"),
+                            AutoCycleText,
+                            Node::default(),
+                            Text::new("Auto-cycle: off"),
+                            TextFont {
+                                font_size: 20.0,
+                                ..default()
+                            },
+                            TextColor(Color::srgb(0.5, 0.7, 0.9)),
+                        ));
+                    });
+
                 bottom.spawn((
                     Name::new("Button Instructions"),
                     Node::default(),
-                    Text::new(
-                        "Next mode: Enter  |  Pause: Space  |  Balls/tick: Up/Down",
-                    ),
+                    Text::new(bindings.help_line()),
                     TextFont {
                         font_size: 20.0,
                         ..default()
                     },
                     TextColor(Color::srgb(0.7, 0.7, 0.7)),
                 ));
+
+                bottom.spawn((
+                    Name::new("Version Line"),
+                    VersionText,
+                    Node {
+                        align_self: AlignSelf::FlexEnd,
+                        ..default()
+                    },
+                    Text::new(versions::summary()),
+                    TextFont {
+                        font_size: 14.0,
+                        ..default()
+                    },
+                    TextColor(Color::srgb(0.5, 0.5, 0.5)),
+                ));
             });
         });
 }
@@ -284,343 +986,2383 @@ struct BallCounterText;
 struct ModeText;
 
 #[derive(Component)]
-struct BallsPerTickText;
+struct StepText;
 
 #[derive(Component)]
-struct ClippedBallCounterText;
+struct DebugRenderText;
 
 #[derive(Component)]
-struct TopLight;
+struct TargetFpsText;
 
-#[derive(Resource, Default)]
-struct ClippedBallCount(usize);
+/// Crate version line in the bottom-right corner; shown by default so
+/// screenshots record the versions, toggled with `I`.
+#[derive(Component)]
+struct VersionText;
 
-/// Real-time delay after entering a mode before FPS milestones are recorded,
-/// so frame-0 spikes don't register.
-const PERF_WARMUP: Duration = Duration::from_millis(1000);
+#[derive(Component)]
+struct SpawnRateText;
 
-/// Separate timer resource so state transitions are never blocked.
-/// Reset via `Changed<State<PhysicsMode>>` in `tick_warmup_timer`.
-#[derive(Resource)]
-struct WarmupTimer(Timer);
+#[derive(Component)]
+struct SleepCounterText;
 
-impl Default for WarmupTimer {
-    fn default() -> Self {
-        Self(Timer::new(PERF_WARMUP, TimerMode::Once))
-    }
-}
+#[derive(Component)]
+struct ContactCounterText;
 
-/// Milestone ball counts recorded when FPS first crosses below a threshold.
-#[derive(Resource, Default)]
-struct PerfStats {
-    /// Ball count when instantaneous FPS first dropped below 50.
-    first_below_50: Option<usize>,
-    /// Ball count when 1-sec average FPS first dropped below 50.
-    avg_below_50: Option<usize>,
-    /// Ball count when instantaneous FPS first dropped below 15.
-    first_below_15: Option<usize>,
-    /// Ball count when 1-sec average FPS first dropped below 15.
-    avg_below_15: Option<usize>,
-}
+#[derive(Component)]
+struct NewContactRateText;
 
-// ── OnEnter helpers ───────────────────────────────────────────────────────────
+#[derive(Component)]
+struct KineticEnergyText;
 
-fn enter_2d_camera(
-    mut commands: Commands,
-    camera_2d: Query<Entity, With<Camera2d>>,
-    camera_3d: Query<Entity, With<Camera3d>>,
-    windows: Query<&Window>,
-) {
-    if !camera_2d.is_empty() {
-        return;
-    }
-    for e in &camera_3d {
-        commands.entity(e).despawn();
-    }
-    let scale = windows
-        .single()
-        .map(|w: &Window| ortho_scale_for_window(w.width(), w.height()))
-        .unwrap_or(2.0);
-    commands.spawn((
-        Name::new("Camera"),
-        Camera2d,
-        IsDefaultUiCamera,
-        Projection::Orthographic(OrthographicProjection {
-            scale,
-            ..OrthographicProjection::default_2d()
-        }),
-    ));
-}
+#[derive(Component)]
+struct PenetrationText;
 
-fn enter_3d_camera(
-    mut commands: Commands,
-    camera_2d: Query<Entity, With<Camera2d>>,
-    camera_3d: Query<Entity, With<Camera3d>>,
-    windows: Query<&Window>,
-) {
-    if !camera_3d.is_empty() {
-        return;
-    }
-    for e in &camera_2d {
-        commands.entity(e).despawn();
-    }
-    // Position above and in front of the pool, angled down to show all four
-    // walls, the floor, and the open top. Zoom is adjusted for the current window.
-    let cam_pos = windows
-        .single()
-        .map(|w: &Window| cam3d_pos_for_window(w.width(), w.height()))
-        .unwrap_or(CAM3D_LOOK_AT + CAM3D_REF_OFFSET);
-    commands.spawn((
-        Name::new("Camera"),
-        Camera3d::default(),
-        IsDefaultUiCamera,
-        Transform::from_translation(cam_pos).looking_at(CAM3D_LOOK_AT, Vec3::Y),
-    ));
+#[derive(Component)]
+struct FloorForceText;
 
-    // Point light positioned above the pool center.
-    commands.spawn((
-        Name::new("Top Light"),
-        TopLight,
-        PointLight {
-            intensity: 50_000_000_000.0,
-            range: 5_000.0,
-            shadows_enabled: false,
-            ..default()
-        },
-        Transform::from_xyz(0.0, 1200.0, 0.0),
-    ));
-}
+#[derive(Component)]
+struct PileHeightText;
 
-fn despawn_top_light(mut commands: Commands, lights: Query<Entity, With<TopLight>>) {
-    for e in &lights {
-        commands.entity(e).despawn();
-    }
-}
+#[derive(Component)]
+struct ZoneText;
 
-fn spawn_walls_system(
-    mut commands: Commands,
-    mut meshes: ResMut<Assets<Mesh>>,
-    mut materials: ResMut<Assets<StandardMaterial>>,
-    state: Res<State<PhysicsMode>>,
+#[derive(Component)]
+struct JointText;
+
+#[derive(Component)]
+struct ClippedBallCounterText;
+
+#[derive(Component)]
+struct ExplodedBallCounterText;
+
+#[derive(Component)]
+struct DrainedBallCounterText;
+
+#[derive(Component)]
+struct OutOfBoundsText;
+
+#[derive(Component)]
+struct BurstSpikeText;
+
+#[derive(Component)]
+struct FloorDrainText;
+
+#[derive(Component)]
+struct FloorThicknessText;
+
+#[derive(Component)]
+struct TopWallText;
+
+#[derive(Component)]
+struct NoWallsText;
+
+#[derive(Component)]
+struct PresentModeText;
+
+#[derive(Component)]
+struct InterpolationText;
+
+#[derive(Component)]
+struct GravityText;
+
+#[derive(Component)]
+struct GravityAngleText;
+
+#[derive(Component)]
+struct RestitutionText;
+
+#[derive(Component)]
+struct FrictionText;
+
+#[derive(Component)]
+struct DampingText;
+
+#[derive(Component)]
+struct DensityText;
+
+#[derive(Component)]
+struct ShapeText;
+
+#[derive(Component)]
+struct PatternText;
+
+#[derive(Component)]
+struct NozzleText;
+
+#[derive(Component)]
+struct ColorBySpeedText;
+
+#[derive(Component)]
+struct CcdText;
+
+#[derive(Component)]
+struct RenderBallsText;
+
+#[derive(Component)]
+struct SpinText;
+
+#[derive(Component)]
+struct SubstepsText;
+
+#[derive(Component)]
+struct RapierIterationsText;
+
+#[derive(Component)]
+struct TimeScaleText;
+
+#[derive(Component)]
+struct AutoCycleText;
+
+#[derive(Component)]
+struct HistoryPanel;
+
+#[derive(Component)]
+struct HistoryText;
+
+#[derive(Component)]
+struct TopLight;
+
+#[derive(Resource, Default)]
+struct ClippedBallCount(usize);
+
+/// Balls removed since the last mode switch because a solver blow-up sent them to
+/// NaN or far beyond the pool, see [`exploded`].
+#[derive(Resource, Default)]
+struct ExplodedBallCount(usize);
+
+/// Balls removed by the top drain since the last mode switch.
+#[derive(Resource, Default)]
+struct DrainedBallCount(usize);
+
+/// Default per-mode dwell time for auto-cycle; override with `--cycle-secs`.
+const DEFAULT_CYCLE_DWELL: Duration = Duration::from_secs(15);
+
+/// Unattended mode cycling, toggled with `C`. The timer runs on real time.
+#[derive(Resource)]
+struct AutoCycle {
+    enabled: bool,
+    dwell: Timer,
+}
+
+impl AutoCycle {
+    fn new(dwell: Duration) -> Self {
+        Self {
+            enabled: false,
+            dwell: Timer::new(dwell, TimerMode::Once),
+        }
+    }
+}
+
+/// Default real-time delay after entering a mode before FPS milestones are
+/// recorded, so frame-0 spikes don't register. `--warmup-ms` overrides it.
+const PERF_WARMUP: Duration = Duration::from_millis(1000);
+
+/// Separate timer resource so state transitions are never blocked.
+/// Reset via `Changed<State<PhysicsMode>>` in `tick_warmup_timer`.
+#[derive(Resource)]
+struct WarmupTimer(Timer);
+
+impl Default for WarmupTimer {
+    fn default() -> Self {
+        let warmup = cli::parsed("--warmup-ms").map_or(PERF_WARMUP, Duration::from_millis);
+        Self(Timer::new(warmup, TimerMode::Once))
+    }
+}
+
+/// FPS levels milestones are recorded at without `--thresholds`.
+const DEFAULT_FPS_THRESHOLDS: [f32; 2] = [50.0, 15.0];
+
+/// Parses `--thresholds`: comma-separated positive FPS values, returned highest
+/// first without duplicates. `None` if any value is invalid.
+fn parse_thresholds(raw: &str) -> Option<Vec<f32>> {
+    let mut thresholds = raw
+        .split(',')
+        .map(|value| value.trim().parse::<f32>().ok().filter(|fps| *fps > 0.0))
+        .collect::<Option<Vec<_>>>()?;
+    thresholds.sort_by(|a, b| b.total_cmp(a));
+    thresholds.dedup();
+    Some(thresholds)
+}
+
+/// FPS levels milestones are recorded at, highest first, from `--thresholds`
+/// (e.g. `50,30,15`). Parsed once at startup; [`PerfStats`] is built from it.
+#[derive(Resource, Debug, Clone, PartialEq)]
+struct FpsThresholds(Vec<f32>);
+
+impl Default for FpsThresholds {
+    fn default() -> Self {
+        Self(DEFAULT_FPS_THRESHOLDS.to_vec())
+    }
+}
+
+impl FpsThresholds {
+    fn from_args() -> Self {
+        let Some(raw) = cli::value("--thresholds") else {
+            return Self::default();
+        };
+        parse_thresholds(&raw).map_or_else(
+            || {
+                warn!("Ignoring invalid value for --thresholds: {raw:?}");
+                Self::default()
+            },
+            Self,
+        )
+    }
+}
+
+/// Ball counts latched when FPS first drops below one threshold.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct FpsMilestone {
+    threshold: f32,
+    /// Ball count when instantaneous FPS first dropped below `threshold`.
+    first: Option<usize>,
+    /// Ball count when 1-sec average FPS first dropped below `threshold`.
+    avg: Option<usize>,
+}
+
+/// Milestone ball counts recorded when FPS first crosses below a threshold.
+#[derive(Resource, Debug, Clone, PartialEq)]
+struct PerfStats {
+    /// One per FPS threshold, highest first.
+    below: Vec<FpsMilestone>,
+    /// Ball count when the p99 frame time first exceeded 33 ms.
+    p99_above_33: Option<usize>,
+    /// Ball count when the 1-sec average FPS first dropped below `--target-fps`.
+    balls_at_target: Option<usize>,
+}
+
+impl PerfStats {
+    fn new(thresholds: &[f32]) -> Self {
+        Self {
+            below: thresholds
+                .iter()
+                .map(|&threshold| FpsMilestone {
+                    threshold,
+                    first: None,
+                    avg: None,
+                })
+                .collect(),
+            p99_above_33: None,
+            balls_at_target: None,
+        }
+    }
+
+    fn thresholds(&self) -> Vec<f32> {
+        self.below
+            .iter()
+            .map(|milestone| milestone.threshold)
+            .collect()
+    }
+
+    /// Ball count when instantaneous FPS first dropped below the highest threshold.
+    fn first_below_highest(&self) -> Option<usize> {
+        self.below.first().and_then(|milestone| milestone.first)
+    }
+
+    /// Ball count when 1-sec average FPS first dropped below the lowest threshold.
+    fn avg_below_lowest(&self) -> Option<usize> {
+        self.below.last().and_then(|milestone| milestone.avg)
+    }
+
+    /// Every milestone with the name used for it in the CSV header, screenshot file
+    /// names and `/stats`, e.g. `first_below_50`.
+    fn named(&self) -> Vec<(String, Option<usize>)> {
+        let mut named = Vec::with_capacity(2 * self.below.len() + 2);
+        for milestone in &self.below {
+            named.push((
+                format!("first_below_{}", milestone.threshold),
+                milestone.first,
+            ));
+            named.push((format!("avg_below_{}", milestone.threshold), milestone.avg));
+        }
+        named.push(("p99_above_33".to_string(), self.p99_above_33));
+        named.push(("balls_at_target".to_string(), self.balls_at_target));
+        named
+    }
+}
+
+impl Default for PerfStats {
+    /// Milestones at the default thresholds; the app's own stats come from
+    /// [`FpsThresholds`].
+    fn default() -> Self {
+        Self::new(&DEFAULT_FPS_THRESHOLDS)
+    }
+}
+
+/// FPS the run should hold, from `--target-fps`. Once the 1-sec average drops
+/// below it the ball count is latched into [`PerfStats::balls_at_target`] and
+/// spawning stops, giving one headline number per backend.
+#[derive(Resource, Default, Clone, Copy)]
+struct TargetFps(Option<f32>);
+
+/// Number of recent frames kept in [`FrameTimeWindow`] (~2 s at 60 FPS).
+const FRAME_TIME_WINDOW: usize = 120;
+
+/// p99 frame time (ms) above which a run counts as stuttering.
+const P99_STUTTER_MS: f32 = 33.0;
+
+/// Ring buffer of the last real frame times. Percentiles over it expose stalls
+/// that the diagnostics FPS average smooths over.
+#[derive(Resource, Default)]
+struct FrameTimeWindow(VecDeque<Duration>);
+
+impl FrameTimeWindow {
+    fn push(&mut self, frame_time: Duration) {
+        if self.0.len() == FRAME_TIME_WINDOW {
+            self.0.pop_front();
+        }
+        self.0.push_back(frame_time);
+    }
+
+    fn is_full(&self) -> bool {
+        self.0.len() == FRAME_TIME_WINDOW
+    }
+
+    /// Nearest-rank percentile (`p` in 0–100) in milliseconds; `None` while empty.
+    fn percentile_ms(&self, p: f32) -> Option<f32> {
+        let mut sorted: Vec<Duration> = self.0.iter().copied().collect();
+        sorted.sort_unstable();
+        let rank = (p * sorted.len() as f32 / 100.0).ceil() as usize;
+        let index = rank.clamp(1, sorted.len().max(1)) - 1;
+        sorted.get(index).map(|d| d.as_micros() as f32 / 1000.0)
+    }
+}
+
+// ── OnEnter helpers ───────────────────────────────────────────────────────────
+
+fn enter_2d_camera(
+    mut commands: Commands,
+    camera_2d: Query<Entity, (With<Camera2d>, Without<PerfGraphCamera>)>,
+    camera_3d: Query<Entity, With<Camera3d>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    pool: Res<PoolConfig>,
+) {
+    if !camera_2d.is_empty() {
+        return;
+    }
+    for e in &camera_3d {
+        commands.entity(e).despawn();
+    }
+    let scale = windows
+        .single()
+        .map(|w: &Window| ortho_scale_for_window(*pool, w.width(), w.height()))
+        .unwrap_or(2.0);
+    commands.spawn((
+        Name::new("Camera"),
+        Camera2d,
+        IsDefaultUiCamera,
+        Projection::Orthographic(OrthographicProjection {
+            scale,
+            ..OrthographicProjection::default_2d()
+        }),
+    ));
+}
+
+fn enter_3d_camera(
+    mut commands: Commands,
+    camera_2d: Query<Entity, (With<Camera2d>, Without<PerfGraphCamera>)>,
+    camera_3d: Query<Entity, With<Camera3d>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    pool: Res<PoolConfig>,
+) {
+    if !camera_3d.is_empty() {
+        return;
+    }
+    for e in &camera_2d {
+        commands.entity(e).despawn();
+    }
+    // Position above and in front of the pool, angled down to show all four
+    // walls, the floor, and the open top. Zoom is adjusted for the current window.
+    let cam_pos = windows
+        .single()
+        .map(|w: &Window| cam3d_pos_for_window(*pool, w.width(), w.height()))
+        .unwrap_or(CAM3D_LOOK_AT + CAM3D_REF_OFFSET);
+    commands.spawn((
+        Name::new("Camera"),
+        Camera3d::default(),
+        IsDefaultUiCamera,
+        Transform::from_translation(cam_pos).looking_at(CAM3D_LOOK_AT, Vec3::Y),
+    ));
+
+    commands.spawn((Name::new("Top Light"), TopLight, top_light()));
+}
+
+/// Point light positioned above the pool center.
+fn top_light() -> (PointLight, Transform) {
+    (
+        PointLight {
+            intensity: 50_000_000_000.0,
+            range: 5_000.0,
+            shadows_enabled: false,
+            ..default()
+        },
+        Transform::from_xyz(0.0, 1200.0, 0.0),
+    )
+}
+
+fn despawn_top_light(mut commands: Commands, lights: Query<Entity, With<TopLight>>) {
+    for e in &lights {
+        commands.entity(e).despawn();
+    }
+}
+
+fn spawn_walls_system(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    state: Res<State<PhysicsMode>>,
+    ball_config: BallConfig,
+    wall_config: WallConfig,
+) {
+    wall_config.spawn(
+        &mut commands,
+        &mut meshes,
+        &mut materials,
+        *state.get(),
+        ball_config.surface(),
+    );
+}
+
+fn reset_ball_count(mut ball_count: ResMut<BallCount>) {
+    ball_count.0 = 0;
+}
+
+/// Resets the escape counters: clipped (floor / walls), exploded and drained (top).
+/// `drain_top_balls` refreshes the drained label on the next frame.
+fn reset_clipped_ball_count(
+    mut clipped: ResMut<ClippedBallCount>,
+    mut exploded: ResMut<ExplodedBallCount>,
+    mut drained: ResMut<DrainedBallCount>,
+    mut query: Query<(&mut Text, Has<ExplodedBallCounterText>), EscapeCounterText>,
+) {
+    clipped.0 = 0;
+    exploded.0 = 0;
+    drained.0 = 0;
+    for (mut text, is_exploded) in &mut query {
+        **text = escape_label(is_exploded, 0);
+    }
+}
+
+/// The clipped and exploded counters, both written by [`detect_clipped_balls`].
+type EscapeCounterText = Or<(With<ClippedBallCounterText>, With<ExplodedBallCounterText>)>;
+
+fn escape_label(is_exploded: bool, count: usize) -> String {
+    if is_exploded {
+        format!("Exploded balls: {count}")
+    } else {
+        format!("Clipped balls: {count}")
+    }
+}
+
+fn reset_perf_stats(
+    thresholds: Res<FpsThresholds>,
+    mut stats: ResMut<PerfStats>,
+    mut frame_times: ResMut<FrameTimeWindow>,
+) {
+    *stats = PerfStats::new(&thresholds.0);
+    // Drop the transition frames so they don't count against the new mode.
+    frame_times.0.clear();
+}
+
+fn pause_simulation(mut vtime: ResMut<Time<Virtual>>) {
+    vtime.pause();
+}
+
+fn update_mode_text(state: Res<State<PhysicsMode>>, mut query: Query<&mut Text, With<ModeText>>) {
+    for mut text in &mut query {
+        **text = state.get().label().to_string();
+    }
+}
+
+// ── Update systems ────────────────────────────────────────────────────────────
+
+/// Adjusts camera zoom / position whenever the primary window is resized so the
+/// whole physics pool always fits inside the window without being clipped.
+///
+/// Uses `Changed<Window>` rather than `WindowResized` events so it works both
+/// in real windowed builds (where WinitPlugin mutates the component on resize)
+/// and in headless tests (where `Window` can be mutated directly).
+///
+/// - 2D camera: updates the orthographic projection scale.
+/// - 3D camera: scales its distance from the look-at point along the fixed
+///   view direction, which is equivalent to perspective zoom.
+///
+/// A resize also drops any [`Cam3dOrbit`] back to the fitted view.
+fn fit_camera_to_pool(
+    windows: Query<&Window, (With<PrimaryWindow>, Changed<Window>)>,
+    pool: Res<PoolConfig>,
+    lock: Res<CameraLock>,
+    mut orbit: ResMut<Cam3dOrbit>,
+    mut fitted_size: Local<Vec2>,
+    mut cam2d: Query<&mut Projection, With<Camera2d>>,
+    mut cam3d: Query<&mut Transform, With<Camera3d>>,
+) {
+    if lock.0 {
+        return; // framed by hand, see `manual_camera`
+    }
+    let Ok(window) = windows.single() else {
+        return; // no change this frame
+    };
+    // `Changed<Window>` also fires on every cursor move; only a real resize resets the orbit.
+    if window.size() != *fitted_size {
+        *fitted_size = window.size();
+        orbit.set_if_neq(Cam3dOrbit::default());
+    }
+    fit_cameras(
+        *pool,
+        window.width(),
+        window.height(),
+        *orbit,
+        &mut cam2d,
+        &mut cam3d,
+    );
+}
+
+fn fit_cameras(
+    pool: PoolConfig,
+    w: f32,
+    h: f32,
+    orbit: Cam3dOrbit,
+    cam2d: &mut Query<&mut Projection, With<Camera2d>>,
+    cam3d: &mut Query<&mut Transform, With<Camera3d>>,
+) {
+    for mut proj in cam2d.iter_mut() {
+        if let Projection::Orthographic(ref mut ortho) = *proj {
+            ortho.scale = ortho_scale_for_window(pool, w, h);
+        }
+    }
+
+    for mut transform in cam3d.iter_mut() {
+        *transform = orbit.transform(pool, w, h);
+    }
+}
+
+/// `F11` toggles between windowed and borderless fullscreen.
+///
+/// The resize that follows a mode change is not reliably reported, so the toggle
+/// sets the expected resolution itself (the primary monitor's size going in, the
+/// remembered windowed size coming out) and refits both cameras right away. Since
+/// that also marks the window changed, `fit_camera_to_pool` agrees with it on the
+/// next frame instead of refitting to the old size.
+fn toggle_fullscreen(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+    monitors: Query<&Monitor, With<PrimaryMonitor>>,
+    lock: Res<CameraLock>,
+    mut orbit: ResMut<Cam3dOrbit>,
+    mut windowed_size: Local<Option<UVec2>>,
+    pool: Res<PoolConfig>,
+    mut cam2d: Query<&mut Projection, With<Camera2d>>,
+    mut cam3d: Query<&mut Transform, With<Camera3d>>,
+) {
+    if !keys.just_pressed(bindings.fullscreen) {
+        return;
+    }
+    let Ok(mut window) = windows.single_mut() else {
+        return;
+    };
+
+    let size = if window.mode == WindowMode::Windowed {
+        *windowed_size = Some(window.physical_size());
+        window.mode = WindowMode::BorderlessFullscreen(MonitorSelection::Current);
+        monitors
+            .single()
+            .ok()
+            .map(|monitor| UVec2::new(monitor.physical_width, monitor.physical_height))
+    } else {
+        window.mode = WindowMode::Windowed;
+        windowed_size.take()
+    };
+    if let Some(size) = size {
+        window.resolution.set_physical_resolution(size.x, size.y);
+    }
+    if !lock.0 {
+        orbit.set_if_neq(Cam3dOrbit::default());
+        fit_cameras(
+            *pool,
+            window.width(),
+            window.height(),
+            *orbit,
+            &mut cam2d,
+            &mut cam3d,
+        );
+    }
+}
+
+/// While set, the cameras are framed by hand (see [`manual_camera`]) and window
+/// resizes no longer refit them. Toggled with `Ctrl + L`.
+#[derive(Resource, Default)]
+struct CameraLock(bool);
+
+/// `Ctrl + L` locks the camera for manual framing, or unlocks it and snaps back to
+/// the fitted view of the whole pool.
+fn handle_camera_lock(
+    input: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut lock: ResMut<CameraLock>,
+    mut orbit: ResMut<Cam3dOrbit>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    pool: Res<PoolConfig>,
+    mut cam2d: Query<(&mut Projection, &mut Transform), (With<Camera2d>, Without<Camera3d>)>,
+    mut cam3d: Query<&mut Transform, With<Camera3d>>,
+) {
+    if !(input.just_pressed(bindings.camera_lock)
+        && input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]))
+    {
+        return;
+    }
+    lock.0 = !lock.0;
+    if lock.0 {
+        return;
+    }
+
+    let Ok(window) = windows.single() else {
+        return;
+    };
+    let (w, h) = (window.width(), window.height());
+    for (mut proj, mut transform) in &mut cam2d {
+        if let Projection::Orthographic(ref mut ortho) = *proj {
+            ortho.scale = ortho_scale_for_window(*pool, w, h);
+        }
+        transform.translation = Vec3::ZERO;
+    }
+    orbit.set_if_neq(Cam3dOrbit::default());
+    for mut transform in &mut cam3d {
+        *transform = orbit.transform(*pool, w, h);
+    }
+}
+
+/// Radians the 3D camera orbits per pixel of middle-mouse drag.
+const ORBIT_SENSITIVITY: f32 = 0.005;
+
+/// Lowest and highest elevation of the orbiting 3D camera above the look-at point.
+const ORBIT_MIN_ELEVATION: f32 = 5.0_f32.to_radians();
+const ORBIT_MAX_ELEVATION: f32 = 85.0_f32.to_radians();
+
+/// Closest and farthest the orbiting camera dollies, relative to the fitted distance.
+const ORBIT_MIN_ZOOM: f32 = 0.2;
+const ORBIT_MAX_ZOOM: f32 = 4.0;
+
+/// Orbit of the unlocked 3D camera about [`CAM3D_LOOK_AT`], relative to the fitted
+/// view: middle-drag turns it, the scroll wheel dollies. Reset by a resize, `F11`
+/// and unlocking; locking keeps the orbited view as the start of manual framing.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+struct Cam3dOrbit {
+    /// Turn about the vertical axis, in radians.
+    yaw: f32,
+    /// Elevation change from the fitted view's, in radians.
+    pitch: f32,
+    /// Distance from the look-at point, as a multiple of the fitted distance.
+    zoom: f32,
+}
+
+impl Default for Cam3dOrbit {
+    fn default() -> Self {
+        Self {
+            yaw: 0.0,
+            pitch: 0.0,
+            zoom: 1.0,
+        }
+    }
+}
+
+impl Cam3dOrbit {
+    /// Elevation of the fitted view, which [`CAM3D_REF_OFFSET`] fixes.
+    fn fitted_elevation() -> f32 {
+        CAM3D_REF_OFFSET
+            .y
+            .atan2(Vec2::new(CAM3D_REF_OFFSET.x, CAM3D_REF_OFFSET.z).length())
+    }
+
+    /// Camera transform for a window of `width × height`. The default orbit is
+    /// exactly the fitted view.
+    fn transform(self, pool: PoolConfig, width: f32, height: f32) -> Transform {
+        let rotation = Quat::from_rotation_y(self.yaw) * Quat::from_rotation_x(-self.pitch);
+        let offset = rotation * cam3d_offset_for_window(pool, width, height) * self.zoom;
+        Transform::from_translation(CAM3D_LOOK_AT + offset).looking_at(CAM3D_LOOK_AT, Vec3::Y)
+    }
+
+    /// Applies a middle-drag of `drag` pixels and `scroll_lines` of scrolling
+    /// (up dollies in), keeping the camera above the floor and off the vertical.
+    fn turn(&mut self, drag: Vec2, scroll_lines: f32) {
+        let fitted = Self::fitted_elevation();
+        self.yaw -= drag.x * ORBIT_SENSITIVITY;
+        self.pitch = (self.pitch + drag.y * ORBIT_SENSITIVITY)
+            .clamp(ORBIT_MIN_ELEVATION - fitted, ORBIT_MAX_ELEVATION - fitted);
+        self.zoom =
+            (self.zoom / CAMERA_ZOOM_STEP.powf(scroll_lines)).clamp(ORBIT_MIN_ZOOM, ORBIT_MAX_ZOOM);
+    }
+}
+
+/// Middle-drag orbits the 3D camera about the pool and the scroll wheel dollies it,
+/// while the camera is unlocked (locked, [`manual_camera`] owns the scroll wheel).
+fn orbit_camera(
+    state: Res<State<PhysicsMode>>,
+    lock: Res<CameraLock>,
+    buttons: Res<ButtonInput<MouseButton>>,
+    motion: Res<AccumulatedMouseMotion>,
+    scroll: Res<AccumulatedMouseScroll>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    pool: Res<PoolConfig>,
+    mut orbit: ResMut<Cam3dOrbit>,
+    mut cam3d: Query<&mut Transform, With<Camera3d>>,
+) {
+    if lock.0 || !matches!(state.get(), PhysicsMode::Avian3d | PhysicsMode::Rapier3d) {
+        return;
+    }
+    let drag = if buttons.pressed(MouseButton::Middle) {
+        motion.delta
+    } else {
+        Vec2::ZERO
+    };
+    let scroll_lines = match scroll.unit {
+        MouseScrollUnit::Line => scroll.delta.y,
+        MouseScrollUnit::Pixel => scroll.delta.y / SCROLL_PIXELS_PER_LINE,
+    };
+    if drag == Vec2::ZERO && scroll_lines == 0.0 {
+        return;
+    }
+    let Ok(window) = windows.single() else {
+        return;
+    };
+
+    orbit.turn(drag, scroll_lines);
+    for mut transform in &mut cam3d {
+        *transform = orbit.transform(*pool, window.width(), window.height());
+    }
+}
+
+/// Pan speed for `Ctrl` + `W` / `A` / `S` / `D`, in pixels per second at 1x zoom.
+const CAMERA_PAN_SPEED: f32 = 1200.0;
+
+/// Zoom factor per scroll-wheel line.
+const CAMERA_ZOOM_STEP: f32 = 1.1;
+
+/// Distance the 3D camera dollies per scroll-wheel line, in pixels.
+const CAMERA_DOLLY_STEP: f32 = 150.0;
+
+/// Touchpads report pixels rather than lines; this many count as one line.
+const SCROLL_PIXELS_PER_LINE: f32 = 50.0;
+
+/// Manual framing while [`CameraLock`] is set: `Ctrl` + `W` / `A` / `S` / `D` pans
+/// and the scroll wheel zooms (orthographic scale in 2D, a dolly along the view
+/// direction in 3D). `Ctrl` keeps the keys clear of their unmodified bindings.
+fn manual_camera(
+    input: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    scroll: Res<AccumulatedMouseScroll>,
+    time: Res<Time<Real>>,
+    lock: Res<CameraLock>,
+    mut cam2d: Query<(&mut Projection, &mut Transform), (With<Camera2d>, Without<Camera3d>)>,
+    mut cam3d: Query<&mut Transform, With<Camera3d>>,
+) {
+    if !lock.0 {
+        return;
+    }
+    let scroll_lines = match scroll.unit {
+        MouseScrollUnit::Line => scroll.delta.y,
+        MouseScrollUnit::Pixel => scroll.delta.y / SCROLL_PIXELS_PER_LINE,
+    };
+    let mut pan = Vec2::ZERO;
+    if input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        for (key, direction) in [
+            (bindings.pan_up, Vec2::Y),
+            (bindings.pan_down, Vec2::NEG_Y),
+            (bindings.pan_left, Vec2::NEG_X),
+            (bindings.pan_right, Vec2::X),
+        ] {
+            if input.pressed(key) {
+                pan += direction;
+            }
+        }
+    }
+    let pan = pan * CAMERA_PAN_SPEED * time.delta_secs();
+
+    for (mut proj, mut transform) in &mut cam2d {
+        if let Projection::Orthographic(ref mut ortho) = *proj {
+            // Scrolling up zooms in.
+            ortho.scale /= CAMERA_ZOOM_STEP.powf(scroll_lines);
+            transform.translation += (pan * ortho.scale).extend(0.0);
+        }
+    }
+    for mut transform in &mut cam3d {
+        let (right, up, forward) = (transform.right(), transform.up(), transform.forward());
+        transform.translation +=
+            right * pan.x + up * pan.y + forward * scroll_lines * CAMERA_DOLLY_STEP;
+    }
+}
+
+fn toggle_pause(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut time: ResMut<Time<Virtual>>,
+) {
+    if keys.just_pressed(bindings.pause) {
+        if time.is_paused() {
+            time.unpause();
+        } else {
+            time.pause();
+        }
+    }
+}
+
+/// Requests a single simulation frame while paused; consumed by [`step_virtual_time`].
+#[derive(Resource, Default)]
+struct StepOnce(bool);
+
+/// `N` advances the paused simulation by exactly one fixed timestep.
+fn handle_step(
+    keys: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    time: Res<Time<Virtual>>,
+    mut step: ResMut<StepOnce>,
+) {
+    if keys.just_pressed(bindings.step) && time.is_paused() {
+        step.0 = true;
+    }
+}
+
+/// Runs right after Bevy updates `Time<Virtual>` in `First`. Virtual time stays
+/// paused but is advanced by one fixed timestep, so `FixedMain` (Avian) runs once and
+/// `Update` / `PostUpdate` systems (spawner, Rapier) see a single frame's delta.
+/// The next frame's time update yields a zero delta again.
+fn step_virtual_time(
+    mut step: ResMut<StepOnce>,
+    fixed: Res<Time<Fixed>>,
+    mut vtime: ResMut<Time<Virtual>>,
+    mut time: ResMut<Time>,
+) {
+    if !step.0 {
+        return;
+    }
+    step.0 = false;
+
+    vtime.advance_by(fixed.timestep());
+    *time = vtime.as_generic();
+}
+
+/// Pause state under the mode label: `PAUSED` with the `N` step hint while the whole
+/// simulation is paused, `SPAWN PAUSED` while only the spawner is (`F8`).
+fn pause_label(paused: bool, spawn_paused: SpawnPaused) -> &'static str {
+    if paused {
+        "PAUSED — STEP (N)"
+    } else if spawn_paused.0 {
+        "SPAWN PAUSED"
+    } else {
+        ""
+    }
+}
+
+fn update_step_indicator(
+    time: Res<Time<Virtual>>,
+    spawn_paused: Res<SpawnPaused>,
+    mut query: Query<&mut Text, With<StepText>>,
+) {
+    let label = pause_label(time.is_paused(), *spawn_paused);
+    for mut text in &mut query {
+        if text.as_str() != label {
+            **text = label.to_string();
+        }
+    }
+}
+
+/// Ball counter text; appends `(cap)` once [`MaxBalls`] is reached.
+fn ball_counter_label(count: usize, max_balls: MaxBalls) -> String {
+    if max_balls.reached(count) {
+        format!("Balls: {count} (cap)")
+    } else {
+        format!("Balls: {count}")
+    }
+}
+
+fn update_ball_counter(
+    ball_count: Res<BallCount>,
+    max_balls: Res<MaxBalls>,
+    mut query: Query<&mut Text, With<BallCounterText>>,
+) {
+    if ball_count.is_changed() || max_balls.is_changed() {
+        for mut text in &mut query {
+            **text = ball_counter_label(ball_count.0, *max_balls);
+        }
+    }
+}
+
+fn sleep_counter_label(stats: SleepStats) -> String {
+    format!("{} sleeping / {} awake", stats.sleeping, stats.awake)
+}
+
+fn update_sleep_counter(
+    stats: Res<SleepStats>,
+    mut query: Query<&mut Text, With<SleepCounterText>>,
+) {
+    if stats.is_changed() {
+        for mut text in &mut query {
+            **text = sleep_counter_label(*stats);
+        }
+    }
+}
+
+fn contact_counter_label(count: ContactCount) -> String {
+    format!("Contacts: {}", count.0)
+}
+
+fn update_contact_counter(
+    count: Res<ContactCount>,
+    mut query: Query<&mut Text, With<ContactCounterText>>,
+) {
+    if count.is_changed() {
+        for mut text in &mut query {
+            **text = contact_counter_label(*count);
+        }
+    }
+}
+
+/// The rate, or `None` while collision events are off.
+fn new_contact_rate_label(per_second: Option<usize>) -> String {
+    match per_second {
+        Some(per_second) => format!("New contacts: {per_second}/s"),
+        None => "New contacts: off (--new-contacts)".to_string(),
+    }
+}
+
+/// The rate resource ticks every frame, so compare text instead of relying on
+/// change detection; the label only moves once per second.
+fn update_new_contact_rate(
+    events: Res<CollisionEvents>,
+    rate: Res<NewContactRate>,
+    mut query: Query<&mut Text, With<NewContactRateText>>,
+) {
+    let label = new_contact_rate_label(events.0.then_some(rate.per_second));
+    for mut text in &mut query {
+        if **text != label {
+            **text = label.clone();
+        }
+    }
+}
+
+fn energy_label(total: f32, change_per_second: Option<f32>) -> String {
+    match change_per_second {
+        Some(change) => format!("Kinetic energy: {total:.3e} ({change:+.2e}/s)"),
+        None => format!("Kinetic energy: {total:.3e}"),
+    }
+}
+
+/// Shows the summed kinetic energy and its change over the last second. A rise
+/// while the ball count held steady is flagged in red.
+fn update_energy_display(
+    energy: Res<KineticEnergy>,
+    mut query: Query<(&mut Text, &mut TextColor), With<KineticEnergyText>>,
+) {
+    for (mut text, mut color) in &mut query {
+        **text = energy_label(energy.total, energy.change_per_second());
+        color.0 = if energy.is_gaining() {
+            Color::srgb(1.0, 0.4, 0.4)
+        } else {
+            Color::srgb(0.6, 0.6, 0.9)
+        };
+    }
+}
+
+fn penetration_label(stats: PenetrationStats) -> String {
+    format!("Max penetration: {:.2} px", stats.max)
+}
+
+fn update_penetration_display(
+    stats: Res<PenetrationStats>,
+    mut query: Query<&mut Text, With<PenetrationText>>,
+) {
+    if stats.is_changed() {
+        for mut text in &mut query {
+            **text = penetration_label(*stats);
+        }
+    }
+}
+
+/// The force with its share of the pool's weight; a settled pile on a flat floor
+/// should approach 100 %.
+fn floor_force_label(force: FloorForce) -> String {
+    match force.share() {
+        Some(share) => format!(
+            "Floor force: {:.3e} ({:.0}% of weight)",
+            force.total,
+            share * 100.0
+        ),
+        None => format!("Floor force: {:.3e}", force.total),
+    }
+}
+
+fn update_floor_force_display(
+    force: Res<FloorForce>,
+    mut query: Query<&mut Text, With<FloorForceText>>,
+) {
+    if force.is_changed() {
+        for mut text in &mut query {
+            **text = floor_force_label(*force);
+        }
+    }
+}
+
+/// One line per mark: when the settled pile first reached it, or `-`.
+fn pile_height_label(pile: &PileHeight) -> String {
+    pile.marks
+        .iter()
+        .zip(&pile.reached)
+        .map(|(mark, reached)| match reached {
+            Some(reached) => format!(
+                "Pile {mark:.0}%: {:.1} s / {} balls",
+                reached.secs, reached.balls
+            ),
+            None => format!("Pile {mark:.0}%: -"),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// The marks resource changes every frame with its clock, so compare the text instead.
+fn update_pile_height_display(
+    pile: Res<PileHeight>,
+    mut query: Query<&mut Text, With<PileHeightText>>,
+) {
+    let label = pile_height_label(&pile);
+    for mut text in &mut query {
+        if **text != label {
+            **text = label.clone();
+        }
+    }
+}
+
+fn update_zone_display(
+    zones: Res<SensorZones>,
+    occupancy: Res<ZoneOccupancy>,
+    mut query: Query<&mut Text, With<ZoneText>>,
+) {
+    if !occupancy.is_changed() {
+        return;
+    }
+    let label = zone_label(*zones, *occupancy);
+    for mut text in &mut query {
+        **text = label.clone();
+    }
+}
+
+/// Refreshed on a mode switch, a toggle or a new FPS sample.
+fn update_interpolation_display(
+    state: Res<State<PhysicsMode>>,
+    interpolation: Res<AvianInterpolation>,
+    fps: Res<InterpolationFps>,
+    mut query: Query<&mut Text, With<InterpolationText>>,
+) {
+    if !state.is_changed() && !interpolation.is_changed() && !fps.is_changed() {
+        return;
+    }
+    let label = interpolation_label(*state.get(), *interpolation, &fps);
+    for mut text in &mut query {
+        **text = label.clone();
+    }
+}
+
+/// Empty until a chain has been dropped, so the HUD is unchanged without chains.
+fn joint_label(count: JointCount) -> String {
+    match count.0 {
+        0 => String::new(),
+        n => format!("Joints: {n}"),
+    }
+}
+
+fn update_joint_display(count: Res<JointCount>, mut query: Query<&mut Text, With<JointText>>) {
+    if !count.is_changed() {
+        return;
+    }
+    let label = joint_label(*count);
+    for mut text in &mut query {
+        **text = label.clone();
+    }
+}
+
+/// How long after an `F2` burst frame times count towards its spike.
+const BURST_SPIKE_WINDOW: Duration = Duration::from_millis(500);
+
+fn burst_spike_label(worst: Option<Duration>) -> String {
+    match worst {
+        Some(worst) => format!("Burst spike: {:.1} ms", worst.as_secs_f32() * 1000.0),
+        None => "Burst spike: - (F2)".to_string(),
+    }
+}
+
+/// Shows the worst frame time in the [`BURST_SPIKE_WINDOW`] after the last
+/// `F2` burst: the transient cost of a large batch of new contacts.
+fn update_burst_spike(
+    time: Res<Time<Real>>,
+    last_burst: Res<LastBurst>,
+    mut worst: Local<Option<Duration>>,
+    mut query: Query<&mut Text, With<BurstSpikeText>>,
+) {
+    let Some(fired) = last_burst.0 else {
+        return;
+    };
+    // The firing frame only queued the spawns; measure from the next one.
+    if last_burst.is_changed() {
+        *worst = None;
+        return;
+    }
+    if time.elapsed().saturating_sub(fired) > BURST_SPIKE_WINDOW {
+        return;
+    }
+
+    *worst = Some(worst.map_or(time.delta(), |worst| worst.max(time.delta())));
+    for mut text in &mut query {
+        **text = burst_spike_label(*worst);
+    }
+}
+
+/// Resets and ticks `WarmupTimer`. Detects state changes via `Changed<State>` so
+/// a single system covers all modes without 4× `OnEnter` registrations.
+fn tick_warmup_timer(
+    state: Res<State<PhysicsMode>>,
+    mut warmup: ResMut<WarmupTimer>,
+    time: Res<Time<Real>>,
+) {
+    if state.is_changed() {
+        warmup.0.reset();
+    }
+    warmup.0.tick(time.delta());
+}
+
+/// Starts the warmup over, for a reset that empties the pool without leaving the mode.
+fn restart_warmup_timer(mut warmup: ResMut<WarmupTimer>) {
+    warmup.0.reset();
+}
+
+fn record_frame_time(time: Res<Time<Real>>, mut frame_times: ResMut<FrameTimeWindow>) {
+    frame_times.push(time.delta());
+}
+
+fn update_fps_display(
+    diagnostics: Res<DiagnosticsStore>,
+    ball_count: Res<BallCount>,
+    frame_times: Res<FrameTimeWindow>,
+    step_time: Res<PhysicsStepTime>,
+    time: Res<Time<Real>>,
+    mut stats: ResMut<PerfStats>,
+    warmup: Res<WarmupTimer>,
+    target: Res<TargetFps>,
+    mut frozen: ResMut<SpawnFrozen>,
+    state: Res<State<PhysicsMode>>,
+    clipped: Res<ClippedBallCount>,
+    served: Option<Res<serve::SharedStats>>,
+    partner_ran: Option<Res<split::PartnerRan>>,
+    mut query: Query<&mut Text, With<FpsDisplayText>>,
+) {
+    let diag = diagnostics.get(&FrameTimeDiagnosticsPlugin::FPS);
+    let fps = diag.and_then(|d| d.value()).unwrap_or(0.0);
+    let fps_avg = diag.and_then(|d| d.average()).unwrap_or(0.0);
+    let p50 = frame_times.percentile_ms(50.0).unwrap_or(0.0);
+    let p99 = frame_times.percentile_ms(99.0).unwrap_or(0.0);
+    let physics_ms = step_time.last.as_secs_f32() * 1000.0;
+    let frame_ms = time.delta_secs() * 1000.0;
+    let balls = ball_count.0;
+
+    // Record milestones on first crossing, but only after the warmup and while no
+    // other engine shares the frame. Milestones are read through `Deref` so the
+    // stats only count as changed when one latches.
+    if warmup.0.elapsed() >= warmup.0.duration() && split::solo_run(partner_ran) {
+        for index in 0..stats.below.len() {
+            let FpsMilestone {
+                threshold,
+                first,
+                avg,
+            } = stats.below[index];
+            let threshold = f64::from(threshold);
+            if fps < threshold && fps > 0.0 && first.is_none() {
+                stats.below[index].first = Some(balls);
+            }
+            if fps_avg < threshold && fps_avg > 0.0 && avg.is_none() {
+                stats.below[index].avg = Some(balls);
+            }
+        }
+        // Only judge p99 over a full window so a few slow frames can't dominate it.
+        if frame_times.is_full() && p99 > P99_STUTTER_MS && stats.p99_above_33.is_none() {
+            stats.p99_above_33 = Some(balls);
+        }
+        if let Some(target) = target.0
+            && fps_avg < target as f64
+            && fps_avg > 0.0
+            && stats.balls_at_target.is_none()
+        {
+            stats.balls_at_target = Some(balls);
+            frozen.0 = true;
+        }
+    }
+
+    let display = format!(
+        "FPS:  {fps:.0}\nAvg:  {fps_avg:.0}\np50:  {p50:.1} ms\np99:  {p99:.1} ms\nPhysics: {physics_ms:.1} ms / Frame: {frame_ms:.1} ms\n\n{}",
+        milestone_lines(&stats),
+    );
+
+    for mut text in &mut query {
+        **text = display.clone();
+    }
+
+    if let Some(served) = served {
+        served.publish(serve::StatsSnapshot {
+            mode: *state.get(),
+            fps,
+            avg_fps: fps_avg,
+            ball_count: balls,
+            clipped: clipped.0,
+            milestones: stats.clone(),
+        });
+    }
+}
+
+/// The HUD's milestone block: first and average drop below every threshold, then
+/// the p99 stutter milestone, with labels padded to one column.
+fn milestone_lines(stats: &PerfStats) -> String {
+    let fmt = |opt: Option<usize>| opt.map_or_else(|| "-".to_string(), |n| format!("{n} balls"));
+    let mut lines: Vec<String> = Vec::new();
+    for milestone in &stats.below {
+        lines.push(format!(
+            "{:<12}{}",
+            format!("First <{}:", milestone.threshold),
+            fmt(milestone.first)
+        ));
+        lines.push(format!(
+            "{:<12}{}",
+            format!("Avg <{}:", milestone.threshold),
+            fmt(milestone.avg)
+        ));
+    }
+    lines.push(format!("{:<12}{}", "p99 >33ms:", fmt(stats.p99_above_33)));
+    lines.join("\n")
+}
+
+fn target_fps_label(target: TargetFps, balls_at_target: Option<usize>) -> String {
+    match (target.0, balls_at_target) {
+        (None, _) => String::new(),
+        (Some(fps), None) => format!("Holding {fps:.0} FPS..."),
+        (Some(fps), Some(balls)) => format!("Held {fps:.0} FPS to {} balls", thousands(balls)),
+    }
+}
+
+/// Formats `n` with `,` thousands separators, e.g. `12,400`.
+fn thousands(n: usize) -> String {
+    let digits = n.to_string();
+    let mut out = String::with_capacity(digits.len() + digits.len() / 3);
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            out.push(',');
+        }
+        out.push(digit);
+    }
+    out
+}
+
+fn update_target_fps_headline(
+    target: Res<TargetFps>,
+    stats: Res<PerfStats>,
+    mut query: Query<&mut Text, With<TargetFpsText>>,
+) {
+    if stats.is_changed() {
+        for mut text in &mut query {
+            **text = target_fps_label(*target, stats.balls_at_target);
+        }
+    }
+}
+
+/// `R` toggles debug collider rendering; `debug_render` enables it for the active
+/// backend only.
+fn handle_debug_render(
+    input: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut debug: ResMut<DebugRender>,
+    mut query: Query<&mut Text, With<DebugRenderText>>,
+) {
+    if !input.just_pressed(bindings.debug_colliders) {
+        return;
+    }
+
+    debug.0 = !debug.0;
+    for mut text in &mut query {
+        **text = if debug.0 { "DEBUG" } else { "" }.to_string();
+    }
+}
+
+/// `H` shows or hides the per-mode results history panel.
+fn toggle_history_panel(
+    input: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut query: Query<&mut Visibility, With<HistoryPanel>>,
+) {
+    if !input.just_pressed(bindings.history) {
+        return;
+    }
+
+    for mut visibility in &mut query {
+        *visibility = match *visibility {
+            Visibility::Hidden => Visibility::Inherited,
+            _ => Visibility::Hidden,
+        };
+    }
+}
+
+/// `I` shows or hides the crate version line.
+fn toggle_version_line(
+    input: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut query: Query<&mut Visibility, With<VersionText>>,
+) {
+    if !input.just_pressed(bindings.versions) {
+        return;
+    }
+
+    for mut visibility in &mut query {
+        *visibility = match *visibility {
+            Visibility::Hidden => Visibility::Inherited,
+            _ => Visibility::Hidden,
+        };
+    }
+}
+
+fn update_history_panel(
+    history: Res<BenchmarkHistory>,
+    stats: Res<PerfStats>,
+    mut query: Query<&mut Text, With<HistoryText>>,
+) {
+    if !history.is_changed() {
+        return;
+    }
+
+    for mut text in &mut query {
+        **text = benchmark::history_table(&history, &stats.thresholds());
+    }
+}
+
+/// How far above the spawn line a ball must rise before the top drain removes it.
+/// Large enough that balls stacked above the drop line by the Column/Grid patterns
+/// (one `GRID_SPACING` per extra ball in a tick) are not drained at spawn.
+const TOP_DRAIN_MARGIN: f32 = 200.0;
+
+/// Top drain: removes balls that bounced out of the pool above the spawn line and
+/// frees their slot in `BallCount`, so continuous-flow runs keep spawning.
+fn drain_top_balls(
+    mut commands: Commands,
+    mut ball_count: ResMut<BallCount>,
+    balls: Query<(Entity, &Transform), (With<Ball>, Without<SplitPartner>)>,
+    (spawn_config, pool): (Res<SpawnConfig>, Res<PoolConfig>),
+    mut drained: ResMut<DrainedBallCount>,
+    mut query: Query<&mut Text, With<DrainedBallCounterText>>,
+) {
+    let drain_y = spawn_config.y + TOP_DRAIN_MARGIN * pool.scale;
+    for (entity, transform) in &balls {
+        if transform.translation.y > drain_y {
+            commands.entity(entity).despawn();
+            ball_count.0 = ball_count.0.saturating_sub(1);
+            drained.0 += 1;
+        }
+    }
+
+    if drained.is_changed() {
+        for mut text in &mut query {
+            **text = format!("Drained (top): {}", drained.0);
+        }
+    }
+}
+
+/// How far past the pool boundary a ball must be before it counts as clipped.
+const CLIP_MARGIN: f32 = 20.0;
+
+/// Distance from the origin, in pool widths, past which a ball counts as exploded.
+const EXPLODED_POOL_WIDTHS: f32 = 10.0;
+
+/// Whether a ball at `pos` was thrown out by a numerical blow-up: a NaN or infinite
+/// coordinate, or one over [`EXPLODED_POOL_WIDTHS`] widths of `pool` from the origin.
+/// Such a ball never falls past the floor threshold, so it would otherwise linger.
+fn exploded(pos: Vec3, pool: PoolConfig) -> bool {
+    !pos.is_finite() || pos.abs().max_element() > EXPLODED_POOL_WIDTHS * pool.width
+}
+
+/// Despawns any ball that escaped the pool: below the floor's underside or, in 3D,
+/// past the front/back walls. Tracks the cumulative count via `ClippedBallCount`
+/// resource and updates the UI counter. With [`NoWalls`] every ball eventually
+/// falls that far, and is recycled to the spawn line instead.
+///
+/// Balls that [`exploded`] are despawned first, walls or not, with a warning naming
+/// the mode and ball count, and counted in `ExplodedBallCount` instead.
+fn detect_clipped_balls(
+    mut commands: Commands,
+    mut ball_count: ResMut<BallCount>,
+    balls: Query<(Entity, &Transform), (With<Ball>, Without<SplitPartner>)>,
+    (mut clipped, mut blown_up): (ResMut<ClippedBallCount>, ResMut<ExplodedBallCount>),
+    state: Res<State<PhysicsMode>>,
+    (pool, spawn_config): (Res<PoolConfig>, Res<SpawnConfig>),
+    no_walls: Res<NoWalls>,
+    mut query: Query<(&mut Text, Has<ExplodedBallCounterText>), EscapeCounterText>,
+) {
+    let floor_y = -pool.height / 2.0 - CLIP_MARGIN * pool.scale;
+    let max_z = pool.depth / 2.0 + CLIP_MARGIN * pool.scale;
+    let is_3d = matches!(state.get(), PhysicsMode::Avian3d | PhysicsMode::Rapier3d);
+
+    for (entity, transform) in &balls {
+        let pos = transform.translation;
+        if exploded(pos, *pool) {
+            warn!(
+                "Ball exploded to {pos} in {} at {} balls",
+                state.get().label(),
+                thousands(ball_count.0)
+            );
+            commands.entity(entity).despawn();
+            ball_count.0 = ball_count.0.saturating_sub(1);
+            blown_up.0 += 1;
+            continue;
+        }
+        if pos.y < floor_y || (is_3d && pos.z.abs() > max_z) {
+            if no_walls.0 {
+                spawner::recycle_ball(
+                    &mut commands,
+                    entity,
+                    *state.get(),
+                    transform,
+                    &spawn_config,
+                );
+                continue;
+            }
+            commands.entity(entity).despawn();
+            ball_count.0 = ball_count.0.saturating_sub(1);
+            clipped.0 += 1;
+        }
+    }
+
+    for (mut text, is_exploded) in &mut query {
+        let count = if is_exploded { blown_up.0 } else { clipped.0 };
+        **text = escape_label(is_exploded, count);
+    }
+}
+
+/// Whether a ball centre lies outside the pool: past a side wall, below the floor's
+/// underside or, in 3D, past the front/back walls. Balls dropping through an open
+/// drain or floor are expected to leave below the pool, so that case is ignored then.
+fn out_of_bounds(pos: Vec3, pool: PoolConfig, is_3d: bool, drain: bool) -> bool {
+    pos.x.abs() > pool.width / 2.0
+        || (!drain && pos.y < -pool.height / 2.0)
+        || (is_3d && pos.z.abs() > pool.depth / 2.0)
+}
+
+/// Counts the balls currently outside the pool bounds. Unlike the cumulative
+/// clipped counter this is an instantaneous leak gauge: a value that stays above
+/// zero means a ball is squeezed through a wall seam in the active backend.
+fn update_out_of_bounds_counter(
+    balls: Query<&Transform, (With<Ball>, Without<SplitPartner>)>,
+    state: Res<State<PhysicsMode>>,
+    pool: Res<PoolConfig>,
+    (drain, obstacles): (Res<FloorDrain>, Res<ObstacleLayout>),
+    mut query: Query<&mut Text, With<OutOfBoundsText>>,
+) {
+    let is_3d = matches!(state.get(), PhysicsMode::Avian3d | PhysicsMode::Rapier3d);
+    let drain = drain.0 || obstacles.open_floor;
+    let count = balls
+        .iter()
+        .filter(|transform| out_of_bounds(transform.translation, *pool, is_3d, drain))
+        .count();
+
+    for mut text in &mut query {
+        **text = format!("Out of bounds: {count}");
+    }
+}
+
+/// Factor the spawn rate grows (Up) or shrinks (Down) by per second the key is held.
+const SPAWN_RATE_CHANGE_PER_SEC: f32 = 4.0;
+
+fn spawn_rate_label(rate: SpawnRate) -> String {
+    let (interval, per_tick) = rate.schedule();
+    format!(
+        "Spawn rate: {:.0} balls/s ({per_tick} every {} ms)",
+        rate.0,
+        interval.as_millis()
+    )
+}
+
+/// Holding Up / Down raises or lowers the spawn rate smoothly, in real time so it
+/// still responds while paused. `spawner` derives the timer interval and batch size.
+fn handle_spawn_rate(
+    input: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    time: Res<Time<Real>>,
+    mut rate: ResMut<SpawnRate>,
+    mut query: Query<&mut Text, With<SpawnRateText>>,
+) {
+    let direction = match (
+        input.pressed(bindings.spawn_rate_up),
+        input.pressed(bindings.spawn_rate_down),
+    ) {
+        (true, false) => 1.0,
+        (false, true) => -1.0,
+        _ => return,
+    };
+
+    let factor = SPAWN_RATE_CHANGE_PER_SEC.powf(direction * time.delta_secs());
+    rate.0 = (rate.0 * factor).clamp(SpawnRate::MIN, SpawnRate::MAX);
+    for mut text in &mut query {
+        **text = spawn_rate_label(*rate);
+    }
+}
+
+/// Multiplier applied per `[` / `]` press.
+const GRAVITY_SCALE_STEP: f32 = 1.25;
+
+fn gravity_label(scale: GravityScale) -> String {
+    format!("Gravity: {:.2}x", scale.0)
+}
+
+/// `[` / `]` weaken or strengthen gravity. `backend::apply_gravity` pushes the
+/// new scale into all four engines.
+fn handle_gravity(
+    input: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut gravity_scale: ResMut<GravityScale>,
+    mut query: Query<&mut Text, With<GravityText>>,
+) {
+    // `Shift` / `Ctrl` + bracket adjust Rapier's solver iterations instead.
+    if input.any_pressed([
+        KeyCode::ShiftLeft,
+        KeyCode::ShiftRight,
+        KeyCode::ControlLeft,
+        KeyCode::ControlRight,
+    ]) {
+        return;
+    }
+    let factor = if input.just_pressed(bindings.gravity_up) {
+        GRAVITY_SCALE_STEP
+    } else if input.just_pressed(bindings.gravity_down) {
+        GRAVITY_SCALE_STEP.recip()
+    } else {
+        return;
+    };
+
+    gravity_scale.0 = (gravity_scale.0 * factor).clamp(GravityScale::MIN, GravityScale::MAX);
+    for mut text in &mut query {
+        **text = gravity_label(*gravity_scale);
+    }
+}
+
+/// Degrees the gravity vector rotates per `Q` / `E` press.
+const GRAVITY_ANGLE_STEP: f32 = 5.0;
+
+fn gravity_angle_label(angle: GravityAngle) -> String {
+    format!("Tilt: {:+.0}°", angle.0)
+}
+
+/// `Q` / `E` tilt gravity towards -X / +X to slosh the pile sideways.
+/// The angle wraps into -180°..180°.
+fn handle_gravity_direction(
+    input: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut angle: ResMut<GravityAngle>,
+    mut query: Query<&mut Text, With<GravityAngleText>>,
+) {
+    let step = if input.just_pressed(bindings.tilt_right) {
+        GRAVITY_ANGLE_STEP
+    } else if input.just_pressed(bindings.tilt_left) {
+        -GRAVITY_ANGLE_STEP
+    } else {
+        return;
+    };
+
+    angle.0 = (angle.0 + step + 180.0).rem_euclid(360.0) - 180.0;
+    for mut text in &mut query {
+        **text = gravity_angle_label(*angle);
+    }
+}
+
+/// Restitution change per `;` / `'` press.
+const RESTITUTION_STEP: f32 = 0.1;
+
+/// Friction change per `Shift` + `;` / `'` press.
+const FRICTION_STEP: f32 = 0.1;
+const FRICTION_MAX: f32 = 2.0;
+
+fn restitution_label(restitution: BallRestitution) -> String {
+    format!("Restitution: {:.2}", restitution.0)
+}
+
+fn friction_label(friction: BallFriction) -> String {
+    format!("Friction: {:.2}", friction.0)
+}
+
+/// `;` / `'` decrease or increase restitution (0–1); with `Shift` held they adjust
+/// friction (0–2) instead. Only newly spawned balls pick up the new values;
+/// walls pick them up on the next mode enter.
+fn handle_surface(
+    input: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut restitution: ResMut<BallRestitution>,
+    mut friction: ResMut<BallFriction>,
+    mut restitution_text: Query<&mut Text, (With<RestitutionText>, Without<FrictionText>)>,
+    mut friction_text: Query<&mut Text, (With<FrictionText>, Without<RestitutionText>)>,
+) {
+    let direction = if input.just_pressed(bindings.restitution_up) {
+        1.0
+    } else if input.just_pressed(bindings.restitution_down) {
+        -1.0
+    } else {
+        return;
+    };
+
+    if input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        friction.0 = (friction.0 + direction * FRICTION_STEP).clamp(0.0, FRICTION_MAX);
+        for mut text in &mut friction_text {
+            **text = friction_label(*friction);
+        }
+    } else {
+        restitution.0 = (restitution.0 + direction * RESTITUTION_STEP).clamp(0.0, 1.0);
+        for mut text in &mut restitution_text {
+            **text = restitution_label(*restitution);
+        }
+    }
+}
+
+/// Damping change per `K` / `L` press, and its upper bound.
+const DAMPING_STEP: f32 = 0.1;
+const DAMPING_MAX: f32 = 5.0;
+
+fn damping_label(linear: BallLinearDamping, angular: BallAngularDamping) -> String {
+    format!("Damping: {:.1} lin / {:.1} ang", linear.0, angular.0)
+}
+
+/// `K` / `L` decrease or increase linear damping of new balls; with `Shift` held
+/// they adjust angular damping instead. Damping sets how fast a pile settles and
+/// goes to sleep, and with it how soon FPS recovers.
+fn handle_damping(
+    input: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut linear: ResMut<BallLinearDamping>,
+    mut angular: ResMut<BallAngularDamping>,
+    mut query: Query<&mut Text, With<DampingText>>,
+) {
+    // `Ctrl + L` toggles the camera lock instead.
+    if input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        return;
+    }
+    let direction = if input.just_pressed(bindings.damping_up) {
+        1.0
+    } else if input.just_pressed(bindings.damping_down) {
+        -1.0
+    } else {
+        return;
+    };
+
+    let damping = if input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        &mut angular.0
+    } else {
+        &mut linear.0
+    };
+    *damping = (*damping + direction * DAMPING_STEP).clamp(0.0, DAMPING_MAX);
+    for mut text in &mut query {
+        **text = damping_label(*linear, *angular);
+    }
+}
+
+/// Density factor per `Y` / `U` press, and its bounds.
+const DENSITY_STEP: f32 = 2.0;
+const DENSITY_MIN: f32 = 1.0 / 64.0;
+const DENSITY_MAX: f32 = 64.0;
+
+fn density_label(density: BallDensity) -> String {
+    // Plain `Display`: the powers of two the keys step through print exactly.
+    format!("Density: {}", density.0)
+}
+
+/// `Y` / `U` halve or double the ball density. Unlike the other ball settings this
+/// reaches the balls already in the pool too (see `spawner::apply_ball_density`):
+/// heavier balls press harder on the pile, which changes how the engines cope with
+/// deep stacks.
+fn handle_density(
+    input: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut density: ResMut<BallDensity>,
+    mut query: Query<&mut Text, With<DensityText>>,
+) {
+    let factor = if input.just_pressed(bindings.density_up) {
+        DENSITY_STEP
+    } else if input.just_pressed(bindings.density_down) {
+        1.0 / DENSITY_STEP
+    } else {
+        return;
+    };
+
+    density.0 = (density.0 * factor).clamp(DENSITY_MIN, DENSITY_MAX);
+    for mut text in &mut query {
+        **text = density_label(*density);
+    }
+}
+
+fn shape_label(shape: SpawnShape) -> String {
+    format!("Shape: {}", shape.label())
+}
+
+/// `S` cycles the shape of newly spawned balls (Circle → Box → Capsule → Ngon).
+fn handle_spawn_shape(
+    input: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    sides: Res<NgonSides>,
+    mut shape: ResMut<SpawnShape>,
+    mut query: Query<&mut Text, With<ShapeText>>,
+) {
+    // `Ctrl + S` pans the locked camera instead.
+    if !input.just_pressed(bindings.shape)
+        || input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight])
+    {
+        return;
+    }
+
+    *shape = shape.next(*sides);
+    for mut text in &mut query {
+        **text = shape_label(*shape);
+    }
+}
+
+/// The bi-disperse pattern also shows how many balls of each size are live.
+fn pattern_label(pattern: SpawnPattern, mix: SizeMix) -> String {
+    match pattern {
+        SpawnPattern::BiDisperse => format!(
+            "Pattern: {} ({} small / {} large)",
+            pattern.label(),
+            mix.small,
+            mix.large
+        ),
+        _ => format!("Pattern: {}", pattern.label()),
+    }
+}
+
+/// `G` cycles where timed balls are dropped (Rain → Grid → Column → Chain → Bi-disperse).
+fn handle_spawn_pattern(
+    input: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut pattern: ResMut<SpawnPattern>,
 ) {
-    walls::spawn_walls(&mut commands, &mut meshes, &mut materials, *state.get());
+    if input.just_pressed(bindings.pattern) {
+        *pattern = pattern.next();
+    }
 }
 
-fn reset_ball_count(mut ball_count: ResMut<BallCount>) {
-    ball_count.0 = 0;
+/// Refreshed when the pattern changes or, under the bi-disperse pattern, the size counts.
+fn update_pattern_display(
+    pattern: Res<SpawnPattern>,
+    mix: Res<SizeMix>,
+    mut query: Query<&mut Text, With<PatternText>>,
+) {
+    if !pattern.is_changed() && !mix.is_changed() {
+        return;
+    }
+    let label = pattern_label(*pattern, *mix);
+    for mut text in &mut query {
+        **text = label.clone();
+    }
 }
 
-fn reset_clipped_ball_count(
-    mut clipped: ResMut<ClippedBallCount>,
-    mut query: Query<&mut Text, With<ClippedBallCounterText>>,
+fn nozzle_label(nozzles: NozzleCount) -> String {
+    match nozzles.0 {
+        0 => "Nozzles: off".to_string(),
+        n => format!("Nozzles: {n}"),
+    }
+}
+
+/// `O` / `P` remove or add a rain nozzle (0 = anywhere in the range, up to
+/// [`NozzleCount::MAX`]).
+fn handle_nozzles(
+    input: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut nozzles: ResMut<NozzleCount>,
+    mut query: Query<&mut Text, With<NozzleText>>,
 ) {
-    clipped.0 = 0;
+    let count = if input.just_pressed(bindings.nozzles_up) {
+        (nozzles.0 + 1).min(NozzleCount::MAX)
+    } else if input.just_pressed(bindings.nozzles_down) {
+        nozzles.0.saturating_sub(1)
+    } else {
+        return;
+    };
+
+    nozzles.0 = count;
     for mut text in &mut query {
-        **text = "Clipped balls: 0".to_string();
+        **text = nozzle_label(*nozzles);
     }
 }
 
-fn reset_perf_stats(mut stats: ResMut<PerfStats>) {
-    *stats = PerfStats::default();
+fn color_by_speed_label(
+    color_by_speed: ColorBySpeed,
+    color_by_mass: ColorByMass,
+    color_by_sleep: ColorBySleep,
+) -> String {
+    let color = if color_by_speed.0 {
+        "speed"
+    } else if color_by_mass.0 {
+        "mass"
+    } else if color_by_sleep.0 {
+        "sleep"
+    } else {
+        "flat"
+    };
+    format!("Color: {color}")
 }
 
-fn pause_simulation(mut vtime: ResMut<Time<Virtual>>) {
-    vtime.pause();
+/// `V` toggles the speed heat-map, `Shift + V` the mass gradient and `F7` the
+/// sleeping-ball tint; switching one on switches the others off. All cost FPS (see
+/// `speed_color`), so the HUD always shows whether one is on.
+fn handle_color_by_speed(
+    input: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut color_by_speed: ResMut<ColorBySpeed>,
+    mut color_by_mass: ResMut<ColorByMass>,
+    mut color_by_sleep: ResMut<ColorBySleep>,
+    mut query: Query<&mut Text, With<ColorBySpeedText>>,
+) {
+    let shift = input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    if input.just_pressed(bindings.color_by_sleep) {
+        color_by_sleep.0 = !color_by_sleep.0;
+        color_by_speed.0 = false;
+        color_by_mass.0 = false;
+    } else if input.just_pressed(bindings.color_by_speed) && shift {
+        color_by_mass.0 = !color_by_mass.0;
+        color_by_speed.0 = false;
+        color_by_sleep.0 = false;
+    } else if input.just_pressed(bindings.color_by_speed) {
+        color_by_speed.0 = !color_by_speed.0;
+        color_by_mass.0 = false;
+        color_by_sleep.0 = false;
+    } else {
+        return;
+    }
+    for mut text in &mut query {
+        **text = color_by_speed_label(*color_by_speed, *color_by_mass, *color_by_sleep);
+    }
 }
 
-fn update_mode_text(state: Res<State<PhysicsMode>>, mut query: Query<&mut Text, With<ModeText>>) {
+fn floor_drain_label(drain: FloorDrain) -> String {
+    format!("Floor drain: {}", if drain.0 { "open" } else { "closed" })
+}
+
+/// `B` opens or closes a gap in the middle of the floor. The floor is rebuilt in
+/// place; balls that fall through are counted by `detect_clipped_balls`.
+fn handle_floor_drain(
+    input: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    state: Res<State<PhysicsMode>>,
+    ball_config: BallConfig,
+    pool: Res<PoolConfig>,
+    floors: Query<Entity, With<walls::Floor>>,
+    mut drain: ResMut<FloorDrain>,
+    (no_walls, obstacles, thickness, mono): (
+        Res<NoWalls>,
+        Res<ObstacleLayout>,
+        Res<FloorThickness>,
+        Res<MonoWalls>,
+    ),
+    mut query: Query<&mut Text, With<FloorDrainText>>,
+) {
+    if !input.just_pressed(bindings.floor_drain) {
+        return;
+    }
+
+    drain.0 = !drain.0;
+    for floor in &floors {
+        commands.entity(floor).despawn();
+    }
+    // Without walls or a floor the setting only takes effect once the pool is rebuilt.
+    // `WallConfig::has_floor` would read the drain this system writes.
+    if !no_walls.0 && !obstacles.open_floor {
+        walls::spawn_floor(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            *pool,
+            *state.get(),
+            ball_config.surface(),
+            drain.0,
+            *thickness,
+            *mono,
+        );
+    }
     for mut text in &mut query {
-        **text = state.get().label().to_string();
+        **text = floor_drain_label(*drain);
     }
 }
 
-// ── Update systems ────────────────────────────────────────────────────────────
+fn floor_thickness_label(thickness: FloorThickness) -> String {
+    format!("Floor: {:.1} px", thickness.0)
+}
 
-/// Adjusts camera zoom / position whenever the primary window is resized so the
-/// whole physics pool always fits inside the window without being clipped.
-///
-/// Uses `Changed<Window>` rather than `WindowResized` events so it works both
-/// in real windowed builds (where WinitPlugin mutates the component on resize)
-/// and in headless tests (where `Window` can be mutated directly).
-///
-/// - 2D camera: updates the orthographic projection scale.
-/// - 3D camera: scales its distance from the look-at point along the fixed
-///   view direction, which is equivalent to perspective zoom.
-fn fit_camera_to_pool(
-    windows: Query<&Window, (With<PrimaryWindow>, Changed<Window>)>,
-    mut cam2d: Query<&mut Projection, With<Camera2d>>,
-    mut cam3d: Query<&mut Transform, With<Camera3d>>,
+/// `PageDown` / `PageUp` halve or double the floor's thickness, rebuilding it in place
+/// like the drain toggle. Balls resting on it are pushed up as it grows.
+fn handle_floor_thickness(
+    input: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    state: Res<State<PhysicsMode>>,
+    ball_config: BallConfig,
+    pool: Res<PoolConfig>,
+    floors: Query<Entity, With<walls::Floor>>,
+    mut thickness: ResMut<FloorThickness>,
+    (drain, no_walls, obstacles, mono): (
+        Res<FloorDrain>,
+        Res<NoWalls>,
+        Res<ObstacleLayout>,
+        Res<MonoWalls>,
+    ),
+    mut query: Query<&mut Text, With<FloorThicknessText>>,
 ) {
-    let Ok(window) = windows.single() else {
-        return; // no change this frame
+    let thicker = if input.just_pressed(bindings.floor_thickness_up) {
+        true
+    } else if input.just_pressed(bindings.floor_thickness_down) {
+        false
+    } else {
+        return;
     };
-    let (w, h) = (window.width(), window.height());
 
-    for mut proj in &mut cam2d {
-        if let Projection::Orthographic(ref mut ortho) = *proj {
-            ortho.scale = ortho_scale_for_window(w, h);
-        }
+    let stepped = thickness.step(thicker);
+    if !thickness.set_if_neq(stepped) {
+        return;
     }
-
-    for mut transform in &mut cam3d {
-        transform.translation = cam3d_pos_for_window(w, h);
+    for floor in &floors {
+        commands.entity(floor).despawn();
+    }
+    if !no_walls.0 && !obstacles.open_floor {
+        walls::spawn_floor(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            *pool,
+            *state.get(),
+            ball_config.surface(),
+            drain.0,
+            *thickness,
+            *mono,
+        );
+    }
+    for mut text in &mut query {
+        **text = floor_thickness_label(*thickness);
     }
 }
 
-fn toggle_pause(keys: Res<ButtonInput<KeyCode>>, mut time: ResMut<Time<Virtual>>) {
-    if keys.just_pressed(KeyCode::Space) {
-        if time.is_paused() {
-            time.unpause();
-        } else {
-            time.pause();
+fn top_wall_label(top_wall: TopWall) -> String {
+    format!("Top wall: {}", if top_wall.0 { "sealed" } else { "open" })
+}
+
+/// `T` seals the pool with a top wall or removes it again. While sealed the timed
+/// spawner pauses; balls already in the pool stay and get compressed under the lid.
+fn handle_top_wall(
+    input: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    state: Res<State<PhysicsMode>>,
+    ball_config: BallConfig,
+    pool: Res<PoolConfig>,
+    lids: Query<Entity, With<walls::Lid>>,
+    mut top_wall: ResMut<TopWall>,
+    (no_walls, mono): (Res<NoWalls>, Res<MonoWalls>),
+    mut query: Query<&mut Text, With<TopWallText>>,
+) {
+    if !input.just_pressed(bindings.top_wall) {
+        return;
+    }
+
+    top_wall.0 = !top_wall.0;
+    if top_wall.0 && !no_walls.0 {
+        walls::spawn_top_wall(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            *pool,
+            *state.get(),
+            ball_config.surface(),
+            *mono,
+        );
+    } else {
+        for lid in &lids {
+            commands.entity(lid).despawn();
         }
     }
+    for mut text in &mut query {
+        **text = top_wall_label(*top_wall);
+    }
 }
 
-fn update_ball_counter(
-    ball_count: Res<BallCount>,
-    mut query: Query<&mut Text, With<BallCounterText>>,
+fn no_walls_label(no_walls: NoWalls) -> String {
+    format!(
+        "Walls: {}",
+        if no_walls.0 { "off (free fall)" } else { "on" }
+    )
+}
+
+/// `W` removes the whole pool or builds it again (see [`restore_walls`]). Without
+/// walls the balls fall forever and [`detect_clipped_balls`] recycles them to the
+/// spawn line, so no contacts form and the frame time is integration alone.
+/// `Ctrl + W` pans the locked camera instead.
+fn handle_no_walls(
+    input: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut commands: Commands,
+    pool: Query<Entity, With<walls::Wall>>,
+    mut no_walls: ResMut<NoWalls>,
+    mut query: Query<&mut Text, With<NoWallsText>>,
 ) {
-    if ball_count.is_changed() {
-        for mut text in &mut query {
-            **text = format!("Balls: {}", ball_count.0);
+    if !input.just_pressed(bindings.no_walls)
+        || input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight])
+    {
+        return;
+    }
+
+    no_walls.0 = !no_walls.0;
+    if no_walls.0 {
+        for wall in &pool {
+            commands.entity(wall).despawn();
         }
     }
+    for mut text in &mut query {
+        **text = no_walls_label(*no_walls);
+    }
 }
 
-/// Resets and ticks `WarmupTimer`. Detects state changes via `Changed<State>` so
-/// a single system covers all modes without 4× `OnEnter` registrations.
-fn tick_warmup_timer(
+/// Rebuilds the active mode's pool once [`handle_no_walls`] turns the walls back on.
+/// A separate system because [`WallConfig`] reads [`NoWalls`] itself.
+fn restore_walls(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
     state: Res<State<PhysicsMode>>,
-    mut warmup: ResMut<WarmupTimer>,
-    time: Res<Time<Real>>,
+    no_walls: Res<NoWalls>,
+    ball_config: BallConfig,
+    wall_config: WallConfig,
 ) {
-    if state.is_changed() {
-        warmup.0 = Timer::new(PERF_WARMUP, TimerMode::Once);
+    if !no_walls.is_changed() || no_walls.is_added() || no_walls.0 {
+        return;
     }
-    warmup.0.tick(time.delta());
+    wall_config.spawn(
+        &mut commands,
+        &mut meshes,
+        &mut materials,
+        *state.get(),
+        ball_config.surface(),
+    );
 }
 
-fn update_fps_display(
-    diagnostics: Res<DiagnosticsStore>,
-    ball_count: Res<BallCount>,
-    mut stats: ResMut<PerfStats>,
-    warmup: Res<WarmupTimer>,
-    mut query: Query<&mut Text, With<FpsDisplayText>>,
+fn present_mode_label(present_mode: PresentMode) -> String {
+    let vsync = matches!(
+        present_mode,
+        PresentMode::AutoVsync | PresentMode::Fifo | PresentMode::FifoRelaxed
+    );
+    format!("VSync: {}", if vsync { "on" } else { "off (uncapped)" })
+}
+
+/// `F` switches the window between VSync and an uncapped present mode. With VSync
+/// the FPS never rises above the refresh rate, so the "first below 50" milestone
+/// only fires once the machine can't keep up with the display; uncapped shows the
+/// real headroom. `AutoNoVsync` picks `Immediate` and falls back to `Mailbox` on
+/// platforms without it.
+fn handle_present_mode(
+    input: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+    mut query: Query<&mut Text, With<PresentModeText>>,
 ) {
-    let diag = diagnostics.get(&FrameTimeDiagnosticsPlugin::FPS);
-    let fps = diag.and_then(|d| d.value()).unwrap_or(0.0);
-    let fps_avg = diag.and_then(|d| d.average()).unwrap_or(0.0);
-    let balls = ball_count.0;
+    if !input.just_pressed(bindings.vsync) {
+        return;
+    }
+    let Ok(mut window) = windows.single_mut() else {
+        return;
+    };
 
-    // Record milestones on first crossing, but only after the 300 ms warmup.
-    if warmup.0.elapsed() >= warmup.0.duration() {
-        if fps < 50.0 && fps > 0.0 && stats.first_below_50.is_none() {
-            stats.first_below_50 = Some(balls);
-        }
-        if fps_avg < 50.0 && fps_avg > 0.0 && stats.avg_below_50.is_none() {
-            stats.avg_below_50 = Some(balls);
-        }
-        if fps < 15.0 && fps > 0.0 && stats.first_below_15.is_none() {
-            stats.first_below_15 = Some(balls);
-        }
-        if fps_avg < 15.0 && fps_avg > 0.0 && stats.avg_below_15.is_none() {
-            stats.avg_below_15 = Some(balls);
+    window.present_mode = match window.present_mode {
+        PresentMode::AutoNoVsync | PresentMode::Immediate | PresentMode::Mailbox => {
+            PresentMode::AutoVsync
         }
+        _ => PresentMode::AutoNoVsync,
+    };
+    for mut text in &mut query {
+        **text = present_mode_label(window.present_mode);
     }
+}
 
-    let fmt = |opt: Option<usize>| -> String {
-        opt.map_or_else(|| "-".to_string(), |n| format!("{n} balls"))
-    };
+fn ccd_label(ccd: BallCcd) -> String {
+    format!("CCD: {}", if ccd.0 { "on" } else { "off" })
+}
 
-    let display = format!(
-        "FPS:  {fps:.0}\nAvg:  {fps_avg:.0}\n\nFirst <50:  {}\nAvg <50:    {}\nFirst <15:  {}\nAvg <15:    {}",
-        fmt(stats.first_below_50),
-        fmt(stats.avg_below_50),
-        fmt(stats.first_below_15),
-        fmt(stats.avg_below_15),
-    );
+/// `X` toggles continuous collision detection for newly spawned balls.
+/// CCD costs FPS in both engines, so the HUD always shows whether it is on.
+fn handle_ccd(
+    input: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut ccd: ResMut<BallCcd>,
+    mut query: Query<&mut Text, With<CcdText>>,
+) {
+    if !input.just_pressed(bindings.ccd) {
+        return;
+    }
 
+    ccd.0 = !ccd.0;
     for mut text in &mut query {
-        **text = display.clone();
+        **text = ccd_label(*ccd);
     }
 }
 
-/// Despawns any ball whose Y falls below the screen bottom (clipped through the floor).
-/// Tracks the cumulative count via `ClippedBallCount` resource and updates the UI counter.
-fn detect_clipped_balls(
-    mut commands: Commands,
-    mut ball_count: ResMut<BallCount>,
-    balls: Query<(Entity, &Transform), With<Ball>>,
-    mut clipped: ResMut<ClippedBallCount>,
-    mut query: Query<&mut Text, With<ClippedBallCounterText>>,
+fn render_balls_label(render: RenderBalls) -> String {
+    format!("Render balls: {}", if render.0 { "on" } else { "off" })
+}
+
+/// `M` toggles ball rendering. `spawner` strips or restores the visuals of the balls
+/// already in the pool; compare FPS either way to split draw cost from physics cost.
+fn handle_render_balls(
+    input: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut render: ResMut<RenderBalls>,
+    mut query: Query<&mut Text, With<RenderBallsText>>,
 ) {
-    // Screen bottom is at -540 (HEIGHT / 2 = 540).
-    const FLOOR_Y: f32 = -540.0;
+    if !input.just_pressed(bindings.render_balls) {
+        return;
+    }
 
-    for (entity, transform) in &balls {
-        if transform.translation.y < FLOOR_Y {
-            commands.entity(entity).despawn();
-            ball_count.0 = ball_count.0.saturating_sub(1);
-            clipped.0 += 1;
+    render.0 = !render.0;
+    for mut text in &mut query {
+        **text = render_balls_label(*render);
+    }
+}
+
+/// `Escape` exits cleanly; `benchmark` logs the active mode's run on the way out.
+fn handle_quit(
+    input: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut exit: MessageWriter<AppExit>,
+) {
+    if input.just_pressed(bindings.quit) {
+        exit.write(AppExit::Success);
+    }
+}
+
+fn spin_label(spin: InitialSpin) -> String {
+    let InitialSpin(min, max) = spin;
+    if min == max {
+        if min == 0.0 {
+            "Spin: off".to_string()
+        } else {
+            format!("Spin: {min:.1} rad/s")
         }
+    } else {
+        format!("Spin: {min:.1} to {max:.1} rad/s")
     }
+}
 
-    for mut text in &mut query {
-        **text = format!("Clipped balls: {}", clipped.0);
+/// The spin range only comes from the command line; this shows it once it is set.
+fn update_spin_display(spin: Res<InitialSpin>, mut query: Query<&mut Text, With<SpinText>>) {
+    if spin.is_changed() {
+        for mut text in &mut query {
+            **text = spin_label(*spin);
+        }
+    }
+}
+
+fn substeps_label(substeps: Substeps, mode: PhysicsMode) -> String {
+    match substeps.0 {
+        Some(n) => format!("Substeps: {n}"),
+        None => format!("Substeps: native ({})", Substeps::native(mode)),
     }
 }
 
-/// Up/Down arrows increase or decrease balls spawned per tick (min 1).
-fn handle_balls_per_tick(
+/// `,` / `.` decrease or increase solver substeps for both engines. The first
+/// press starts from the active engine's native default.
+fn handle_substeps(
     input: Res<ButtonInput<KeyCode>>,
-    mut balls_per_tick: ResMut<BallsPerTick>,
-    mut query: Query<&mut Text, With<BallsPerTickText>>,
+    bindings: Res<KeyBindings>,
+    state: Res<State<PhysicsMode>>,
+    mut substeps: ResMut<Substeps>,
+    mut query: Query<&mut Text, With<SubstepsText>>,
 ) {
-    let changed = if input.just_pressed(KeyCode::ArrowUp) {
-        balls_per_tick.0 += 1;
-        true
-    } else if input.just_pressed(KeyCode::ArrowDown) {
-        balls_per_tick.0 = balls_per_tick.0.saturating_sub(1).max(1);
-        true
+    let mode = *state.get();
+    let current = substeps.0.unwrap_or_else(|| Substeps::native(mode));
+    if input.just_pressed(bindings.substeps_up) {
+        substeps.0 = Some((current + 1).min(Substeps::MAX));
+    } else if input.just_pressed(bindings.substeps_down) {
+        substeps.0 = Some(current.saturating_sub(1).max(1));
+    }
+
+    // The native label depends on the active engine, so refresh on mode change too.
+    if substeps.is_changed() || state.is_changed() {
+        for mut text in &mut query {
+            **text = substeps_label(*substeps, mode);
+        }
+    }
+}
+
+fn rapier_iterations_label(iterations: RapierIterations, mode: PhysicsMode) -> String {
+    match mode {
+        PhysicsMode::Rapier2d | PhysicsMode::Rapier3d => format!(
+            "Velocity iters: {}  Stabilization iters: {}",
+            iterations.velocity, iterations.stabilization
+        ),
+        PhysicsMode::Avian2d | PhysicsMode::Avian3d => "Velocity iters: n/a (Avian)".to_string(),
+    }
+}
+
+/// `Shift` + `[` / `]` decrease or increase Rapier's PGS velocity iterations and
+/// `Ctrl` + `[` / `]` its stabilization (position) iterations, both run inside each
+/// solver substep. Tuning these to match Avian's pile quality before comparing FPS
+/// keeps the benchmark fair. Inert in the Avian modes, whose solver has neither loop.
+fn handle_rapier_iterations(
+    input: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    state: Res<State<PhysicsMode>>,
+    mut iterations: ResMut<RapierIterations>,
+    mut query: Query<&mut Text, With<RapierIterationsText>>,
+) {
+    let mode = *state.get();
+    let rapier = matches!(mode, PhysicsMode::Rapier2d | PhysicsMode::Rapier3d);
+    let step: isize = if input.just_pressed(bindings.gravity_up) {
+        1
+    } else if input.just_pressed(bindings.gravity_down) {
+        -1
     } else {
-        false
+        0
     };
 
-    if changed {
+    if rapier && step != 0 {
+        let count = if input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+            Some(&mut iterations.velocity)
+        } else if input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+            Some(&mut iterations.stabilization)
+        } else {
+            None
+        };
+        if let Some(count) = count {
+            *count = count
+                .saturating_add_signed(step)
+                .clamp(1, RapierIterations::MAX);
+        }
+    }
+
+    // The label depends on the active engine, so refresh on mode change too.
+    if iterations.is_changed() || state.is_changed() {
         for mut text in &mut query {
-            **text = format!("Balls/tick: {}", balls_per_tick.0);
+            **text = rapier_iterations_label(*iterations, mode);
         }
     }
 }
 
+/// Bounds for the `Time<Virtual>` relative speed set by [`handle_time_scale`].
+const MIN_TIME_SCALE: f32 = 0.1;
+const MAX_TIME_SCALE: f32 = 4.0;
+const TIME_SCALE_STEP: f32 = 0.1;
+
+fn time_scale_label(scale: f32) -> String {
+    format!("Speed: {scale:.1}x")
+}
+
+/// `-` / `=` slow down or speed up virtual time; `0` resets to real time.
+/// Physics and the ball spawn timer both run on `Time<Virtual>`, so slow motion
+/// keeps the spawn density per simulated second unchanged.
+fn handle_time_scale(
+    input: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut vtime: ResMut<Time<Virtual>>,
+    mut query: Query<&mut Text, With<TimeScaleText>>,
+) {
+    let current = vtime.relative_speed();
+    let scale = if input.just_pressed(bindings.speed_up) {
+        current + TIME_SCALE_STEP
+    } else if input.just_pressed(bindings.speed_down) {
+        current - TIME_SCALE_STEP
+    } else if input.just_pressed(bindings.speed_reset) {
+        1.0
+    } else {
+        return;
+    };
+
+    let scale = scale.clamp(MIN_TIME_SCALE, MAX_TIME_SCALE);
+    vtime.set_relative_speed(scale);
+    for mut text in &mut query {
+        **text = time_scale_label(scale);
+    }
+}
+
 /// Keys 1-4 jump to a specific mode; Enter cycles to the next one.
 /// The transition is immediate; `OnEnter` handles pausing and timer reset.
 fn handle_mode_switch(
     input: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
     state: Res<State<PhysicsMode>>,
     mut next_state: ResMut<NextState<PhysicsMode>>,
 ) {
-    let new_mode = if input.just_pressed(KeyCode::Digit1) {
+    // `Shift` + digit is the bulk spawn in `spawner`.
+    if input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        return;
+    }
+    let new_mode = if input.just_pressed(bindings.modes[0]) {
         Some(PhysicsMode::Avian2d)
-    } else if input.just_pressed(KeyCode::Digit2) {
+    } else if input.just_pressed(bindings.modes[1]) {
         Some(PhysicsMode::Avian3d)
-    } else if input.just_pressed(KeyCode::Digit3) {
+    } else if input.just_pressed(bindings.modes[2]) {
         Some(PhysicsMode::Rapier2d)
-    } else if input.just_pressed(KeyCode::Digit4) {
+    } else if input.just_pressed(bindings.modes[3]) {
         Some(PhysicsMode::Rapier3d)
-    } else if input.just_pressed(KeyCode::Enter) {
+    } else if input.just_pressed(bindings.next_mode) {
         Some(state.get().next())
     } else {
-        None
+        None
+    };
+
+    let Some(new_mode) = new_mode else { return };
+    if *state.get() == new_mode {
+        return;
+    }
+
+    next_state.set(new_mode);
+}
+
+/// `C` toggles auto-cycle. While enabled, each mode runs unpaused for the dwell
+/// time and then advances exactly like pressing Enter. Any mode change, manual or
+/// automatic, restarts the dwell timer.
+fn auto_cycle_modes(
+    input: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    state: Res<State<PhysicsMode>>,
+    mut next_state: ResMut<NextState<PhysicsMode>>,
+    mut auto_cycle: ResMut<AutoCycle>,
+    time: Res<Time<Real>>,
+    mut vtime: ResMut<Time<Virtual>>,
+    mut query: Query<&mut Text, With<AutoCycleText>>,
+) {
+    let toggled = input.just_pressed(bindings.auto_cycle);
+    if toggled {
+        auto_cycle.enabled = !auto_cycle.enabled;
+    }
+    // OnEnter pauses the simulation; undo that so every mode actually runs.
+    if toggled || state.is_changed() {
+        auto_cycle.dwell.reset();
+        if auto_cycle.enabled {
+            vtime.unpause();
+        }
+    }
+
+    let label = if auto_cycle.enabled {
+        auto_cycle.dwell.tick(time.delta());
+        if auto_cycle.dwell.just_finished() {
+            next_state.set(state.get().next());
+        }
+        format!(
+            "Auto-cycle: {:.0}s",
+            auto_cycle.dwell.remaining_secs().ceil()
+        )
+    } else {
+        "Auto-cycle: off".to_string()
     };
-
-    let Some(new_mode) = new_mode else { return };
-    if *state.get() == new_mode {
-        return;
+    for mut text in &mut query {
+        **text = label.clone();
     }
-
-    next_state.set(new_mode);
 }
 
 // ── Tests ─────────────────────────────────────────────────────────────────────
@@ -635,49 +3377,77 @@ mod tests {
     #[test]
     fn ortho_scale_at_reference_window() {
         // 960×540 is the designed window; pool is 1920×1080 = 2×, so scale = 2.
-        assert_eq!(ortho_scale_for_window(960.0, 540.0), 2.0);
+        assert_eq!(
+            ortho_scale_for_window(PoolConfig::default(), 960.0, 540.0),
+            2.0
+        );
     }
 
     #[test]
     fn ortho_scale_at_pool_size() {
         // Pool exactly fills the window → scale = 1.
-        assert_eq!(ortho_scale_for_window(1920.0, 1080.0), 1.0);
+        assert_eq!(
+            ortho_scale_for_window(PoolConfig::default(), 1920.0, 1080.0),
+            1.0
+        );
     }
 
     #[test]
     fn ortho_scale_width_constrained() {
         // 480×540: width is the bottleneck → 1920/480 = 4.
-        assert_eq!(ortho_scale_for_window(480.0, 540.0), 4.0);
+        assert_eq!(
+            ortho_scale_for_window(PoolConfig::default(), 480.0, 540.0),
+            4.0
+        );
     }
 
     #[test]
     fn ortho_scale_height_constrained() {
         // 960×270: height is the bottleneck → 1080/270 = 4.
-        assert_eq!(ortho_scale_for_window(960.0, 270.0), 4.0);
+        assert_eq!(
+            ortho_scale_for_window(PoolConfig::default(), 960.0, 270.0),
+            4.0
+        );
     }
 
     #[test]
     fn ortho_scale_wide_window_uses_height() {
         // 1920×540: width fits at scale 1, height needs scale 2 → 2.
-        assert_eq!(ortho_scale_for_window(1920.0, 540.0), 2.0);
+        assert_eq!(
+            ortho_scale_for_window(PoolConfig::default(), 1920.0, 540.0),
+            2.0
+        );
     }
 
     #[test]
     fn ortho_scale_large_window_zooms_in() {
         // Larger window → smaller scale (camera zooms in).
-        assert!(ortho_scale_for_window(1920.0, 1080.0) < ortho_scale_for_window(960.0, 540.0));
+        assert!(
+            ortho_scale_for_window(PoolConfig::default(), 1920.0, 1080.0)
+                < ortho_scale_for_window(PoolConfig::default(), 960.0, 540.0)
+        );
+    }
+
+    #[test]
+    fn ortho_scale_follows_the_pool_size() {
+        // A pool twice as wide needs twice the scale in the reference window.
+        let wide = PoolConfig {
+            width: 3840.0,
+            ..PoolConfig::default()
+        };
+        assert_eq!(ortho_scale_for_window(wide, 960.0, 540.0), 4.0);
     }
 
     #[test]
     fn cam3d_at_reference_window_is_default_position() {
-        let pos = cam3d_pos_for_window(960.0, 540.0);
+        let pos = cam3d_pos_for_window(PoolConfig::default(), 960.0, 540.0);
         assert_eq!(pos, Vec3::new(0.0, 3000.0, 3200.0));
     }
 
     #[test]
     fn cam3d_smaller_window_moves_camera_farther() {
-        let ref_pos = cam3d_pos_for_window(960.0, 540.0);
-        let small_pos = cam3d_pos_for_window(480.0, 270.0);
+        let ref_pos = cam3d_pos_for_window(PoolConfig::default(), 960.0, 540.0);
+        let small_pos = cam3d_pos_for_window(PoolConfig::default(), 480.0, 270.0);
         let ref_dist = (ref_pos - CAM3D_LOOK_AT).length();
         let small_dist = (small_pos - CAM3D_LOOK_AT).length();
         assert!(small_dist > ref_dist, "smaller window must push camera farther out");
@@ -685,19 +3455,369 @@ mod tests {
 
     #[test]
     fn cam3d_direction_unchanged_on_resize() {
-        let ref_pos = cam3d_pos_for_window(960.0, 540.0);
-        let small_pos = cam3d_pos_for_window(480.0, 270.0);
+        let ref_pos = cam3d_pos_for_window(PoolConfig::default(), 960.0, 540.0);
+        let small_pos = cam3d_pos_for_window(PoolConfig::default(), 480.0, 270.0);
         let ref_dir = (ref_pos - CAM3D_LOOK_AT).normalize();
         let small_dir = (small_pos - CAM3D_LOOK_AT).normalize();
         // Directions should be identical (only distance changes).
         assert!(ref_dir.distance(small_dir) < 1e-5);
     }
 
+    // ── Unit tests: HUD labels ─────────────────────────────────────────────────
+
+    #[test]
+    fn ball_counter_marks_cap() {
+        assert_eq!(ball_counter_label(99, MaxBalls(100)), "Balls: 99");
+        assert_eq!(ball_counter_label(100, MaxBalls(100)), "Balls: 100 (cap)");
+    }
+
+    #[test]
+    fn ball_counter_zero_max_is_unlimited() {
+        assert_eq!(ball_counter_label(1_000_000, MaxBalls(0)), "Balls: 1000000");
+    }
+
+    // ── Unit tests: frame-time percentiles ─────────────────────────────────────
+
+    #[test]
+    fn target_fps_headline_groups_thousands() {
+        assert_eq!(thousands(0), "0");
+        assert_eq!(thousands(999), "999");
+        assert_eq!(thousands(12_400), "12,400");
+        assert_eq!(thousands(1_234_567), "1,234,567");
+        assert_eq!(
+            target_fps_label(TargetFps(Some(60.0)), Some(12_400)),
+            "Held 60 FPS to 12,400 balls"
+        );
+        assert_eq!(target_fps_label(TargetFps(None), Some(12_400)), "");
+    }
+
+    #[test]
+    fn out_of_bounds_checks_each_pool_face() {
+        let pool = PoolConfig::default();
+        let x = pool.width / 2.0 + 1.0;
+        let y = -pool.height / 2.0 - 1.0;
+        let z = pool.depth / 2.0 + 1.0;
+        assert!(!out_of_bounds(Vec3::ZERO, pool, true, false));
+        assert!(out_of_bounds(Vec3::new(-x, 0.0, 0.0), pool, false, false));
+        assert!(out_of_bounds(Vec3::new(0.0, y, 0.0), pool, false, false));
+        assert!(!out_of_bounds(Vec3::new(0.0, y, 0.0), pool, false, true));
+        assert!(out_of_bounds(Vec3::new(0.0, 0.0, z), pool, true, false));
+        assert!(!out_of_bounds(Vec3::new(0.0, 0.0, z), pool, false, false));
+    }
+
+    #[test]
+    fn spin_label_describes_the_range() {
+        assert_eq!(spin_label(InitialSpin::default()), "Spin: off");
+        assert_eq!(spin_label(InitialSpin(5.0, 5.0)), "Spin: 5.0 rad/s");
+        assert_eq!(
+            spin_label(InitialSpin(-10.0, 10.0)),
+            "Spin: -10.0 to 10.0 rad/s"
+        );
+    }
+
+    #[test]
+    fn density_steps_stay_within_bounds() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.init_resource::<ButtonInput<KeyCode>>();
+        app.init_resource::<KeyBindings>();
+        app.init_resource::<BallDensity>();
+        app.add_systems(Update, handle_density);
+        let label = app.world_mut().spawn((DensityText, Text::default())).id();
+        let bindings = app.world().resource::<KeyBindings>().clone();
+        let press = |app: &mut App, key: KeyCode, times: usize| {
+            for _ in 0..times {
+                let mut keys = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+                keys.release(key);
+                keys.clear();
+                keys.press(key);
+                app.update();
+            }
+        };
+
+        press(&mut app, bindings.density_down, 10);
+        assert_eq!(app.world().resource::<BallDensity>().0, DENSITY_MIN);
+        assert_eq!(
+            app.world().get::<Text>(label).unwrap().0,
+            "Density: 0.015625"
+        );
+
+        press(&mut app, bindings.density_up, 20);
+        assert_eq!(app.world().resource::<BallDensity>().0, DENSITY_MAX);
+        assert_eq!(app.world().get::<Text>(label).unwrap().0, "Density: 64");
+    }
+
+    #[test]
+    fn energy_label_shows_the_trend_once_known() {
+        assert_eq!(energy_label(12_340.0, None), "Kinetic energy: 1.234e4");
+        assert_eq!(
+            energy_label(12_340.0, Some(-250.0)),
+            "Kinetic energy: 1.234e4 (-2.50e2/s)"
+        );
+    }
+
+    #[test]
+    fn floor_force_label_shows_the_share_of_weight() {
+        assert_eq!(
+            floor_force_label(FloorForce::default()),
+            "Floor force: 0.000e0"
+        );
+        assert_eq!(
+            floor_force_label(FloorForce {
+                total: 4_500.0,
+                weight: 5_000.0,
+            }),
+            "Floor force: 4.500e3 (90% of weight)"
+        );
+    }
+
+    #[test]
+    fn thresholds_parse_sorted_highest_first() {
+        assert_eq!(
+            parse_thresholds("15, 50,30,50"),
+            Some(vec![50.0, 30.0, 15.0])
+        );
+        assert_eq!(parse_thresholds("50,0"), None);
+        assert_eq!(parse_thresholds("50,fast"), None);
+    }
+
+    #[test]
+    fn milestone_lines_list_every_threshold() {
+        let mut stats = PerfStats::new(&[50.0, 30.0]);
+        stats.below[1].first = Some(4200);
+        assert_eq!(
+            milestone_lines(&stats),
+            "First <50:  -\nAvg <50:    -\nFirst <30:  4200 balls\nAvg <30:    -\np99 >33ms:  -"
+        );
+    }
+
+    #[test]
+    fn new_contact_rate_label_is_per_second() {
+        assert_eq!(new_contact_rate_label(Some(1250)), "New contacts: 1250/s");
+        assert_eq!(
+            new_contact_rate_label(None),
+            "New contacts: off (--new-contacts)"
+        );
+    }
+
+    #[test]
+    fn spawn_rate_label_shows_the_derived_batch() {
+        assert_eq!(
+            spawn_rate_label(SpawnRate::default()),
+            "Spawn rate: 20 balls/s (1 every 50 ms)"
+        );
+        assert_eq!(
+            spawn_rate_label(SpawnRate(1000.0)),
+            "Spawn rate: 1000 balls/s (20 every 20 ms)"
+        );
+    }
+
+    #[test]
+    fn present_mode_label_reports_vsync() {
+        assert_eq!(present_mode_label(PresentMode::default()), "VSync: on");
+        assert_eq!(present_mode_label(PresentMode::AutoVsync), "VSync: on");
+        assert_eq!(
+            present_mode_label(PresentMode::AutoNoVsync),
+            "VSync: off (uncapped)"
+        );
+        assert_eq!(
+            present_mode_label(PresentMode::Immediate),
+            "VSync: off (uncapped)"
+        );
+    }
+
+    #[test]
+    fn frame_time_window_drops_oldest_when_full() {
+        let mut window = FrameTimeWindow::default();
+        for ms in 0..FRAME_TIME_WINDOW as u64 + 5 {
+            window.push(Duration::from_millis(ms));
+        }
+        assert!(window.is_full());
+        assert_eq!(window.0.front(), Some(&Duration::from_millis(5)));
+    }
+
+    #[test]
+    fn frame_time_percentiles_expose_spikes() {
+        let mut window = FrameTimeWindow::default();
+        assert_eq!(window.percentile_ms(99.0), None);
+        for _ in 0..98 {
+            window.push(Duration::from_millis(10));
+        }
+        window.push(Duration::from_millis(50));
+        window.push(Duration::from_millis(80));
+        assert_eq!(window.percentile_ms(50.0), Some(10.0));
+        assert_eq!(window.percentile_ms(99.0), Some(50.0));
+        assert_eq!(window.percentile_ms(100.0), Some(80.0));
+    }
+
+    // ── Integration tests: detect_clipped_balls system ─────────────────────────
+
+    fn make_clip_test_app(mode: PhysicsMode) -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, bevy::state::app::StatesPlugin));
+        app.insert_state(mode);
+        app.init_resource::<BallCount>();
+        app.init_resource::<ClippedBallCount>();
+        app.init_resource::<PoolConfig>();
+        app.init_resource::<SpawnConfig>();
+        app.init_resource::<ExplodedBallCount>();
+        app.init_resource::<NoWalls>();
+        app.add_systems(Update, detect_clipped_balls);
+        app
+    }
+
+    #[test]
+    fn without_walls_fallen_balls_are_recycled_to_the_top() {
+        let mut app = make_clip_test_app(PhysicsMode::Avian2d);
+        app.world_mut().resource_mut::<NoWalls>().0 = true;
+        app.world_mut().resource_mut::<BallCount>().0 = 1;
+        let fallen = app
+            .world_mut()
+            .spawn((Ball, Transform::from_xyz(40.0, -2000.0, 0.0)))
+            .id();
+
+        app.update();
+
+        let transform = app.world().get::<Transform>(fallen).unwrap();
+        assert_eq!(
+            transform.translation,
+            Vec3::new(40.0, SpawnConfig::default().y, 0.0)
+        );
+        assert_eq!(app.world().resource::<ClippedBallCount>().0, 0);
+        assert_eq!(app.world().resource::<BallCount>().0, 1);
+    }
+
+    #[test]
+    fn clipped_ball_below_floor_is_despawned() {
+        let mut app = make_clip_test_app(PhysicsMode::Avian2d);
+        app.world_mut().resource_mut::<BallCount>().0 = 2;
+        let inside = app
+            .world_mut()
+            .spawn((Ball, Transform::from_xyz(0.0, -500.0, 0.0)))
+            .id();
+        let below = app
+            .world_mut()
+            .spawn((Ball, Transform::from_xyz(0.0, -600.0, 0.0)))
+            .id();
+
+        app.update();
+
+        assert!(app.world().get_entity(inside).is_ok());
+        assert!(app.world().get_entity(below).is_err());
+        assert_eq!(app.world().resource::<ClippedBallCount>().0, 1);
+        assert_eq!(app.world().resource::<BallCount>().0, 1);
+    }
+
+    #[test]
+    fn exploded_balls_are_despawned_even_without_walls() {
+        let mut app = make_clip_test_app(PhysicsMode::Avian3d);
+        app.world_mut().resource_mut::<NoWalls>().0 = true;
+        app.world_mut().resource_mut::<BallCount>().0 = 3;
+        let nan = app
+            .world_mut()
+            .spawn((Ball, Transform::from_xyz(f32::NAN, 0.0, 0.0)))
+            .id();
+        let far = app
+            .world_mut()
+            .spawn((Ball, Transform::from_xyz(0.0, 1.0e6, 0.0)))
+            .id();
+        let inside = app
+            .world_mut()
+            .spawn((Ball, Transform::from_xyz(0.0, 0.0, 0.0)))
+            .id();
+
+        app.update();
+
+        assert!(app.world().get_entity(nan).is_err());
+        assert!(app.world().get_entity(far).is_err());
+        assert!(app.world().get_entity(inside).is_ok());
+        assert_eq!(app.world().resource::<ExplodedBallCount>().0, 2);
+        assert_eq!(app.world().resource::<ClippedBallCount>().0, 0);
+        assert_eq!(app.world().resource::<BallCount>().0, 1);
+    }
+
+    #[test]
+    fn clipped_ball_past_back_wall_only_counts_in_3d() {
+        let escaped = Transform::from_xyz(0.0, 0.0, -PoolConfig::default().depth);
+
+        let mut app_3d = make_clip_test_app(PhysicsMode::Rapier3d);
+        app_3d.world_mut().spawn((Ball, escaped));
+        app_3d.update();
+        assert_eq!(app_3d.world().resource::<ClippedBallCount>().0, 1);
+
+        let mut app_2d = make_clip_test_app(PhysicsMode::Rapier2d);
+        app_2d.world_mut().spawn((Ball, escaped));
+        app_2d.update();
+        assert_eq!(app_2d.world().resource::<ClippedBallCount>().0, 0);
+    }
+
+    #[test]
+    fn top_drain_removes_balls_above_the_line() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.init_resource::<BallCount>();
+        app.init_resource::<DrainedBallCount>();
+        app.init_resource::<SpawnConfig>();
+        app.init_resource::<PoolConfig>();
+        app.add_systems(Update, drain_top_balls);
+        app.world_mut().resource_mut::<BallCount>().0 = 2;
+        let drain_y = SpawnConfig::default().y + TOP_DRAIN_MARGIN;
+        let below = app
+            .world_mut()
+            .spawn((Ball, Transform::from_xyz(0.0, drain_y - 1.0, 0.0)))
+            .id();
+        let above = app
+            .world_mut()
+            .spawn((Ball, Transform::from_xyz(0.0, drain_y + 1.0, 0.0)))
+            .id();
+
+        app.update();
+
+        assert!(app.world().get_entity(below).is_ok());
+        assert!(app.world().get_entity(above).is_err());
+        assert_eq!(app.world().resource::<DrainedBallCount>().0, 1);
+        assert_eq!(app.world().resource::<BallCount>().0, 1);
+    }
+
+    #[test]
+    fn pause_label_tells_a_full_pause_from_a_spawn_pause() {
+        assert_eq!(pause_label(true, SpawnPaused(true)), "PAUSED — STEP (N)");
+        assert_eq!(pause_label(false, SpawnPaused(true)), "SPAWN PAUSED");
+        assert_eq!(pause_label(false, SpawnPaused(false)), "");
+    }
+
+    // ── Integration tests: step_virtual_time system ────────────────────────────
+
+    #[test]
+    fn step_advances_paused_time_by_one_fixed_timestep() {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins);
+        app.init_resource::<StepOnce>();
+        app.add_systems(First, step_virtual_time.after(TimeSystems));
+        app.world_mut().resource_mut::<Time<Virtual>>().pause();
+        app.update();
+
+        let before = app.world().resource::<Time<Virtual>>().elapsed();
+        app.world_mut().resource_mut::<StepOnce>().0 = true;
+        app.update();
+        let stepped = app.world().resource::<Time<Virtual>>().elapsed();
+        let timestep = app.world().resource::<Time<Fixed>>().timestep();
+        assert_eq!(stepped - before, timestep);
+        assert!(!app.world().resource::<StepOnce>().0);
+
+        // Only one frame: the next update leaves paused time where it was.
+        app.update();
+        assert_eq!(app.world().resource::<Time<Virtual>>().elapsed(), stepped);
+    }
+
     // ── Integration tests: fit_camera_to_pool system ───────────────────────────
 
     fn make_test_app() -> App {
         let mut app = App::new();
         app.add_plugins(MinimalPlugins);
+        app.init_resource::<PoolConfig>();
+        app.init_resource::<CameraLock>();
+        app.init_resource::<Cam3dOrbit>();
         app.add_systems(Update, fit_camera_to_pool);
         app
     }
@@ -738,7 +3858,7 @@ mod tests {
         };
         assert_eq!(
             ortho.scale,
-            ortho_scale_for_window(960.0, 540.0),
+            ortho_scale_for_window(PoolConfig::default(), 960.0, 540.0),
             "scale should match 960×540 window after first update"
         );
     }
@@ -774,7 +3894,7 @@ mod tests {
         };
         assert_eq!(
             ortho.scale,
-            ortho_scale_for_window(480.0, 540.0),
+            ortho_scale_for_window(PoolConfig::default(), 480.0, 540.0),
             "scale must update to fit narrower window"
         );
     }
@@ -823,7 +3943,7 @@ mod tests {
         let win = spawn_primary_window(&mut app, 960, 540);
         app.world_mut().spawn((
             Camera3d::default(),
-            Transform::from_translation(cam3d_pos_for_window(960.0, 540.0))
+            Transform::from_translation(cam3d_pos_for_window(PoolConfig::default(), 960.0, 540.0))
                 .looking_at(CAM3D_LOOK_AT, Vec3::Y),
         ));
         app.update(); // consume initial Changed
@@ -842,8 +3962,148 @@ mod tests {
         let tf = q.single(app.world()).unwrap();
         assert_eq!(
             tf.translation,
-            cam3d_pos_for_window(480.0, 540.0),
+            cam3d_pos_for_window(PoolConfig::default(), 480.0, 540.0),
             "3D camera position must update to fit narrower window"
         );
     }
+
+    #[test]
+    fn resize_resets_the_3d_orbit() {
+        let mut app = make_test_app();
+        let win = spawn_primary_window(&mut app, 960, 540);
+        app.world_mut()
+            .spawn((Camera3d::default(), Transform::default()));
+        app.update(); // consume initial Changed
+
+        app.world_mut()
+            .resource_mut::<Cam3dOrbit>()
+            .turn(Vec2::new(200.0, 0.0), 2.0);
+        // A cursor move marks the window changed without resizing it.
+        app.world_mut()
+            .entity_mut(win)
+            .get_mut::<Window>()
+            .unwrap()
+            .set_changed();
+        app.update();
+        assert_ne!(*app.world().resource::<Cam3dOrbit>(), Cam3dOrbit::default());
+
+        app.world_mut()
+            .entity_mut(win)
+            .get_mut::<Window>()
+            .unwrap()
+            .resolution = WindowResolution::new(480_u32, 540_u32);
+        app.update();
+
+        assert_eq!(*app.world().resource::<Cam3dOrbit>(), Cam3dOrbit::default());
+        let mut q = app
+            .world_mut()
+            .query_filtered::<&Transform, With<Camera3d>>();
+        let tf = q.single(app.world()).unwrap();
+        assert_eq!(
+            tf.translation,
+            cam3d_pos_for_window(PoolConfig::default(), 480.0, 540.0)
+        );
+    }
+
+    #[test]
+    fn orbit_starts_at_the_fitted_view_and_stays_clamped() {
+        let pool = PoolConfig::default();
+        let fitted = Cam3dOrbit::default().transform(pool, 960.0, 540.0);
+        assert_eq!(fitted.translation, cam3d_pos_for_window(pool, 960.0, 540.0));
+
+        let mut orbit = Cam3dOrbit::default();
+        orbit.turn(Vec2::new(0.0, 1.0e6), -100.0);
+        assert_eq!(orbit.zoom, ORBIT_MAX_ZOOM);
+        let offset = (orbit.transform(pool, 960.0, 540.0).translation - CAM3D_LOOK_AT).normalize();
+        assert!((offset.y.asin() - ORBIT_MAX_ELEVATION).abs() < 1e-4);
+    }
+
+    #[test]
+    fn locked_camera_ignores_window_resize() {
+        let mut app = make_test_app();
+        let win = spawn_primary_window(&mut app, 960, 540);
+        app.world_mut().spawn((
+            Camera2d,
+            Projection::Orthographic(OrthographicProjection {
+                scale: 0.5, // zoomed in by hand
+                ..OrthographicProjection::default_2d()
+            }),
+        ));
+        app.world_mut().resource_mut::<CameraLock>().0 = true;
+        app.update();
+
+        app.world_mut()
+            .entity_mut(win)
+            .get_mut::<Window>()
+            .unwrap()
+            .resolution = WindowResolution::new(480_u32, 540_u32);
+        app.update();
+
+        let mut q = app
+            .world_mut()
+            .query_filtered::<&Projection, With<Camera2d>>();
+        let Projection::Orthographic(ortho) = q.single(app.world()).unwrap() else {
+            panic!("expected orthographic projection");
+        };
+        assert_eq!(ortho.scale, 0.5);
+    }
+
+    #[test]
+    fn fullscreen_toggle_refits_both_cameras() {
+        let mut app = make_test_app();
+        app.add_systems(Update, toggle_fullscreen.after(fit_camera_to_pool));
+        app.init_resource::<ButtonInput<KeyCode>>();
+        app.init_resource::<KeyBindings>();
+        let win = spawn_primary_window(&mut app, 960, 540);
+        app.world_mut().spawn((
+            Monitor {
+                name: None,
+                physical_height: 1080,
+                physical_width: 1920,
+                physical_position: IVec2::ZERO,
+                refresh_rate_millihertz: None,
+                scale_factor: 1.0,
+                video_modes: Vec::new(),
+            },
+            PrimaryMonitor,
+        ));
+        app.world_mut().spawn((
+            Camera2d,
+            Projection::Orthographic(OrthographicProjection::default_2d()),
+        ));
+        app.world_mut().spawn((
+            Camera3d::default(),
+            Transform::from_translation(cam3d_pos_for_window(PoolConfig::default(), 960.0, 540.0)),
+        ));
+        app.update(); // consume initial Changed
+
+        let mut keys = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+        keys.press(KeyCode::F11);
+        app.update();
+        // No resize is reported; the follow-up refit must not undo the toggle's.
+        app.world_mut()
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .clear();
+        app.update();
+
+        let window = app.world().entity(win).get::<Window>().unwrap();
+        assert!(matches!(window.mode, WindowMode::BorderlessFullscreen(_)));
+        let mut q = app
+            .world_mut()
+            .query_filtered::<&Projection, With<Camera2d>>();
+        let Projection::Orthographic(ortho) = q.single(app.world()).unwrap() else {
+            panic!("expected orthographic projection");
+        };
+        assert_eq!(
+            ortho.scale,
+            ortho_scale_for_window(PoolConfig::default(), 1920.0, 1080.0)
+        );
+        let mut q = app
+            .world_mut()
+            .query_filtered::<&Transform, With<Camera3d>>();
+        assert_eq!(
+            q.single(app.world()).unwrap().translation,
+            cam3d_pos_for_window(PoolConfig::default(), 1920.0, 1080.0)
+        );
+    }
 }
@@ -0,0 +1,250 @@
+//! Extra static colliders loaded from a RON file — `--scene <path>` adds funnels,
+//! ramps and baffles to the pool without recompiling, to compare how each engine
+//! handles more complex static geometry.
+//!
+//! ```ron
+//! (
+//!     obstacles: [
+//!         // A funnel: two ramps tilted towards a gap in the middle.
+//!         (position: (-450.0, 100.0), size: (800.0, 20.0), rotation: -20.0),
+//!         (position: (450.0, 100.0), size: (800.0, 20.0), rotation: 20.0),
+//!     ],
+//! )
+//! ```
+//!
+//! Every obstacle is a box built with `backend::spawn_wall`, so it shares the
+//! pool's surface and is rebuilt with the rest of the walls. Without `--scene` the
+//! layout is empty and the pool is unchanged.
+//!
+//! `--scene funnel` loads the built-in [`ObstacleLayout::funnel`] preset instead of
+//! a file: a V of two ramps with a narrow outlet and no pool floor below it, so
+//! balls pour in, jam at the outlet, and those that get through fall out of the
+//! pool and are counted as clipped.
+
+use bevy::prelude::*;
+use serde::Deserialize;
+
+use crate::backend::{self, PhysicsMode, Surface};
+use crate::cli;
+use crate::spawner::BALL_RADIUS;
+use crate::walls::PoolConfig;
+
+const OBSTACLE_COLOR: Color = Color::srgb(0.55, 0.45, 0.35);
+
+/// Gap between the funnel's ramps: five ball diameters, narrow enough to jam.
+const FUNNEL_OUTLET: f32 = 10.0 * BALL_RADIUS;
+
+/// Height of the funnel's outlet above the bottom of the pool, as a fraction of the
+/// pool's height.
+const FUNNEL_OUTLET_HEIGHT: f32 = 0.25;
+
+/// Slope of the funnel's ramps, in degrees from horizontal.
+const FUNNEL_SLOPE: f32 = 30.0;
+
+const FUNNEL_THICKNESS: f32 = 20.0;
+
+/// One static box, in the pool's pixel coordinates.
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize)]
+pub struct Obstacle {
+    /// Centre (x, y).
+    pub position: [f32; 2],
+    /// Full extents (width, height). In 3D the box spans the pool's depth.
+    pub size: [f32; 2],
+    /// Counter-clockwise rotation about the centre, in degrees.
+    #[serde(default)]
+    pub rotation: f32,
+}
+
+/// Obstacles spawned alongside the pool's walls in every mode.
+#[derive(Resource, Debug, Clone, PartialEq, Default, Deserialize)]
+pub struct ObstacleLayout {
+    pub obstacles: Vec<Obstacle>,
+    /// Leaves out the pool's floor, so balls that get past the obstacles fall out
+    /// of the pool and are counted as clipped.
+    #[serde(default)]
+    pub open_floor: bool,
+}
+
+impl ObstacleLayout {
+    /// Layout from the `--scene` file, or the funnel preset fitted to `pool`. Logs a
+    /// warning and falls back to the empty layout if the file can't be read or parsed.
+    pub fn from_args(pool: PoolConfig) -> Self {
+        let Some(path) = cli::value("--scene") else {
+            return Self::default();
+        };
+        if path == "funnel" {
+            info!("Loaded the built-in funnel scene");
+            return Self::funnel(pool);
+        }
+        let layout = std::fs::read_to_string(&path)
+            .map_err(|err| err.to_string())
+            .and_then(|text| Self::parse(&text).map_err(|err| err.to_string()));
+        match layout {
+            Ok(layout) => {
+                info!("Loaded {} obstacles from {path}", layout.obstacles.len());
+                layout
+            }
+            Err(err) => {
+                warn!("Ignoring --scene {path}: {err}");
+                Self::default()
+            }
+        }
+    }
+
+    /// Two ramps sloping down from the side walls of `pool` to a [`FUNNEL_OUTLET`]
+    /// gap in the middle, over an open floor.
+    pub fn funnel(pool: PoolConfig) -> Self {
+        let half_width = pool.width / 2.0 - pool.thickness;
+        let run = half_width - FUNNEL_OUTLET / 2.0;
+        let slope = FUNNEL_SLOPE.to_radians();
+        let outlet_y = Self::funnel_outlet_y(pool);
+        let ramp = |side: f32| Obstacle {
+            position: [
+                side * (FUNNEL_OUTLET + run) / 2.0,
+                outlet_y + run * slope.tan() / 2.0,
+            ],
+            size: [run / slope.cos(), FUNNEL_THICKNESS * pool.scale],
+            rotation: side * FUNNEL_SLOPE,
+        };
+        Self {
+            obstacles: vec![ramp(-1.0), ramp(1.0)],
+            open_floor: true,
+        }
+    }
+
+    /// Height of the funnel's outlet: [`FUNNEL_OUTLET_HEIGHT`] of the way up `pool`.
+    fn funnel_outlet_y(pool: PoolConfig) -> f32 {
+        (FUNNEL_OUTLET_HEIGHT - 0.5) * pool.height
+    }
+
+    fn parse(text: &str) -> ron::error::SpannedResult<Self> {
+        ron::from_str(text)
+    }
+
+    /// Whether a ball of `radius` centred at `point` would overlap an obstacle.
+    /// Conservative at the corners, where it treats the ball as a square.
+    pub fn blocks(&self, point: Vec2, radius: f32) -> bool {
+        self.obstacles.iter().any(|obstacle| {
+            let local = Vec2::from_angle(-obstacle.rotation.to_radians())
+                .rotate(point - Vec2::from(obstacle.position));
+            let half = Vec2::from(obstacle.size) / 2.0 + radius;
+            local.abs().cmplt(half).all()
+        })
+    }
+
+    /// Spawns every obstacle as a static wall. `depth` is the 3D extent along Z.
+    pub fn spawn(
+        &self,
+        commands: &mut Commands,
+        meshes: &mut Assets<Mesh>,
+        materials: &mut Assets<StandardMaterial>,
+        mode: PhysicsMode,
+        surface: Surface,
+        depth: f32,
+    ) {
+        for obstacle in &self.obstacles {
+            let [x, y] = obstacle.position;
+            let [width, height] = obstacle.size;
+            backend::spawn_wall(
+                commands,
+                meshes,
+                materials,
+                mode,
+                Vec3::new(x, y, 0.0),
+                Quat::from_rotation_z(obstacle.rotation.to_radians()),
+                Vec3::new(width, height, depth),
+                OBSTACLE_COLOR,
+                surface,
+            );
+        }
+    }
+}
+
+/// Loads the `--scene` layout. Added after `spawner::plugin`, whose [`PoolConfig`]
+/// the funnel preset is fitted to.
+pub fn plugin(app: &mut App) {
+    let pool = *app.world().resource::<PoolConfig>();
+    app.insert_resource(ObstacleLayout::from_args(pool));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_defaults_rotation_to_zero() {
+        let layout = ObstacleLayout::parse(
+            "(obstacles: [
+                (position: (-450.0, 100.0), size: (800.0, 20.0), rotation: -20.0),
+                (position: (0.0, -200.0), size: (40.0, 300.0)),
+            ])",
+        )
+        .unwrap();
+        assert_eq!(
+            layout.obstacles,
+            [
+                Obstacle {
+                    position: [-450.0, 100.0],
+                    size: [800.0, 20.0],
+                    rotation: -20.0,
+                },
+                Obstacle {
+                    position: [0.0, -200.0],
+                    size: [40.0, 300.0],
+                    rotation: 0.0,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn funnel_ramps_meet_the_side_walls_and_leave_an_outlet() {
+        let tall = PoolConfig {
+            height: 2160.0,
+            ..PoolConfig::default()
+        };
+        for pool in [PoolConfig::default(), tall] {
+            assert_funnel_fits(pool);
+        }
+    }
+
+    fn assert_funnel_fits(pool: PoolConfig) {
+        let funnel = ObstacleLayout::funnel(pool);
+        let outlet_y = ObstacleLayout::funnel_outlet_y(pool);
+        assert!(funnel.open_floor);
+        assert!(outlet_y > -pool.height / 2.0 && outlet_y < 0.0);
+        for obstacle in &funnel.obstacles {
+            let [x, y] = obstacle.position;
+            let half = Vec2::from_angle(obstacle.rotation.to_radians()) * obstacle.size[0] / 2.0;
+            let (low, high) = if half.y < 0.0 {
+                (Vec2::new(x, y) + half, Vec2::new(x, y) - half)
+            } else {
+                (Vec2::new(x, y) - half, Vec2::new(x, y) + half)
+            };
+            assert!((low.x.abs() - FUNNEL_OUTLET / 2.0).abs() < 0.01);
+            assert!((low.y - outlet_y).abs() < 0.01);
+            assert!((high.x.abs() - (pool.width / 2.0 - pool.thickness)).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn blocks_follows_the_obstacle_rotation() {
+        let layout = ObstacleLayout {
+            obstacles: vec![Obstacle {
+                position: [100.0, 0.0],
+                size: [200.0, 20.0],
+                rotation: 90.0,
+            }],
+            open_floor: false,
+        };
+        assert!(layout.blocks(Vec2::new(100.0, 90.0), 5.0));
+        assert!(layout.blocks(Vec2::new(114.0, 0.0), 5.0));
+        assert!(!layout.blocks(Vec2::new(116.0, 0.0), 5.0));
+        assert!(!layout.blocks(Vec2::new(190.0, 0.0), 5.0));
+    }
+
+    #[test]
+    fn parse_rejects_missing_size() {
+        assert!(ObstacleLayout::parse("(obstacles: [(position: (0.0, 0.0))])").is_err());
+    }
+}
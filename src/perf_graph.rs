@@ -0,0 +1,309 @@
+//! FPS-vs-ball-count graph in the HUD — every [`SAMPLE_INTERVAL`] of unpaused
+//! running, the smoothed FPS and the ball count are appended to the active mode's
+//! [`PerfCurve`]. The last run of every mode is kept and plotted in its own colour,
+//! so the backends can be compared at a glance rather than by milestone numbers.
+//!
+//! The panel, legend and axes are UI nodes; the curves are line-strip gizmos drawn
+//! over the panel by an overlay [`PerfGraphCamera`] on its own render layer, which
+//! keeps them independent of which 2D or 3D camera is active and costs no entities
+//! per sample.
+
+use bevy::diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin};
+use bevy::{prelude::*, window::PrimaryWindow};
+use std::collections::HashMap;
+use std::time::Duration;
+
+// Bevy 0.17 moved cameras and render layers out of `bevy::render` into `bevy::camera`.
+#[cfg(not(feature = "legacy_state_scoped"))]
+use bevy::camera::{ClearColorConfig, visibility::RenderLayers};
+#[cfg(feature = "legacy_state_scoped")]
+use bevy::render::{camera::ClearColorConfig, view::RenderLayers};
+
+use crate::backend::PhysicsMode;
+use crate::spawner::BallCount;
+
+/// Real time between samples.
+const SAMPLE_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Samples kept per mode; once reached, every other sample is dropped so the
+/// curve keeps its full span at half the resolution.
+const MAX_SAMPLES: usize = 1000;
+
+const PLOT_WIDTH: f32 = 300.0;
+const PLOT_HEIGHT: f32 = 150.0;
+
+/// Where the panel sits in the window, in logical pixels. The plot is its first
+/// child, so the curves can be drawn at a fixed offset from the top-right corner.
+const PANEL_TOP: f32 = 275.0;
+const PANEL_RIGHT: f32 = 10.0;
+const PANEL_PADDING: f32 = 8.0;
+
+/// Render layer of the curves, above the split screen's partner layers.
+const GRAPH_LAYER: usize = 5;
+
+/// Above the main camera and split screen's cameras, so the curves are drawn over
+/// the panel's background.
+const GRAPH_CAMERA_ORDER: isize = 3;
+
+/// Axis minimums, so a short run doesn't stretch across the whole plot.
+const MIN_X_BALLS: usize = 1000;
+const MIN_Y_FPS: f32 = 60.0;
+
+/// Sampled `(ball_count, fps)` points of the most recent run of each mode.
+#[derive(Resource, Default)]
+pub(crate) struct PerfCurve(HashMap<PhysicsMode, Vec<(usize, f32)>>);
+
+impl PerfCurve {
+    fn push(&mut self, mode: PhysicsMode, balls: usize, fps: f32) {
+        let samples = self.0.entry(mode).or_default();
+        if samples.len() == MAX_SAMPLES {
+            let mut index = 0;
+            samples.retain(|_| {
+                index += 1;
+                index % 2 == 1
+            });
+        }
+        samples.push((balls, fps));
+    }
+
+    /// Axis extents covering every curve: `(max ball count, max FPS)`.
+    fn extents(&self) -> (usize, f32) {
+        self.0
+            .values()
+            .flatten()
+            .fold((MIN_X_BALLS, MIN_Y_FPS), |(balls, fps), &(b, f)| {
+                (balls.max(b), fps.max(f))
+            })
+    }
+}
+
+#[derive(Resource)]
+struct SampleTimer(Timer);
+
+/// Gizmo group of the curves, drawn only by the [`PerfGraphCamera`].
+#[derive(Default, Reflect, GizmoConfigGroup)]
+struct PerfGraphGizmos;
+
+/// Overlay camera that draws nothing but the curves. Camera queries that expect the
+/// main camera alone leave it out.
+#[derive(Component)]
+pub(crate) struct PerfGraphCamera;
+
+#[derive(Component)]
+struct AxisText;
+
+pub fn plugin(app: &mut App) {
+    app.init_resource::<PerfCurve>();
+    app.insert_resource(SampleTimer(Timer::new(
+        SAMPLE_INTERVAL,
+        TimerMode::Repeating,
+    )));
+    app.init_gizmo_group::<PerfGraphGizmos>();
+    app.add_systems(Startup, spawn_graph);
+    app.add_systems(
+        Update,
+        (
+            sample_perf_curve,
+            update_axis_label.run_if(resource_changed::<PerfCurve>),
+            draw_perf_graph,
+        )
+            .chain(),
+    );
+    for mode in PhysicsMode::ALL {
+        app.add_systems(OnEnter(mode), clear_active_curve);
+    }
+}
+
+fn spawn_graph(mut commands: Commands, mut gizmo_config: ResMut<GizmoConfigStore>) {
+    let (config, _) = gizmo_config.config_mut::<PerfGraphGizmos>();
+    config.render_layers = RenderLayers::layer(GRAPH_LAYER);
+    commands.spawn((
+        Name::new("Perf Graph Camera"),
+        PerfGraphCamera,
+        Camera2d,
+        Camera {
+            order: GRAPH_CAMERA_ORDER,
+            clear_color: ClearColorConfig::None,
+            ..default()
+        },
+        RenderLayers::layer(GRAPH_LAYER),
+    ));
+
+    commands
+        .spawn((
+            Name::new("Perf Graph"),
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Px(PANEL_TOP),
+                right: Val::Px(PANEL_RIGHT),
+                flex_direction: FlexDirection::Column,
+                row_gap: Val::Px(4.0),
+                padding: UiRect::all(Val::Px(PANEL_PADDING)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+        ))
+        .with_children(|panel| {
+            panel.spawn((
+                Name::new("Perf Graph Plot"),
+                Node {
+                    width: Val::Px(PLOT_WIDTH),
+                    height: Val::Px(PLOT_HEIGHT),
+                    border: UiRect {
+                        left: Val::Px(1.0),
+                        bottom: Val::Px(1.0),
+                        ..default()
+                    },
+                    ..default()
+                },
+                BorderColor::from(Color::srgb(0.6, 0.6, 0.6)),
+            ));
+            panel
+                .spawn((
+                    Name::new("Perf Graph Legend"),
+                    Node {
+                        column_gap: Val::Px(10.0),
+                        ..default()
+                    },
+                ))
+                .with_children(|legend| {
+                    for mode in PhysicsMode::ALL {
+                        legend.spawn((
+                            Text::new(mode.label()),
+                            TextFont {
+                                font_size: 14.0,
+                                ..default()
+                            },
+                            TextColor(mode.color()),
+                        ));
+                    }
+                });
+            panel.spawn((
+                Name::new("Perf Graph Axes"),
+                AxisText,
+                Text::new(axis_label(MIN_X_BALLS, MIN_Y_FPS)),
+                TextFont {
+                    font_size: 14.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(0.7, 0.7, 0.7)),
+            ));
+        });
+}
+
+fn axis_label(max_balls: usize, max_fps: f32) -> String {
+    format!("FPS 0 – {max_fps:.0} vs balls 0 – {max_balls}")
+}
+
+/// New run, new curve: drops the entered mode's previous samples.
+fn clear_active_curve(state: Res<State<PhysicsMode>>, mut curve: ResMut<PerfCurve>) {
+    curve.0.remove(state.get());
+}
+
+/// Samples while the simulation runs; paused frames would only stack points.
+fn sample_perf_curve(
+    real: Res<Time<Real>>,
+    vtime: Res<Time<Virtual>>,
+    diagnostics: Res<DiagnosticsStore>,
+    state: Res<State<PhysicsMode>>,
+    ball_count: Res<BallCount>,
+    mut timer: ResMut<SampleTimer>,
+    mut curve: ResMut<PerfCurve>,
+) {
+    if vtime.is_paused() {
+        return;
+    }
+    timer.0.tick(real.delta());
+    if !timer.0.just_finished() {
+        return;
+    }
+    let Some(fps) = diagnostics
+        .get(&FrameTimeDiagnosticsPlugin::FPS)
+        .and_then(|d| d.smoothed())
+    else {
+        return;
+    };
+    curve.push(*state.get(), ball_count.0, fps as f32);
+}
+
+fn update_axis_label(curve: Res<PerfCurve>, mut axis: Query<&mut Text, With<AxisText>>) {
+    let (max_balls, max_fps) = curve.extents();
+    for mut text in &mut axis {
+        **text = axis_label(max_balls, max_fps);
+    }
+}
+
+/// Where a sample lands in the window, in logical pixels from the top left, for a
+/// window `width` wide and the given axis extents.
+fn plot_position(width: f32, (max_balls, max_fps): (usize, f32), balls: usize, fps: f32) -> Vec2 {
+    let origin = Vec2::new(
+        width - PANEL_RIGHT - PANEL_PADDING - PLOT_WIDTH,
+        PANEL_TOP + PANEL_PADDING + PLOT_HEIGHT,
+    );
+    origin
+        + Vec2::new(
+            balls as f32 / max_balls as f32 * PLOT_WIDTH,
+            -fps / max_fps * PLOT_HEIGHT,
+        )
+}
+
+/// Gizmos last a frame, so the curves are drawn every frame, as one line strip per
+/// mode mapped through the overlay camera, wherever the camera handlers moved it.
+fn draw_perf_graph(
+    curve: Res<PerfCurve>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    camera: Query<(&Camera, &GlobalTransform), With<PerfGraphCamera>>,
+    mut gizmos: Gizmos<PerfGraphGizmos>,
+) {
+    let (Ok(window), Ok((camera, transform))) = (windows.single(), camera.single()) else {
+        return;
+    };
+    let extents = curve.extents();
+    for (&mode, samples) in &curve.0 {
+        let points = samples.iter().filter_map(|&(balls, fps)| {
+            let position = plot_position(window.width(), extents, balls, fps);
+            camera.viewport_to_world_2d(transform, position).ok()
+        });
+        gizmos.linestrip_2d(points, mode.color());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn full_curve_is_halved_but_keeps_its_span() {
+        let mut curve = PerfCurve::default();
+        for i in 0..=MAX_SAMPLES {
+            curve.push(PhysicsMode::Avian2d, i, 60.0);
+        }
+        let samples = &curve.0[&PhysicsMode::Avian2d];
+        assert_eq!(samples.len(), MAX_SAMPLES / 2 + 1);
+        assert_eq!(samples.first().map(|s| s.0), Some(0));
+        assert_eq!(samples.last().map(|s| s.0), Some(MAX_SAMPLES));
+    }
+
+    #[test]
+    fn samples_span_the_plot_from_its_bottom_left_corner() {
+        let extents = (MIN_X_BALLS, MIN_Y_FPS);
+        let right = 1920.0 - PANEL_RIGHT - PANEL_PADDING;
+        let bottom = PANEL_TOP + PANEL_PADDING + PLOT_HEIGHT;
+        assert_eq!(
+            plot_position(1920.0, extents, 0, 0.0),
+            Vec2::new(right - PLOT_WIDTH, bottom)
+        );
+        assert_eq!(
+            plot_position(1920.0, extents, MIN_X_BALLS, MIN_Y_FPS),
+            Vec2::new(right, bottom - PLOT_HEIGHT)
+        );
+    }
+
+    #[test]
+    fn extents_never_shrink_below_minimums() {
+        let mut curve = PerfCurve::default();
+        assert_eq!(curve.extents(), (MIN_X_BALLS, MIN_Y_FPS));
+        curve.push(PhysicsMode::Rapier3d, 5000, 144.0);
+        assert_eq!(curve.extents(), (5000, 144.0));
+    }
+}
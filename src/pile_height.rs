@@ -0,0 +1,151 @@
+//! Pile height marks — the simulated time and ball count at which the settled pile
+//! first reaches each mark, given as percentages of the pool height with
+//! `--pile-marks` (default `25,50,75`).
+//!
+//! The pile top is the highest sleeping ball ([`PileTop`]), so balls bouncing above
+//! the pile don't count. The FPS milestones say when an engine gets slow; these say
+//! how fast it fills the pool, which follows how densely it packs and how quickly
+//! balls come to rest. Reset on every mode switch.
+
+use bevy::prelude::*;
+use std::time::Duration;
+
+use crate::backend::{PhysicsMode, PileTop};
+use crate::cli;
+use crate::spawner::BallCount;
+use crate::walls::PoolConfig;
+
+const DEFAULT_MARKS: [f32; 3] = [25.0, 50.0, 75.0];
+
+/// When the pile first reached a mark.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PileMark {
+    /// Simulated seconds since the mode was entered.
+    pub secs: f32,
+    pub balls: usize,
+}
+
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct PileHeight {
+    /// Percentages of the pool height, ascending.
+    pub marks: Vec<f32>,
+    /// One entry per mark, `None` until the pile reaches it.
+    pub reached: Vec<Option<PileMark>>,
+    /// Simulated time since the mode was entered.
+    elapsed: Duration,
+}
+
+impl PileHeight {
+    fn new(mut marks: Vec<f32>) -> Self {
+        marks.sort_by(f32::total_cmp);
+        Self {
+            reached: vec![None; marks.len()],
+            marks,
+            elapsed: Duration::ZERO,
+        }
+    }
+
+    fn from_args() -> Self {
+        let marks = cli::value("--pile-marks").and_then(|raw| {
+            let marks = parse_marks(&raw);
+            if marks.is_none() {
+                warn!("Ignoring invalid value for --pile-marks: {raw:?}");
+            }
+            marks
+        });
+        Self::new(marks.unwrap_or_else(|| DEFAULT_MARKS.to_vec()))
+    }
+
+    fn reset(&mut self) {
+        self.reached.fill(None);
+        self.elapsed = Duration::ZERO;
+    }
+
+    /// Latches every mark at or below `percent` that has not been reached yet.
+    fn observe(&mut self, percent: f32, balls: usize) {
+        let secs = self.elapsed.as_secs_f32();
+        for (mark, reached) in self.marks.iter().zip(&mut self.reached) {
+            if reached.is_none() && percent >= *mark {
+                *reached = Some(PileMark { secs, balls });
+            }
+        }
+    }
+}
+
+/// Comma-separated percentages, each in (0, 100].
+fn parse_marks(raw: &str) -> Option<Vec<f32>> {
+    raw.split(',')
+        .map(|mark| {
+            mark.trim()
+                .parse::<f32>()
+                .ok()
+                .filter(|mark| *mark > 0.0 && *mark <= 100.0)
+        })
+        .collect()
+}
+
+pub fn plugin(app: &mut App) {
+    app.insert_resource(PileHeight::from_args());
+    app.add_systems(Update, track_pile_height);
+    for mode in PhysicsMode::ALL {
+        app.add_systems(OnEnter(mode), reset_pile_height);
+    }
+}
+
+fn reset_pile_height(mut pile: ResMut<PileHeight>) {
+    pile.reset();
+}
+
+/// Measured from the floor's underside, the bottom of the [`PoolConfig`] height.
+fn track_pile_height(
+    time: Res<Time<Virtual>>,
+    pool: Res<PoolConfig>,
+    top: Res<PileTop>,
+    ball_count: Res<BallCount>,
+    mut pile: ResMut<PileHeight>,
+) {
+    pile.elapsed += time.delta();
+    if let Some(top) = top.0 {
+        let percent = (top + pool.height / 2.0) / pool.height * 100.0;
+        pile.observe(percent, ball_count.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_marks_accepts_percentages_only() {
+        assert_eq!(parse_marks("10, 90,50"), Some(vec![10.0, 90.0, 50.0]));
+        assert_eq!(parse_marks("0,50"), None);
+        assert_eq!(parse_marks("50,tall"), None);
+    }
+
+    #[test]
+    fn observe_latches_each_mark_once() {
+        let mut pile = PileHeight::new(vec![75.0, 25.0, 50.0]);
+        assert_eq!(pile.marks, [25.0, 50.0, 75.0]);
+        pile.observe(60.0, 800);
+        pile.elapsed = Duration::from_secs(10);
+        pile.observe(90.0, 2000);
+        pile.observe(95.0, 2500);
+        assert_eq!(
+            pile.reached,
+            [
+                Some(PileMark {
+                    secs: 0.0,
+                    balls: 800
+                }),
+                Some(PileMark {
+                    secs: 0.0,
+                    balls: 800
+                }),
+                Some(PileMark {
+                    secs: 10.0,
+                    balls: 2000
+                }),
+            ]
+        );
+    }
+}
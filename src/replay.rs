@@ -0,0 +1,240 @@
+//! Session recording — `--record <path>` writes every key press and release and
+//! every mode change, stamped with the real time since startup, one per line;
+//! `--replay <path>` feeds them back into `ButtonInput<KeyCode>` at the same times.
+//!
+//! Spawns need no recording: the file starts with the spawn seed, and with the same
+//! seed the spawner reproduces the same drops. Replayed presses go through the same
+//! systems as real ones, so a replay can be diffed against the recording across
+//! crate versions. Mode changes are replayed too, correcting the mode should a
+//! replayed key land a frame off. Mouse input (click spawns, drags) is not recorded.
+//!
+//! ```text
+//! 0.000000	seed	42
+//! 0.016512	mode	Avian2D
+//! 2.304871	press	Space
+//! 2.398210	release	Space
+//! ```
+
+use bevy::prelude::*;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::Write;
+use std::time::Duration;
+
+// Bevy 0.16 named the input set `InputSystem`; 0.17+ renamed system sets to `*Systems`.
+#[cfg(feature = "legacy_state_scoped")]
+use bevy::input::InputSystem as InputSystems;
+#[cfg(not(feature = "legacy_state_scoped"))]
+use bevy::input::InputSystems;
+
+use crate::backend::PhysicsMode;
+use crate::cli;
+use crate::spawner::SpawnRng;
+
+/// One line of a session file.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Entry {
+    Seed(u64),
+    Mode(PhysicsMode),
+    Press(KeyCode),
+    Release(KeyCode),
+}
+
+impl Entry {
+    fn to_line(self, at: Duration) -> String {
+        let (kind, value) = match self {
+            Entry::Seed(seed) => ("seed", seed.to_string()),
+            Entry::Mode(mode) => ("mode", mode.label().replace(' ', "")),
+            Entry::Press(key) => ("press", format!("{key:?}")),
+            Entry::Release(key) => ("release", format!("{key:?}")),
+        };
+        format!("{:.6}\t{kind}\t{value}", at.as_secs_f64())
+    }
+
+    fn parse_line(line: &str) -> Option<(Duration, Self)> {
+        let mut fields = line.split('\t');
+        let secs: f64 = fields.next()?.parse().ok()?;
+        let at = Duration::from_micros((secs * 1e6).round() as u64);
+        let kind = fields.next()?;
+        let value = fields.next()?;
+        let entry = match kind {
+            "seed" => Entry::Seed(value.parse().ok()?),
+            "mode" => Entry::Mode(value.parse().ok()?),
+            "press" => Entry::Press(ron::from_str(value).ok()?),
+            "release" => Entry::Release(ron::from_str(value).ok()?),
+            _ => return None,
+        };
+        Some((at, entry))
+    }
+}
+
+/// Open session file, from `--record`.
+#[derive(Resource)]
+struct Recording {
+    file: File,
+}
+
+impl Recording {
+    fn write(&mut self, at: Duration, entry: Entry) {
+        if let Err(err) = writeln!(self.file, "{}", entry.to_line(at)) {
+            warn!("Failed to record session entry: {err}");
+        }
+    }
+}
+
+/// Entries still to replay, from `--replay`, in time order.
+#[derive(Resource)]
+struct Replay {
+    entries: VecDeque<(Duration, Entry)>,
+}
+
+impl Replay {
+    /// Reads a session file. Unreadable lines are skipped with a warning.
+    fn load(path: &str) -> std::io::Result<Self> {
+        let text = std::fs::read_to_string(path)?;
+        let mut entries: Vec<_> = text
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| {
+                let entry = Entry::parse_line(line);
+                if entry.is_none() {
+                    warn!("Skipping unreadable line in {path}: {line:?}");
+                }
+                entry
+            })
+            .collect();
+        entries.sort_by_key(|(at, _)| *at);
+        Ok(Self {
+            entries: entries.into(),
+        })
+    }
+
+    /// Entries due by `now`, removed from the queue.
+    fn due(&mut self, now: Duration) -> Vec<Entry> {
+        let due = self.entries.iter().take_while(|(at, _)| *at <= now).count();
+        self.entries.drain(..due).map(|(_, entry)| entry).collect()
+    }
+}
+
+pub fn plugin(app: &mut App) {
+    if let Some(path) = cli::value("--replay") {
+        match Replay::load(&path) {
+            Ok(replay) => {
+                info!(
+                    "Replaying {} session entries from {path}",
+                    replay.entries.len()
+                );
+                app.insert_resource(replay);
+            }
+            Err(err) => warn!("Ignoring --replay {path}: {err}"),
+        }
+    }
+    if let Some(path) = cli::value("--record") {
+        match File::create(&path) {
+            Ok(file) => {
+                info!("Recording session to {path}");
+                app.insert_resource(Recording { file });
+            }
+            Err(err) => warn!("Ignoring --record {path}: {err}"),
+        }
+    }
+    app.add_systems(
+        PreUpdate,
+        (
+            replay_session.run_if(resource_exists::<Replay>),
+            record_session.run_if(resource_exists::<Recording>),
+        )
+            .chain()
+            .after(InputSystems),
+    );
+}
+
+/// Runs after Bevy has updated the keyboard state for the frame, so replayed keys
+/// show up as `just_pressed` / `just_released` to every `Update` system.
+fn replay_session(
+    mut commands: Commands,
+    real: Res<Time<Real>>,
+    rng: Res<SpawnRng>,
+    state: Res<State<PhysicsMode>>,
+    mut next_state: ResMut<NextState<PhysicsMode>>,
+    mut input: ResMut<ButtonInput<KeyCode>>,
+    mut replay: ResMut<Replay>,
+) {
+    for entry in replay.due(real.elapsed()) {
+        match entry {
+            Entry::Seed(seed) if seed != rng.seed() => warn!(
+                "Session was recorded with seed {seed}, not {}; pass --seed {seed} to reproduce it",
+                rng.seed()
+            ),
+            Entry::Seed(_) => {}
+            Entry::Mode(mode) if mode != *state.get() => next_state.set(mode),
+            Entry::Mode(_) => {}
+            Entry::Press(key) => input.press(key),
+            Entry::Release(key) => input.release(key),
+        }
+    }
+    if replay.entries.is_empty() {
+        info!("Replay finished");
+        commands.remove_resource::<Replay>();
+    }
+}
+
+fn record_session(
+    real: Res<Time<Real>>,
+    rng: Res<SpawnRng>,
+    state: Res<State<PhysicsMode>>,
+    input: Res<ButtonInput<KeyCode>>,
+    mut recording: ResMut<Recording>,
+    mut started: Local<bool>,
+) {
+    let now = real.elapsed();
+    if !*started {
+        *started = true;
+        recording.write(now, Entry::Seed(rng.seed()));
+    }
+    if state.is_changed() {
+        recording.write(now, Entry::Mode(*state.get()));
+    }
+    for &key in input.get_just_pressed() {
+        recording.write(now, Entry::Press(key));
+    }
+    for &key in input.get_just_released() {
+        recording.write(now, Entry::Release(key));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entries_round_trip_through_lines() {
+        let at = Duration::from_millis(2_304);
+        for entry in [
+            Entry::Seed(42),
+            Entry::Mode(PhysicsMode::Rapier3d),
+            Entry::Press(KeyCode::Space),
+            Entry::Release(KeyCode::ArrowUp),
+        ] {
+            assert_eq!(Entry::parse_line(&entry.to_line(at)), Some((at, entry)));
+        }
+        assert_eq!(Entry::parse_line("1.0\tteleport\tKeyQ"), None);
+    }
+
+    #[test]
+    fn due_pops_entries_up_to_now() {
+        let mut replay = Replay {
+            entries: [
+                (Duration::from_secs(1), Entry::Press(KeyCode::Space)),
+                (Duration::from_secs(2), Entry::Release(KeyCode::Space)),
+            ]
+            .into(),
+        };
+        assert_eq!(replay.due(Duration::from_millis(500)), []);
+        assert_eq!(
+            replay.due(Duration::from_secs(1)),
+            [Entry::Press(KeyCode::Space)]
+        );
+        assert_eq!(replay.entries.len(), 1);
+    }
+}
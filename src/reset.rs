@@ -0,0 +1,520 @@
+//! Reset to baseline — `Backspace` puts every runtime setting back to the value it
+//! had at startup (the default, or whatever the command line set), clears the balls
+//! and restarts the drop sequence, warmup, milestones and clipped / drained counters,
+//! lifting any spawn freeze and laying out `--prefill` again, as entering a mode does.
+//! A comparison started after it never inherits settings tweaked along the way. A
+//! "RESET" toast confirms it.
+//!
+//! View toggles (camera, split screen, panels, debug outlines) are left alone: they
+//! change what is shown, not what is simulated.
+
+use bevy::prelude::*;
+use std::time::Duration;
+
+use crate::backend::{
+    GravityAngle, GravityScale, PhysicsMode, RapierIterations, SpawnShape, Substeps,
+};
+use crate::interpolate::AvianInterpolation;
+use crate::keys::KeyBindings;
+use crate::spawner::{
+    self, Ball, BallAngularDamping, BallCcd, BallConfig, BallCount, BallDensity, BallFriction,
+    BallLinearDamping, BallRestitution, NozzleCount, RenderBalls, SpawnPattern, SpawnPaused,
+    SpawnRate,
+};
+use crate::speed_color::{ColorByMass, ColorBySleep, ColorBySpeed};
+use crate::split;
+use crate::walls::{self, FloorDrain, FloorThickness, NoWalls, TopWall, WallConfig};
+use crate::{
+    CcdText, ColorBySpeedText, DampingText, DensityText, FloorDrainText, FloorThicknessText,
+    FpsThresholds, FrictionText, GravityAngleText, GravityText, NoWallsText, NozzleText, PerfStats,
+    RenderBallsText, RestitutionText, ShapeText, SpawnRateText, TimeScaleText, TopWallText,
+};
+
+/// How long the toast stays up.
+const TOAST_DURATION: Duration = Duration::from_millis(1200);
+
+/// Writes one setting back to its startup value.
+type Restore = Box<dyn Fn(&mut World) + Send + Sync>;
+
+/// Startup value of every runtime setting, captured once startup has inserted them.
+#[derive(Resource)]
+struct Baseline(Vec<Restore>);
+
+/// Real time left on the toast.
+#[derive(Resource, Default)]
+struct ResetToast(Option<Timer>);
+
+#[derive(Component)]
+struct ToastText;
+
+pub fn plugin(app: &mut App) {
+    app.init_resource::<ResetToast>();
+    app.add_systems(PostStartup, capture_baseline);
+    app.add_systems(Startup, spawn_toast);
+    app.add_systems(
+        Update,
+        (
+            (
+                restore_baseline,
+                clear_pool,
+                // The same restart as entering a mode: the drop sequence, a freeze
+                // left by the FPS guard or settle run, the warmup, `--prefill` and
+                // whether another engine shared the run.
+                spawner::reset_spawn_sequence,
+                spawner::unfreeze_spawning,
+                crate::restart_warmup_timer,
+                spawner::queue_prefill,
+                split::restart_partner_ran,
+                crate::reset_clipped_ball_count,
+                refresh_setting_labels,
+            )
+                .chain()
+                .run_if(reset_pressed),
+            update_toast,
+        )
+            .chain(),
+    );
+}
+
+fn reset_pressed(input: Res<ButtonInput<KeyCode>>, bindings: Res<KeyBindings>) -> bool {
+    input.just_pressed(bindings.reset)
+}
+
+/// Captures `R` as it is now. It is written back only if it moved, so the systems
+/// that react to a change of `R` only run for settings that actually differ.
+fn restore<R: Resource + Clone + PartialEq>(world: &World) -> Restore {
+    let value = world.resource::<R>().clone();
+    Box::new(move |world| {
+        world.resource_mut::<R>().set_if_neq(value.clone());
+    })
+}
+
+/// Like [`restore`], but without flagging the change: `restore_walls` rebuilds the
+/// pool when [`NoWalls`] is switched off, and [`clear_pool`] rebuilds it already.
+fn restore_quietly<R: Resource + Clone>(world: &World) -> Restore {
+    let value = world.resource::<R>().clone();
+    Box::new(move |world| {
+        *world.resource_mut::<R>().bypass_change_detection() = value.clone();
+    })
+}
+
+fn capture_baseline(world: &mut World) {
+    let speed = world.resource::<Time<Virtual>>().relative_speed();
+    let settings = vec![
+        restore::<SpawnRate>(world),
+        restore::<GravityScale>(world),
+        restore::<GravityAngle>(world),
+        restore::<BallRestitution>(world),
+        restore::<BallFriction>(world),
+        restore::<BallLinearDamping>(world),
+        restore::<BallAngularDamping>(world),
+        restore::<BallDensity>(world),
+        restore::<SpawnShape>(world),
+        restore::<SpawnPattern>(world),
+        restore::<SpawnPaused>(world),
+        restore::<NozzleCount>(world),
+        restore::<BallCcd>(world),
+        restore::<RenderBalls>(world),
+        restore::<ColorBySpeed>(world),
+        restore::<ColorByMass>(world),
+        restore::<ColorBySleep>(world),
+        restore::<Substeps>(world),
+        restore::<RapierIterations>(world),
+        restore::<AvianInterpolation>(world),
+        restore::<FloorDrain>(world),
+        restore::<FloorThickness>(world),
+        restore::<TopWall>(world),
+        restore_quietly::<NoWalls>(world),
+        Box::new(move |world: &mut World| {
+            world
+                .resource_mut::<Time<Virtual>>()
+                .set_relative_speed(speed);
+        }),
+    ];
+    world.insert_resource(Baseline(settings));
+}
+
+fn restore_baseline(world: &mut World) {
+    world.resource_scope(|world, baseline: Mut<Baseline>| {
+        for restore in &baseline.0 {
+            restore(world);
+        }
+    });
+    info!("Reset every runtime setting to its startup value");
+}
+
+/// Empties the pool and rebuilds it with the restored surface, drain and lid, and
+/// restarts the milestones. The counterpart's pool under split screen is rebuilt by
+/// `split` once its floor is gone.
+fn clear_pool(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    state: Res<State<PhysicsMode>>,
+    ball_config: BallConfig,
+    wall_config: WallConfig,
+    pool: Query<Entity, Or<(With<Ball>, With<walls::Wall>)>>,
+    mut ball_count: ResMut<BallCount>,
+    thresholds: Res<FpsThresholds>,
+    mut stats: ResMut<PerfStats>,
+    mut toast: ResMut<ResetToast>,
+) {
+    for entity in &pool {
+        commands.entity(entity).despawn();
+    }
+    wall_config.spawn(
+        &mut commands,
+        &mut meshes,
+        &mut materials,
+        *state.get(),
+        ball_config.surface(),
+    );
+    ball_count.0 = 0;
+    *stats = PerfStats::new(&thresholds.0);
+    toast.0 = Some(Timer::new(TOAST_DURATION, TimerMode::Once));
+}
+
+/// The setting handlers in `main` only write their label when their key is
+/// pressed, so every label of a restored setting is rewritten here. Substeps and
+/// solver iterations refresh themselves on change.
+fn refresh_setting_labels(
+    (restitution, friction, linear, angular, density, shape, ccd, render): (
+        Res<BallRestitution>,
+        Res<BallFriction>,
+        Res<BallLinearDamping>,
+        Res<BallAngularDamping>,
+        Res<BallDensity>,
+        Res<SpawnShape>,
+        Res<BallCcd>,
+        Res<RenderBalls>,
+    ),
+    (rate, gravity, tilt, nozzles): (
+        Res<SpawnRate>,
+        Res<GravityScale>,
+        Res<GravityAngle>,
+        Res<NozzleCount>,
+    ),
+    (speed, mass, sleep): (Res<ColorBySpeed>, Res<ColorByMass>, Res<ColorBySleep>),
+    (drain, floor, top_wall, no_walls): (
+        Res<FloorDrain>,
+        Res<FloorThickness>,
+        Res<TopWall>,
+        Res<NoWalls>,
+    ),
+    vtime: Res<Time<Virtual>>,
+    mut texts: Query<(&mut Text, SettingMarkers)>,
+) {
+    let labels = [
+        crate::spawn_rate_label(*rate),
+        crate::gravity_label(*gravity),
+        crate::gravity_angle_label(*tilt),
+        crate::restitution_label(*restitution),
+        crate::friction_label(*friction),
+        crate::damping_label(*linear, *angular),
+        crate::density_label(*density),
+        crate::shape_label(*shape),
+        crate::nozzle_label(*nozzles),
+        crate::color_by_speed_label(*speed, *mass, *sleep),
+        crate::floor_drain_label(*drain),
+        crate::floor_thickness_label(*floor),
+        crate::top_wall_label(*top_wall),
+        crate::no_walls_label(*no_walls),
+        crate::ccd_label(*ccd),
+        crate::render_balls_label(*render),
+        crate::time_scale_label(vtime.relative_speed()),
+    ];
+    for (mut text, (first, second)) in &mut texts {
+        let (a, b, c, d, e, f, g, h) = first;
+        let (i, j, k, l, m, n, o, p, q) = second;
+        let markers = [a, b, c, d, e, f, g, h, i, j, k, l, m, n, o, p, q];
+        if let Some(label) = markers.iter().position(|&marker| marker) {
+            **text = labels[label].clone();
+        }
+    }
+}
+
+/// One flag per label [`refresh_setting_labels`] writes, in the same order.
+type SettingMarkers = (
+    (
+        Has<SpawnRateText>,
+        Has<GravityText>,
+        Has<GravityAngleText>,
+        Has<RestitutionText>,
+        Has<FrictionText>,
+        Has<DampingText>,
+        Has<DensityText>,
+        Has<ShapeText>,
+    ),
+    (
+        Has<NozzleText>,
+        Has<ColorBySpeedText>,
+        Has<FloorDrainText>,
+        Has<FloorThicknessText>,
+        Has<TopWallText>,
+        Has<NoWallsText>,
+        Has<CcdText>,
+        Has<RenderBallsText>,
+        Has<TimeScaleText>,
+    ),
+);
+
+fn spawn_toast(mut commands: Commands) {
+    commands
+        .spawn((
+            Name::new("Reset Toast"),
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Percent(30.0),
+                width: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+        ))
+        .with_children(|row| {
+            row.spawn((
+                ToastText,
+                Text::new("RESET"),
+                TextFont {
+                    font_size: 40.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(1.0, 0.85, 0.3)),
+                BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+                Visibility::Hidden,
+            ));
+        });
+}
+
+/// Counts down on real time, so the toast clears even while paused.
+fn update_toast(
+    real: Res<Time<Real>>,
+    mut toast: ResMut<ResetToast>,
+    mut query: Query<&mut Visibility, With<ToastText>>,
+) {
+    let showing = match &mut toast.0 {
+        Some(timer) => !timer.tick(real.delta()).is_finished(),
+        None => false,
+    };
+    if !showing {
+        toast.0 = None;
+    }
+    for mut visibility in &mut query {
+        visibility.set_if_neq(if showing {
+            Visibility::Inherited
+        } else {
+            Visibility::Hidden
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::CollisionEvents;
+    use crate::obstacles::ObstacleLayout;
+    use crate::spawner::{
+        InitialSpeed, InitialSpin, Prefill, SpawnFrozen, SpawnIndex, SpawnRng, Temperature,
+    };
+    use crate::walls::{MonoWalls, PoolConfig, RoundedWalls};
+    use crate::{ClippedBallCount, DrainedBallCount, ExplodedBallCount};
+
+    /// [`plugin`] in a 2D pool, with every resource the reset reads at its default.
+    fn make_reset_test_app() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, bevy::state::app::StatesPlugin));
+        app.insert_state(PhysicsMode::Avian2d);
+        app.init_resource::<Assets<Mesh>>()
+            .init_resource::<Assets<StandardMaterial>>()
+            .init_resource::<ButtonInput<KeyCode>>()
+            .init_resource::<KeyBindings>()
+            .init_resource::<PoolConfig>()
+            .init_resource::<RoundedWalls>()
+            .init_resource::<MonoWalls>()
+            .init_resource::<ObstacleLayout>()
+            .init_resource::<InitialSpin>()
+            .init_resource::<InitialSpeed>()
+            .init_resource::<Temperature>()
+            .init_resource::<CollisionEvents>()
+            .init_resource::<BallCount>()
+            .insert_resource(SpawnRng::new(7))
+            .init_resource::<SpawnIndex>()
+            .init_resource::<SpawnFrozen>()
+            .init_resource::<Prefill>()
+            .init_resource::<crate::WarmupTimer>()
+            .init_resource::<split::PartnerView>()
+            .init_resource::<split::PartnerRan>()
+            .init_resource::<FpsThresholds>()
+            .init_resource::<PerfStats>()
+            .init_resource::<SpawnRate>()
+            .init_resource::<GravityScale>()
+            .init_resource::<GravityAngle>()
+            .init_resource::<BallRestitution>()
+            .init_resource::<BallFriction>()
+            .init_resource::<BallLinearDamping>()
+            .init_resource::<BallAngularDamping>()
+            .init_resource::<BallDensity>()
+            .init_resource::<SpawnShape>()
+            .init_resource::<SpawnPattern>()
+            .init_resource::<SpawnPaused>()
+            .init_resource::<NozzleCount>()
+            .init_resource::<BallCcd>()
+            .init_resource::<RenderBalls>()
+            .init_resource::<ColorBySpeed>()
+            .init_resource::<ColorByMass>()
+            .init_resource::<ColorBySleep>()
+            .init_resource::<Substeps>()
+            .init_resource::<RapierIterations>()
+            .init_resource::<AvianInterpolation>()
+            .init_resource::<FloorDrain>()
+            .init_resource::<FloorThickness>()
+            .init_resource::<TopWall>()
+            .init_resource::<NoWalls>()
+            .init_resource::<ClippedBallCount>()
+            .init_resource::<ExplodedBallCount>()
+            .init_resource::<DrainedBallCount>();
+        app.add_plugins(plugin);
+        app
+    }
+
+    #[test]
+    fn reset_restores_settings_counters_and_every_label() {
+        let mut app = make_reset_test_app();
+        app.update();
+
+        let world = app.world_mut();
+        world.insert_resource(SpawnRate(500.0));
+        world.insert_resource(GravityScale(2.5));
+        world.insert_resource(GravityAngle(30.0));
+        world.insert_resource(BallRestitution(0.1));
+        world.insert_resource(BallDensity(8.0));
+        world.insert_resource(ColorBySpeed(true));
+        world.insert_resource(FloorDrain(true));
+        world.insert_resource(FloorThickness(80.0));
+        world.insert_resource(ClippedBallCount(5));
+        world.insert_resource(ExplodedBallCount(2));
+        world.insert_resource(DrainedBallCount(3));
+        world.insert_resource(BallCount(2));
+        world.insert_resource(SpawnIndex(40));
+        world.insert_resource(SpawnFrozen(true));
+        world
+            .resource_mut::<crate::WarmupTimer>()
+            .0
+            .tick(Duration::from_secs(60));
+        world
+            .resource_mut::<Time<Virtual>>()
+            .set_relative_speed(0.25);
+        let ball = world.spawn((Ball, Transform::default())).id();
+
+        let labels: [(Entity, String); 17] = [
+            (
+                world.spawn((Text::default(), SpawnRateText)).id(),
+                crate::spawn_rate_label(SpawnRate::default()),
+            ),
+            (
+                world.spawn((Text::default(), GravityText)).id(),
+                crate::gravity_label(GravityScale::default()),
+            ),
+            (
+                world.spawn((Text::default(), GravityAngleText)).id(),
+                crate::gravity_angle_label(GravityAngle::default()),
+            ),
+            (
+                world.spawn((Text::default(), RestitutionText)).id(),
+                crate::restitution_label(BallRestitution::default()),
+            ),
+            (
+                world.spawn((Text::default(), FrictionText)).id(),
+                crate::friction_label(BallFriction::default()),
+            ),
+            (
+                world.spawn((Text::default(), DampingText)).id(),
+                crate::damping_label(BallLinearDamping::default(), BallAngularDamping::default()),
+            ),
+            (
+                world.spawn((Text::default(), DensityText)).id(),
+                crate::density_label(BallDensity::default()),
+            ),
+            (
+                world.spawn((Text::default(), ShapeText)).id(),
+                crate::shape_label(SpawnShape::default()),
+            ),
+            (
+                world.spawn((Text::default(), NozzleText)).id(),
+                crate::nozzle_label(NozzleCount::default()),
+            ),
+            (
+                world.spawn((Text::default(), ColorBySpeedText)).id(),
+                crate::color_by_speed_label(
+                    ColorBySpeed::default(),
+                    ColorByMass::default(),
+                    ColorBySleep::default(),
+                ),
+            ),
+            (
+                world.spawn((Text::default(), FloorDrainText)).id(),
+                crate::floor_drain_label(FloorDrain::default()),
+            ),
+            (
+                world.spawn((Text::default(), FloorThicknessText)).id(),
+                crate::floor_thickness_label(FloorThickness::default()),
+            ),
+            (
+                world.spawn((Text::default(), TopWallText)).id(),
+                crate::top_wall_label(TopWall::default()),
+            ),
+            (
+                world.spawn((Text::default(), NoWallsText)).id(),
+                crate::no_walls_label(NoWalls::default()),
+            ),
+            (
+                world.spawn((Text::default(), CcdText)).id(),
+                crate::ccd_label(BallCcd::default()),
+            ),
+            (
+                world.spawn((Text::default(), RenderBallsText)).id(),
+                crate::render_balls_label(RenderBalls::default()),
+            ),
+            (
+                world.spawn((Text::default(), TimeScaleText)).id(),
+                crate::time_scale_label(1.0),
+            ),
+        ];
+        world
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(KeyBindings::default().reset);
+
+        app.update();
+
+        let world = app.world();
+        assert_eq!(*world.resource::<SpawnRate>(), SpawnRate::default());
+        assert_eq!(*world.resource::<GravityScale>(), GravityScale::default());
+        assert_eq!(*world.resource::<GravityAngle>(), GravityAngle::default());
+        assert_eq!(
+            *world.resource::<BallRestitution>(),
+            BallRestitution::default()
+        );
+        assert_eq!(*world.resource::<BallDensity>(), BallDensity::default());
+        assert_eq!(*world.resource::<ColorBySpeed>(), ColorBySpeed::default());
+        assert_eq!(*world.resource::<FloorDrain>(), FloorDrain::default());
+        assert_eq!(
+            *world.resource::<FloorThickness>(),
+            FloorThickness::default()
+        );
+        assert_eq!(world.resource::<Time<Virtual>>().relative_speed(), 1.0);
+        assert_eq!(world.resource::<ClippedBallCount>().0, 0);
+        assert_eq!(world.resource::<ExplodedBallCount>().0, 0);
+        assert_eq!(world.resource::<DrainedBallCount>().0, 0);
+        assert_eq!(world.resource::<BallCount>().0, 0);
+        assert_eq!(world.resource::<SpawnIndex>().0, 0);
+        assert!(!world.resource::<SpawnFrozen>().0);
+        assert_eq!(
+            world.resource::<crate::WarmupTimer>().0.elapsed(),
+            Duration::ZERO
+        );
+        assert!(world.get_entity(ball).is_err());
+
+        for (entity, label) in labels {
+            assert_eq!(world.get::<Text>(entity).unwrap().0, label);
+        }
+    }
+}
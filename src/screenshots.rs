@@ -0,0 +1,58 @@
+//! Milestone screenshots — whenever a [`PerfStats`] milestone latches, the primary
+//! window is saved as `milestone_<mode>_<threshold>_<balls>.png` in the working
+//! directory, pairing each number with the state of the pile at that moment.
+
+use bevy::prelude::*;
+use bevy::render::view::screenshot::{Screenshot, save_to_disk};
+
+use crate::PerfStats;
+use crate::backend::PhysicsMode;
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(
+        Update,
+        capture_new_milestones.run_if(resource_changed::<PerfStats>),
+    );
+}
+
+fn screenshot_path(mode: PhysicsMode, threshold: &str, balls: usize) -> String {
+    let mode = mode.label().to_lowercase().replace(' ', "");
+    format!("milestone_{mode}_{threshold}_{balls}.png")
+}
+
+/// Compares against the previous stats so only milestones that went from `None` to
+/// `Some` this frame are captured; the reset on mode enter captures nothing.
+fn capture_new_milestones(
+    mut commands: Commands,
+    state: Res<State<PhysicsMode>>,
+    stats: Res<PerfStats>,
+    mut previous: Local<Option<PerfStats>>,
+) {
+    let before = previous.as_ref().map(PerfStats::named).unwrap_or_default();
+    for (threshold, now) in stats.named() {
+        let was_reached = before
+            .iter()
+            .any(|(name, balls)| *name == threshold && balls.is_some());
+        if let (Some(balls), false) = (now, was_reached) {
+            let path = screenshot_path(*state.get(), &threshold, balls);
+            info!("Saving milestone screenshot {path}");
+            commands
+                .spawn(Screenshot::primary_window())
+                .observe(save_to_disk(path));
+        }
+    }
+    *previous = Some(stats.clone());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn screenshot_path_names_mode_threshold_and_balls() {
+        assert_eq!(
+            screenshot_path(PhysicsMode::Rapier2d, "first_below_15", 12400),
+            "milestone_rapier2d_first_below_15_12400.png"
+        );
+    }
+}
@@ -0,0 +1,112 @@
+//! Sensor zone — with `--sensors`, every mode spawns a static sensor box in the
+//! middle of the pool alongside the walls, and the HUD shows how many balls
+//! currently overlap it ("In zone: N").
+//!
+//! Balls fall straight through a sensor, so the count exercises each engine's
+//! intersection reporting rather than its solver: Avian fills the zone's
+//! `CollidingEntities`, Rapier lists intersection pairs in its narrow phase.
+
+use bevy::prelude::*;
+
+use crate::backend::{self, PhysicsMode};
+use crate::cli;
+use crate::walls::PoolConfig;
+
+/// A quarter of the pool's width, a third of its height and half its depth.
+fn zone_size(pool: PoolConfig) -> Vec3 {
+    Vec3::new(pool.width / 4.0, pool.height / 3.0, pool.depth / 2.0)
+}
+
+const ZONE_COLOR: Color = Color::srgba(0.2, 0.8, 1.0, 0.15);
+
+/// Marks the sensor box.
+#[derive(Component)]
+pub struct SensorZone;
+
+/// Whether modes spawn a sensor zone, from `--sensors`.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SensorZones(pub bool);
+
+/// Balls overlapping the sensor zone in the active mode.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ZoneOccupancy(pub usize);
+
+pub fn plugin(app: &mut App) {
+    let enabled = cli::flag("--sensors");
+    app.insert_resource(SensorZones(enabled));
+    app.init_resource::<ZoneOccupancy>();
+    if !enabled {
+        return;
+    }
+    app.add_systems(Update, update_zone_occupancy);
+    for mode in PhysicsMode::ALL {
+        app.add_systems(OnEnter(mode), spawn_zone);
+    }
+}
+
+fn spawn_zone(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    state: Res<State<PhysicsMode>>,
+    pool: Res<PoolConfig>,
+) {
+    backend::spawn_sensor_zone(
+        &mut commands,
+        &mut meshes,
+        &mut materials,
+        *state.get(),
+        Vec3::ZERO,
+        zone_size(*pool),
+        ZONE_COLOR,
+    );
+}
+
+/// Only the zone is a sensor, so every intersecting pair is a ball inside it.
+fn update_zone_occupancy(
+    state: Res<State<PhysicsMode>>,
+    mut occupancy: ResMut<ZoneOccupancy>,
+    avian2d_zones: Query<&avian2d::prelude::CollidingEntities, With<SensorZone>>,
+    avian3d_zones: Query<&avian3d::prelude::CollidingEntities, With<SensorZone>>,
+    rapier2d_sims: Query<&bevy_rapier2d::plugin::context::RapierContextSimulation>,
+    rapier3d_sims: Query<&bevy_rapier3d::plugin::context::RapierContextSimulation>,
+) {
+    let inside = match state.get() {
+        PhysicsMode::Avian2d => avian2d_zones.iter().map(|colliding| colliding.len()).sum(),
+        PhysicsMode::Avian3d => avian3d_zones.iter().map(|colliding| colliding.len()).sum(),
+        PhysicsMode::Rapier2d => rapier2d_sims
+            .iter()
+            .flat_map(|sim| sim.narrow_phase.intersection_pairs())
+            .filter(|(_, _, intersecting)| *intersecting)
+            .count(),
+        PhysicsMode::Rapier3d => rapier3d_sims
+            .iter()
+            .flat_map(|sim| sim.narrow_phase.intersection_pairs())
+            .filter(|(_, _, intersecting)| *intersecting)
+            .count(),
+    };
+    occupancy.set_if_neq(ZoneOccupancy(inside));
+}
+
+/// The HUD line, empty without `--sensors`.
+pub fn zone_label(zones: SensorZones, occupancy: ZoneOccupancy) -> String {
+    if zones.0 {
+        format!("In zone: {}", occupancy.0)
+    } else {
+        String::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zone_label_is_empty_without_sensors() {
+        assert_eq!(
+            zone_label(SensorZones(true), ZoneOccupancy(12)),
+            "In zone: 12"
+        );
+        assert_eq!(zone_label(SensorZones(false), ZoneOccupancy(12)), "");
+    }
+}
@@ -0,0 +1,167 @@
+//! Live stats over HTTP — `--serve <port>` answers `GET /stats` on
+//! `127.0.0.1:<port>` with the current mode, FPS, ball counts and milestones as JSON,
+//! for external dashboards.
+//!
+//! The server is a single blocking thread on `std::net::TcpListener`, so it adds no
+//! dependencies, and it is only compiled with the `serve` Cargo feature:
+//!
+//! ```sh
+//! cargo run --release --features serve -- --serve 8080
+//! curl http://127.0.0.1:8080/stats
+//! ```
+//!
+//! `update_fps_display` publishes a [`StatsSnapshot`] into [`SharedStats`] every
+//! frame; the server thread only ever reads the latest one.
+
+use bevy::prelude::*;
+use std::sync::{Arc, Mutex};
+
+use crate::PerfStats;
+use crate::backend::PhysicsMode;
+use crate::cli;
+
+/// Everything `/stats` reports, as of the last frame.
+// Only read by the `serve` feature's server.
+#[cfg_attr(not(feature = "serve"), allow(dead_code))]
+#[derive(Default, Clone)]
+pub(crate) struct StatsSnapshot {
+    pub mode: PhysicsMode,
+    pub fps: f64,
+    pub avg_fps: f64,
+    pub ball_count: usize,
+    pub clipped: usize,
+    pub milestones: PerfStats,
+}
+
+impl StatsSnapshot {
+    #[cfg(any(feature = "serve", test))]
+    fn to_json(&self) -> String {
+        let milestones: Vec<String> = self
+            .milestones
+            .named()
+            .into_iter()
+            .map(|(name, balls)| {
+                let balls = balls.map_or_else(|| "null".to_string(), |n| n.to_string());
+                format!(r#""{name}":{balls}"#)
+            })
+            .collect();
+        format!(
+            concat!(
+                r#"{{"mode":"{}","fps":{:.1},"avg_fps":{:.1},"ball_count":{},"clipped":{},"#,
+                r#""milestones":{{{}}}}}"#,
+            ),
+            self.mode.label(),
+            self.fps,
+            self.avg_fps,
+            self.ball_count,
+            self.clipped,
+            milestones.join(","),
+        )
+    }
+}
+
+/// Latest [`StatsSnapshot`], shared with the server thread. Only present while
+/// serving, so publishing costs nothing otherwise.
+// Only constructed by the `serve` feature's server.
+#[cfg_attr(not(feature = "serve"), allow(dead_code))]
+#[derive(Resource, Clone, Default)]
+pub(crate) struct SharedStats(Arc<Mutex<StatsSnapshot>>);
+
+impl SharedStats {
+    pub(crate) fn publish(&self, snapshot: StatsSnapshot) {
+        if let Ok(mut latest) = self.0.lock() {
+            *latest = snapshot;
+        }
+    }
+}
+
+pub fn plugin(app: &mut App) {
+    let Some(port) = cli::parsed::<u16>("--serve") else {
+        return;
+    };
+
+    #[cfg(feature = "serve")]
+    {
+        let shared = SharedStats::default();
+        match server::start(port, shared.clone()) {
+            Ok(()) => {
+                info!("Serving stats on http://127.0.0.1:{port}/stats");
+                app.insert_resource(shared);
+            }
+            Err(err) => warn!("Failed to serve stats on port {port}: {err}"),
+        }
+    }
+    #[cfg(not(feature = "serve"))]
+    {
+        let _ = app;
+        warn!("Ignoring --serve {port}: built without the `serve` feature");
+    }
+}
+
+#[cfg(feature = "serve")]
+mod server {
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::{TcpListener, TcpStream};
+    use std::time::Duration;
+
+    use super::SharedStats;
+
+    /// How long a client gets to send its request line. The server answers one
+    /// connection at a time, so a client that connects and sends nothing would
+    /// otherwise block every later request.
+    const READ_TIMEOUT: Duration = Duration::from_secs(2);
+
+    /// Binds the port and answers requests on a background thread.
+    pub(super) fn start(port: u16, shared: SharedStats) -> std::io::Result<()> {
+        let listener = TcpListener::bind(("127.0.0.1", port))?;
+        std::thread::Builder::new()
+            .name("stats-server".to_string())
+            .spawn(move || {
+                for stream in listener.incoming().flatten() {
+                    // A client that hangs up mid-request is not worth reporting.
+                    let _ = respond(stream, &shared);
+                }
+            })?;
+        Ok(())
+    }
+
+    fn respond(mut stream: TcpStream, shared: &SharedStats) -> std::io::Result<()> {
+        stream.set_read_timeout(Some(READ_TIMEOUT))?;
+        let mut request_line = String::new();
+        BufReader::new(&stream).read_line(&mut request_line)?;
+
+        let (status, body) = if request_line.starts_with("GET /stats ") {
+            let snapshot = shared.0.lock().unwrap_or_else(|e| e.into_inner()).clone();
+            ("200 OK", snapshot.to_json())
+        } else {
+            ("404 Not Found", r#"{"error":"not found"}"#.to_string())
+        };
+        write!(
+            stream,
+            "HTTP/1.1 {status}\r\nContent-Type: application/json\r\nAccess-Control-Allow-Origin: *\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+            body.len(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snapshot_json_reports_unreached_milestones_as_null() {
+        let mut snapshot = StatsSnapshot {
+            mode: PhysicsMode::Rapier3d,
+            fps: 59.96,
+            avg_fps: 60.0,
+            ball_count: 1200,
+            clipped: 3,
+            milestones: PerfStats::new(&[50.0, 15.0]),
+        };
+        snapshot.milestones.below[0].first = Some(900);
+        assert_eq!(
+            snapshot.to_json(),
+            r#"{"mode":"Rapier 3D","fps":60.0,"avg_fps":60.0,"ball_count":1200,"clipped":3,"milestones":{"first_below_50":900,"avg_below_50":null,"first_below_15":null,"avg_below_15":null,"p99_above_33":null,"balls_at_target":null}}"#
+        );
+    }
+}
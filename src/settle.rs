@@ -0,0 +1,304 @@
+//! Settle and report — `Z` freezes the spawner and waits for the pile to come to
+//! rest, then reports the numbers that only mean something once motion stops.
+//!
+//! The pile counts as at rest once its kinetic energy per unit mass (see
+//! [`KineticEnergy::per_unit_mass`], the same in every engine) stays below
+//! `--settle-threshold` for [`CALM_PERIOD`]. The report — ball count, average FPS
+//! over that calm period and the deepest contact penetration — is printed to stdout
+//! and appended as one row to a CSV file, unless another engine ran alongside (see
+//! [`split::solo_run`]).
+//!
+//! The file path is read from the `AVR_SETTLE_CSV` environment variable (default
+//! `settle.csv`). Spawning stays frozen after the report; `Z` again resumes it.
+
+use bevy::prelude::*;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Write;
+use std::time::Duration;
+
+use crate::backend::{KineticEnergy, PenetrationStats, PhysicsMode};
+use crate::keys::KeyBindings;
+use crate::spawner::{BallCount, SpawnFrozen};
+use crate::split::{self, PartnerRan};
+use crate::{benchmark, cli};
+
+/// Environment variable that overrides the settle CSV output path.
+const CSV_PATH_ENV: &str = "AVR_SETTLE_CSV";
+const DEFAULT_CSV_PATH: &str = "settle.csv";
+
+const CSV_HEADER: &str = "mode,balls,settle_secs,avg_fps,max_penetration";
+
+/// How long the energy must stay below the threshold.
+const CALM_PERIOD: Duration = Duration::from_secs(1);
+
+/// Default `--settle-threshold`, in px²/s² per unit mass: an RMS speed of 10 px/s.
+const DEFAULT_THRESHOLD: f32 = 50.0;
+
+/// Energy per unit mass below which the pile counts as at rest.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+struct SettleThreshold(f32);
+
+/// A settle run started with `Z`. Removed by `Z` or a mode change.
+#[derive(Resource)]
+struct SettleRun {
+    /// Real time spent waiting so far.
+    waited: Duration,
+    /// Time the pile has been at rest, and the frames rendered meanwhile.
+    calm: Timer,
+    frames: u32,
+    report: Option<SettleReport>,
+}
+
+impl Default for SettleRun {
+    fn default() -> Self {
+        Self {
+            waited: Duration::ZERO,
+            calm: Timer::new(CALM_PERIOD, TimerMode::Once),
+            frames: 0,
+            report: None,
+        }
+    }
+}
+
+/// Numbers latched once the pile is at rest.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct SettleReport {
+    mode: PhysicsMode,
+    balls: usize,
+    /// Real time from `Z` to the end of the calm period.
+    settle_secs: f32,
+    /// Average FPS over the calm period.
+    avg_fps: f32,
+    /// Deepest contact penetration at the end, in px.
+    max_penetration: f32,
+}
+
+impl SettleReport {
+    fn csv_row(&self) -> String {
+        format!(
+            "{},{},{:.1},{:.1},{:.3}",
+            self.mode.label(),
+            self.balls,
+            self.settle_secs,
+            self.avg_fps,
+            self.max_penetration
+        )
+    }
+
+    fn summary(&self) -> String {
+        format!(
+            "Settled after {:.1} s: {} balls  |  {:.0} FPS at rest  |  max penetration {:.3} px",
+            self.settle_secs, self.balls, self.avg_fps, self.max_penetration
+        )
+    }
+}
+
+/// Average FPS at rest from each mode's latest settle report, so the comparison
+/// table survives mode switches.
+#[derive(Resource, Default)]
+pub(crate) struct SettledFps(HashMap<PhysicsMode, f32>);
+
+impl SettledFps {
+    pub(crate) fn get(&self, mode: PhysicsMode) -> Option<f32> {
+        self.0.get(&mode).copied()
+    }
+}
+
+/// Open handle to the settle CSV. Only inserted if the file could be opened.
+#[derive(Resource)]
+struct SettleLog {
+    file: File,
+}
+
+#[derive(Component)]
+struct SettleText;
+
+pub fn plugin(app: &mut App) {
+    app.insert_resource(SettleThreshold(
+        cli::parsed("--settle-threshold").unwrap_or(DEFAULT_THRESHOLD),
+    ));
+    app.init_resource::<SettledFps>();
+    app.add_systems(Startup, (open_settle_log, spawn_settle_label));
+    app.add_systems(
+        Update,
+        (
+            handle_settle,
+            run_settle.run_if(resource_exists::<SettleRun>),
+            update_settle_label,
+        )
+            .chain(),
+    );
+    for mode in PhysicsMode::ALL {
+        app.add_systems(OnExit(mode), end_settle_run);
+    }
+}
+
+/// A mode change ends the run; the next mode's `OnEnter` unfreezes the spawner.
+fn end_settle_run(mut commands: Commands) {
+    commands.remove_resource::<SettleRun>();
+}
+
+fn open_settle_log(mut commands: Commands) {
+    if let Some(file) = benchmark::open_csv(CSV_PATH_ENV, DEFAULT_CSV_PATH, CSV_HEADER) {
+        commands.insert_resource(SettleLog { file });
+    }
+}
+
+fn spawn_settle_label(mut commands: Commands) {
+    commands
+        .spawn((
+            Name::new("Settle Report"),
+            Node {
+                position_type: PositionType::Absolute,
+                top: Val::Percent(30.0),
+                width: Val::Percent(100.0),
+                justify_content: JustifyContent::Center,
+                ..default()
+            },
+        ))
+        .with_children(|row| {
+            row.spawn((
+                SettleText,
+                Text::new(""),
+                TextFont {
+                    font_size: 22.0,
+                    ..default()
+                },
+                TextColor(Color::srgb(1.0, 0.85, 0.3)),
+                BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+            ));
+        });
+}
+
+/// `Z` starts a settle run, freezing the spawner; while one is waiting or showing
+/// its report, `Z` ends it and resumes spawning.
+fn handle_settle(
+    mut commands: Commands,
+    input: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    run: Option<Res<SettleRun>>,
+    mut frozen: ResMut<SpawnFrozen>,
+) {
+    if !input.just_pressed(bindings.settle) {
+        return;
+    }
+    if run.is_some() {
+        commands.remove_resource::<SettleRun>();
+        frozen.0 = false;
+    } else {
+        commands.init_resource::<SettleRun>();
+        frozen.0 = true;
+    }
+}
+
+/// Waits, in real time, for the energy to stay below the threshold for the calm
+/// period, then latches and logs the report. Any rise above it restarts the period.
+fn run_settle(
+    time: Res<Time<Real>>,
+    state: Res<State<PhysicsMode>>,
+    threshold: Res<SettleThreshold>,
+    energy: Res<KineticEnergy>,
+    penetration: Res<PenetrationStats>,
+    ball_count: Res<BallCount>,
+    mut run: ResMut<SettleRun>,
+    mut settled: ResMut<SettledFps>,
+    log: Option<ResMut<SettleLog>>,
+    ran: Option<Res<PartnerRan>>,
+) {
+    if run.report.is_some() {
+        return;
+    }
+    run.waited += time.delta();
+    if energy.per_unit_mass() > threshold.0 {
+        run.calm.reset();
+        run.frames = 0;
+        return;
+    }
+    run.calm.tick(time.delta());
+    run.frames += 1;
+    if run.calm.elapsed() < run.calm.duration() {
+        return;
+    }
+
+    let report = SettleReport {
+        mode: *state.get(),
+        balls: ball_count.0,
+        settle_secs: run.waited.as_secs_f32(),
+        avg_fps: run.frames as f32 / run.calm.elapsed_secs(),
+        max_penetration: penetration.max,
+    };
+    println!("{}: {}", report.mode.label(), report.summary());
+    if split::solo_run(ran) {
+        if let Some(mut log) = log
+            && let Err(err) = writeln!(log.file, "{}", report.csv_row())
+        {
+            warn!("Failed to write settle log row: {err}");
+        }
+        settled.0.insert(report.mode, report.avg_fps);
+    }
+    run.report = Some(report);
+}
+
+fn settle_label(run: Option<&SettleRun>) -> String {
+    match run {
+        None => String::new(),
+        Some(SettleRun {
+            report: Some(report),
+            ..
+        }) => format!("{}  (Z resumes)", report.summary()),
+        Some(run) => format!(
+            "Settling... at rest for {:.1} / {:.1} s  (Z cancels)",
+            run.calm.elapsed_secs(),
+            CALM_PERIOD.as_secs_f32()
+        ),
+    }
+}
+
+fn update_settle_label(
+    run: Option<Res<SettleRun>>,
+    mut query: Query<(&mut Text, &mut Visibility), With<SettleText>>,
+) {
+    let label = settle_label(run.as_deref());
+    for (mut text, mut visibility) in &mut query {
+        // Hidden rather than empty, so the background doesn't linger as a blank bar.
+        visibility.set_if_neq(if label.is_empty() {
+            Visibility::Hidden
+        } else {
+            Visibility::Inherited
+        });
+        if **text != label {
+            **text = label.clone();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_formats_csv_row_and_summary() {
+        let report = SettleReport {
+            mode: PhysicsMode::Avian3d,
+            balls: 5000,
+            settle_secs: 12.34,
+            avg_fps: 58.26,
+            max_penetration: 0.4215,
+        };
+        assert_eq!(report.csv_row(), "Avian 3D,5000,12.3,58.3,0.422");
+        assert_eq!(
+            report.summary(),
+            "Settled after 12.3 s: 5000 balls  |  58 FPS at rest  |  max penetration 0.422 px"
+        );
+    }
+
+    #[test]
+    fn label_follows_the_run() {
+        assert_eq!(settle_label(None), "");
+        assert_eq!(
+            settle_label(Some(&SettleRun::default())),
+            "Settling... at rest for 0.0 / 1.0 s  (Z cancels)"
+        );
+    }
+}
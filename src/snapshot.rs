@@ -0,0 +1,255 @@
+//! Scenario snapshots — `F5` saves every ball's transform, velocity and shape to a
+//! file, `F9` replaces the current balls with the saved ones. The file stores plain
+//! numbers rather than engine types, so a pile saved in Avian can be loaded in
+//! Rapier (or the other way round) to see how each engine continues from it.
+//!
+//! The path is read from the `AVR_SNAPSHOT` environment variable (default
+//! `snapshot.ron`). The file is a RON list of [`BallState`]s: translation, rotation
+//! quaternion, uniform scale, linear and angular velocity and shape of each ball.
+
+use bevy::prelude::*;
+use ron::ser::PrettyConfig;
+use serde::{Deserialize, Serialize};
+
+use crate::backend::{self, BallAssets, PhysicsMode, SpawnShape};
+use crate::keys::KeyBindings;
+use crate::spawner::{Ball, BallConfig, BallCount};
+
+/// Environment variable that overrides the snapshot path.
+const PATH_ENV: &str = "AVR_SNAPSHOT";
+const DEFAULT_PATH: &str = "snapshot.ron";
+
+/// Backend-independent state of one ball. 2D engines use the XY components of the
+/// linear velocity and the Z component of the angular velocity.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+struct BallState {
+    translation: Vec3,
+    rotation: Quat,
+    scale: f32,
+    linvel: Vec3,
+    angvel: Vec3,
+    shape: SpawnShape,
+}
+
+impl BallState {
+    fn new(transform: &Transform, linvel: Vec3, angvel: Vec3, shape: SpawnShape) -> Self {
+        Self {
+            translation: transform.translation,
+            rotation: transform.rotation,
+            scale: transform.scale.x,
+            linvel,
+            angvel,
+            shape,
+        }
+    }
+}
+
+fn to_ron(balls: &[BallState]) -> Result<String, ron::Error> {
+    ron::ser::to_string_pretty(balls, PrettyConfig::default())
+}
+
+/// Parses a snapshot file. A hand-edited rotation is renormalised and a polygon's
+/// side count clamped, so every ball can still be spawned.
+fn from_ron(text: &str) -> ron::error::SpannedResult<Vec<BallState>> {
+    let mut balls: Vec<BallState> = ron::from_str(text)?;
+    for ball in &mut balls {
+        ball.rotation = ball.rotation.normalize();
+        ball.shape = ball.shape.clamped();
+    }
+    Ok(balls)
+}
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(Update, (save_snapshot, load_snapshot));
+}
+
+fn snapshot_path() -> String {
+    std::env::var(PATH_ENV).unwrap_or_else(|_| DEFAULT_PATH.to_string())
+}
+
+/// `F5` writes the active backend's balls to the snapshot file.
+fn save_snapshot(
+    input: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    state: Res<State<PhysicsMode>>,
+    avian2d_balls: Query<
+        (
+            &Transform,
+            &avian2d::prelude::LinearVelocity,
+            &avian2d::prelude::AngularVelocity,
+            &SpawnShape,
+        ),
+        With<Ball>,
+    >,
+    avian3d_balls: Query<
+        (
+            &Transform,
+            &avian3d::prelude::LinearVelocity,
+            &avian3d::prelude::AngularVelocity,
+            &SpawnShape,
+        ),
+        With<Ball>,
+    >,
+    rapier2d_balls: Query<(&Transform, &bevy_rapier2d::prelude::Velocity, &SpawnShape), With<Ball>>,
+    rapier3d_balls: Query<(&Transform, &bevy_rapier3d::prelude::Velocity, &SpawnShape), With<Ball>>,
+) {
+    if !input.just_pressed(bindings.snapshot_save) {
+        return;
+    }
+
+    let balls: Vec<BallState> = match state.get() {
+        PhysicsMode::Avian2d => avian2d_balls
+            .iter()
+            .map(|(t, v, w, s)| BallState::new(t, v.0.extend(0.0), Vec3::Z * w.0, *s))
+            .collect(),
+        PhysicsMode::Avian3d => avian3d_balls
+            .iter()
+            .map(|(t, v, w, s)| BallState::new(t, v.0, w.0, *s))
+            .collect(),
+        PhysicsMode::Rapier2d => rapier2d_balls
+            .iter()
+            .map(|(t, v, s)| BallState::new(t, v.linvel.extend(0.0), Vec3::Z * v.angvel, *s))
+            .collect(),
+        PhysicsMode::Rapier3d => rapier3d_balls
+            .iter()
+            .map(|(t, v, s)| BallState::new(t, v.linvel, v.angvel, *s))
+            .collect(),
+    };
+
+    let path = snapshot_path();
+    let contents = match to_ron(&balls) {
+        Ok(contents) => contents,
+        Err(err) => {
+            warn!("Failed to serialize snapshot: {err}");
+            return;
+        }
+    };
+    match std::fs::write(&path, contents) {
+        Ok(()) => info!("Saved {} balls to {path}", balls.len()),
+        Err(err) => warn!("Failed to save snapshot to {path}: {err}"),
+    }
+}
+
+/// `F9` despawns the current balls and respawns the snapshot's balls in the
+/// active backend, with their saved transforms and velocities. Surface and CCD
+/// come from the current settings.
+fn load_snapshot(
+    mut commands: Commands,
+    input: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    state: Res<State<PhysicsMode>>,
+    ball_assets: Res<BallAssets>,
+    config: BallConfig,
+    mut ball_count: ResMut<BallCount>,
+    balls: Query<Entity, With<Ball>>,
+) {
+    if !input.just_pressed(bindings.snapshot_load) {
+        return;
+    }
+
+    let path = snapshot_path();
+    let contents = match std::fs::read_to_string(&path) {
+        Ok(contents) => contents,
+        Err(err) => {
+            warn!("Failed to load snapshot from {path}: {err}");
+            return;
+        }
+    };
+    let saved = match from_ron(&contents) {
+        Ok(saved) => saved,
+        Err(err) => {
+            warn!("Failed to load snapshot from {path}: {err}");
+            return;
+        }
+    };
+
+    for ball in &balls {
+        commands.entity(ball).despawn();
+    }
+    let mode = *state.get();
+    for ball in &saved {
+        let mut params = config.params(ball.scale * ball_assets.radius);
+        params.shape = ball.shape;
+        let entity =
+            backend::spawn_ball(&mut commands, mode, ball.translation, params, &ball_assets);
+        let mut entity = commands.entity(entity);
+        entity.insert(Transform {
+            translation: ball.translation,
+            rotation: ball.rotation,
+            scale: Vec3::splat(ball.scale),
+        });
+        match mode {
+            PhysicsMode::Avian2d => entity.insert((
+                avian2d::prelude::LinearVelocity(ball.linvel.truncate()),
+                avian2d::prelude::AngularVelocity(ball.angvel.z),
+            )),
+            PhysicsMode::Avian3d => entity.insert((
+                avian3d::prelude::LinearVelocity(ball.linvel),
+                avian3d::prelude::AngularVelocity(ball.angvel),
+            )),
+            PhysicsMode::Rapier2d => entity.insert(bevy_rapier2d::prelude::Velocity {
+                linvel: ball.linvel.truncate(),
+                angvel: ball.angvel.z,
+            }),
+            PhysicsMode::Rapier3d => entity.insert(bevy_rapier3d::prelude::Velocity {
+                linvel: ball.linvel,
+                angvel: ball.angvel,
+            }),
+        };
+    }
+    ball_count.0 = saved.len();
+    info!("Loaded {} balls from {path}", saved.len());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::NgonSides;
+
+    fn ball(shape: SpawnShape) -> BallState {
+        BallState {
+            translation: Vec3::new(1.5, -2.0, 3.25),
+            rotation: Quat::from_rotation_z(0.5),
+            scale: 1.5,
+            linvel: Vec3::new(10.0, -20.0, 0.0),
+            angvel: Vec3::new(0.0, 0.0, 2.0),
+            shape,
+        }
+    }
+
+    #[test]
+    fn ball_states_round_trip_through_ron() {
+        let balls = [ball(SpawnShape::Capsule), ball(SpawnShape::Circle)];
+        let parsed = from_ron(&to_ron(&balls).unwrap()).unwrap();
+        assert_eq!(parsed.len(), 2);
+        for (parsed, ball) in parsed.iter().zip(&balls) {
+            assert_eq!(parsed.translation, ball.translation);
+            assert!(parsed.rotation.abs_diff_eq(ball.rotation, 1e-6));
+            assert_eq!(parsed.scale, ball.scale);
+            assert_eq!(parsed.linvel, ball.linvel);
+            assert_eq!(parsed.angvel, ball.angvel);
+            assert_eq!(parsed.shape, ball.shape);
+        }
+    }
+
+    #[test]
+    fn polygon_shapes_keep_their_side_count() {
+        let balls = [ball(SpawnShape::Ngon(3)), ball(SpawnShape::Ngon(12))];
+        let parsed = from_ron(&to_ron(&balls).unwrap()).unwrap();
+        assert_eq!(parsed[0].shape, SpawnShape::Ngon(3));
+        assert_eq!(parsed[1].shape, SpawnShape::Ngon(12));
+
+        let text = to_ron(&[ball(SpawnShape::Ngon(200))]).unwrap();
+        assert_eq!(
+            from_ron(&text).unwrap()[0].shape,
+            SpawnShape::Ngon(NgonSides::MAX)
+        );
+    }
+
+    #[test]
+    fn malformed_snapshots_are_rejected() {
+        assert!(from_ron("[(translation: (1.0, 2.0, 3.0))]").is_err());
+        let text = to_ron(&[ball(SpawnShape::Circle)]).unwrap();
+        assert!(from_ron(&text.replace("Circle", "Sphere")).is_err());
+    }
+}
@@ -1,26 +1,54 @@
-//! Ball spawner — drops small balls from the top of the screen on a timer.
+//! Ball spawner — drops small balls from the top of the screen on a timer,
+//! at the cursor on left click, or in bulk with `Shift` + digit.
 
-use bevy::prelude::*;
-use rand::Rng;
+use bevy::{ecs::system::SystemParam, prelude::*, window::PrimaryWindow};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
 use std::time::Duration;
 
-use crate::backend::{self, BallAssets, POOL_DEPTH, PhysicsMode};
+use crate::backend::{
+    self, BallAssets, BallParams, BodyOwner, CollisionEvents, Damping, InFlight, LengthUnit,
+    NgonSides, PhysicsMode, ShapeMeshes, SpawnShape, Surface,
+};
+use crate::cli;
+use crate::drag::DragState;
+use crate::keys::KeyBindings;
+use crate::obstacles::ObstacleLayout;
+use crate::perf_graph::PerfGraphCamera;
+use crate::split::{self, PartnerView, SplitPartner};
+use crate::walls::{FloorThickness, PoolConfig, TopWall};
 
-/// Time between ball spawns. Tweak this to control spawn rate.
-const SPAWN_INTERVAL: Duration = Duration::from_millis(50);
+/// Default spawn rate in balls per second. Adjustable at runtime via [`SpawnRate`].
+/// Measured in virtual time, so pausing or slowing the simulation slows spawning too.
+const DEFAULT_SPAWN_RATE: f32 = 20.0;
+
+/// Shortest time between spawn ticks. Faster rates spawn batches at about this
+/// interval instead of ever-shorter intervals.
+const MIN_SPAWN_INTERVAL: Duration = Duration::from_millis(20);
 
 /// Ball radius in pixels.
-const BALL_RADIUS: f32 = 6.0;
+pub const BALL_RADIUS: f32 = 6.0;
+
+/// Gap between the spawn range, widened by the ball radius, and the inner face of
+/// each side wall.
+const SPAWN_X_MARGIN: f32 = 15.0;
+
+/// Gap between the drop line and the top of the pool, where balls appear.
+const SPAWN_TOP_MARGIN: f32 = 10.0;
 
-/// Ball colour — shared across all modes.
-const BALL_COLOR: Color = Color::srgb(0.9, 0.3, 0.2);
+/// Gap between the 3D spawn region, widened by the ball radius, and the inner face of
+/// the front/back walls.
+const SPAWN_Z_MARGIN: f32 = 40.0;
 
-/// Horizontal spawn range (inside the walls, with a small margin).
-const SPAWN_X_MIN: f32 = -945.0;
-const SPAWN_X_MAX: f32 = 945.0;
+/// Lattice spacing for [`SpawnPattern::Grid`] and vertical offset between balls that
+/// share a spawn point in the same tick. Two diameters leaves room for every shape.
+const GRID_SPACING: f32 = 4.0 * BALL_RADIUS;
 
-/// Y position where balls appear (just below the top of screen).
-const SPAWN_Y: f32 = 530.0;
+/// Seed used when neither `--seed` nor `AVR_SEED` is given.
+const DEFAULT_SEED: u64 = 42;
+
+/// Environment variable fallback for `--seed`.
+const SEED_ENV: &str = "AVR_SEED";
 
 /// Marker component for counting balls.
 #[derive(Component)]
@@ -30,13 +58,595 @@ pub struct Ball;
 #[derive(Resource, Default)]
 pub struct BallCount(pub usize);
 
-/// Resource that controls how many balls are spawned per timer tick.
-#[derive(Resource)]
-pub struct BallsPerTick(pub usize);
+/// Timed spawn rate in balls per second. The spawn timer's interval and the number
+/// of balls per tick are both derived from it (see [`SpawnRate::schedule`]).
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct SpawnRate(pub f32);
+
+impl Default for SpawnRate {
+    fn default() -> Self {
+        Self(DEFAULT_SPAWN_RATE)
+    }
+}
+
+impl SpawnRate {
+    pub const MIN: f32 = 1.0;
+    pub const MAX: f32 = 10_000.0;
+
+    /// Timer interval and balls per tick that together spawn at this rate. Low rates
+    /// drop one ball per longer interval; high rates drop the smallest whole batch
+    /// that keeps the interval at or above [`MIN_SPAWN_INTERVAL`], with the interval
+    /// stretched so the rate stays exact.
+    pub fn schedule(self) -> (Duration, usize) {
+        let rate = self.0.clamp(Self::MIN, Self::MAX);
+        let batch = (rate * MIN_SPAWN_INTERVAL.as_secs_f32()).ceil().max(1.0);
+        (
+            Duration::from_secs_f64(f64::from(batch) / f64::from(rate)),
+            batch as usize,
+        )
+    }
+}
+
+/// Seeded RNG for spawn positions, so every backend sees the identical drop pattern.
+/// Reset to its initial seed on every `OnEnter`, restarting the same sequence.
+#[derive(Resource, Deref, DerefMut)]
+pub struct SpawnRng {
+    seed: u64,
+    #[deref]
+    rng: StdRng,
+}
+
+impl SpawnRng {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.rng = StdRng::seed_from_u64(self.seed);
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+/// Default for [`MaxBalls`] when `--max-balls` is not given.
+const DEFAULT_MAX_BALLS: usize = 50_000;
+
+/// Upper bound on live balls; `0` means unlimited. Set with `--max-balls`.
+/// Once reached, the spawn timer keeps ticking but no new balls are spawned.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MaxBalls(pub usize);
+
+impl MaxBalls {
+    pub fn reached(self, count: usize) -> bool {
+        self.0 != 0 && count >= self.0
+    }
+}
+
+/// Balls the timed spawner keeps in flight (see [`InFlight`]); `0`, the default,
+/// spawns at the plain [`SpawnRate`]. Set with `--target-in-flight`.
+///
+/// With a target the spawner only tops up what has landed since the last frame, so
+/// the falling workload stays constant however large the pile grows. The spawn rate
+/// still caps how fast it tops up.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TargetInFlight(pub usize);
+
+impl TargetInFlight {
+    /// Balls the spawner may add while `in_flight` are already falling.
+    fn budget(self, in_flight: InFlight) -> usize {
+        if self.0 == 0 {
+            usize::MAX
+        } else {
+            self.0.saturating_sub(in_flight.0)
+        }
+    }
+}
+
+/// Restitution (bounciness) given to newly spawned balls and to the walls on mode enter.
+/// Read at spawn time, so changing it only affects balls spawned afterwards.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct BallRestitution(pub f32);
+
+impl Default for BallRestitution {
+    fn default() -> Self {
+        Self(0.5)
+    }
+}
+
+/// Friction coefficient given to newly spawned balls and to the walls on mode enter.
+/// Combined by multiplication in every backend.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct BallFriction(pub f32);
+
+impl Default for BallFriction {
+    fn default() -> Self {
+        Self(0.5)
+    }
+}
+
+/// Linear velocity damping given to newly spawned balls (see [`Damping`]).
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Default)]
+pub struct BallLinearDamping(pub f32);
+
+/// Angular velocity damping given to newly spawned balls (see [`Damping`]).
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Default)]
+pub struct BallAngularDamping(pub f32);
+
+/// Collider density of newly spawned balls (`--density`, default 1.0). Unlike the
+/// other ball settings, changing it also updates every ball already in the pool, so
+/// the whole pile gets heavier or lighter at once.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct BallDensity(pub f32);
 
-impl Default for BallsPerTick {
+impl Default for BallDensity {
     fn default() -> Self {
-        Self(1)
+        Self(1.0)
+    }
+}
+
+/// Balls dropped at once by `F2` (`--burst-size`, default 2000).
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BurstSize(pub usize);
+
+impl Default for BurstSize {
+    fn default() -> Self {
+        Self(2_000)
+    }
+}
+
+/// `Time<Real>` elapsed time at which the last `F2` burst was spawned, so the HUD
+/// can measure the frame-time spike that follows it.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct LastBurst(pub Option<Duration>);
+
+/// Range newly spawned balls draw their radius from, uniformly via [`SpawnRng`].
+/// Set with `--radius-min` / `--radius-max`; defaults to a single size of
+/// [`BALL_RADIUS`], which draws nothing so the drop sequence is unchanged.
+/// Radii above `2 * BALL_RADIUS` make neighbouring grid-pattern balls overlap.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct RadiusRange(pub f32, pub f32);
+
+impl Default for RadiusRange {
+    fn default() -> Self {
+        Self(BALL_RADIUS, BALL_RADIUS)
+    }
+}
+
+impl RadiusRange {
+    /// Smallest accepted radius, so a typo can't create near-zero colliders.
+    pub const MIN: f32 = 1.0;
+
+    fn from_args() -> Self {
+        let min = cli::parsed("--radius-min")
+            .unwrap_or(BALL_RADIUS)
+            .max(Self::MIN);
+        let max = cli::parsed("--radius-max").unwrap_or(min).max(min);
+        Self(min, max)
+    }
+
+    /// Middle of the range; used by click spawns, which must not consume the
+    /// seeded sequence.
+    fn midpoint(self) -> f32 {
+        (self.0 + self.1) / 2.0
+    }
+
+    fn sample(self, rng: &mut StdRng) -> f32 {
+        if self.0 < self.1 {
+            rng.random_range(self.0..=self.1)
+        } else {
+            self.0
+        }
+    }
+}
+
+/// Drop range, derived from the [`PoolConfig`]; `y` is the drop line and `z_half` the
+/// half-depth of the 3D spawn region.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct SpawnConfig {
+    pub x_min: f32,
+    pub x_max: f32,
+    pub y: f32,
+    pub z_half: f32,
+}
+
+impl SpawnConfig {
+    /// Drops from [`SPAWN_TOP_MARGIN`] below the top of `pool`, across the inside of
+    /// its walls minus [`SPAWN_X_MARGIN`] on each side, and in 3D across its depth
+    /// minus [`SPAWN_Z_MARGIN`] at the front and back, with room for balls up to
+    /// `max_radius`. The margins scale with the pool.
+    pub fn for_pool(pool: PoolConfig, max_radius: f32) -> Self {
+        let clearance = pool.thickness + max_radius;
+        let half_range = pool.width / 2.0 - clearance - SPAWN_X_MARGIN * pool.scale;
+        Self {
+            x_min: -half_range,
+            x_max: half_range,
+            y: pool.height / 2.0 - SPAWN_TOP_MARGIN * pool.scale,
+            z_half: pool.depth / 2.0 - clearance - SPAWN_Z_MARGIN * pool.scale,
+        }
+    }
+
+    /// Pulls `position` in from the sides (and front and back) by `inset`, for a ball
+    /// that much larger than the range was sized for.
+    fn inset(&self, position: Vec3, inset: f32) -> Vec3 {
+        let x = position.x.clamp(
+            self.x_min + inset,
+            (self.x_max - inset).max(self.x_min + inset),
+        );
+        let z_half = (self.z_half - inset).max(0.0);
+        Vec3::new(x, position.y, position.z.clamp(-z_half, z_half))
+    }
+}
+
+impl Default for SpawnConfig {
+    fn default() -> Self {
+        Self::for_pool(PoolConfig::default(), BALL_RADIUS)
+    }
+}
+
+/// Number of evenly spaced drop points the [`SpawnPattern::Rain`] pattern spreads
+/// balls across, in turn; `0` drops anywhere in the spawn range. Adjusted with `O` / `P`.
+/// Spreading the stream fills the pool evenly instead of building a central tower.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct NozzleCount(pub usize);
+
+impl NozzleCount {
+    pub const MAX: usize = 64;
+
+    /// Centre X of `nozzle` within the spawn range.
+    fn x(self, nozzle: usize, config: &SpawnConfig) -> f32 {
+        let width = (config.x_max - config.x_min) / self.0 as f32;
+        config.x_min + (nozzle as f32 + 0.5) * width
+    }
+}
+
+/// Random X offset (px) each nozzle adds to its drop point.
+const NOZZLE_JITTER: f32 = BALL_RADIUS;
+
+/// Default for [`SpawnJitter`] when `--spawn-jitter` is not given.
+const DEFAULT_SPAWN_JITTER: f32 = 0.5;
+
+/// Largest random offset (px) added to every timed spawn's X, and Z in 3D, on top
+/// of its pattern position. Set with `--spawn-jitter`; `0` keeps the exact positions.
+///
+/// At high frame rates the timer drops balls in lockstep, so the grid and column
+/// patterns (and the nozzles, once their own jitter repeats) land on exactly the same
+/// spots and build standing structures no real stream would. A fraction of a pixel
+/// is enough to break that. Drawn from the seeded spawn RNG, so runs stay reproducible.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct SpawnJitter(pub f32);
+
+impl Default for SpawnJitter {
+    fn default() -> Self {
+        Self(DEFAULT_SPAWN_JITTER)
+    }
+}
+
+impl SpawnJitter {
+    /// Offsets `position` within the spawn range. Draws nothing from `rng` when off,
+    /// so a zero jitter leaves the seeded sequence as it was.
+    fn apply(self, position: Vec3, config: &SpawnConfig, is_3d: bool, rng: &mut StdRng) -> Vec3 {
+        if self.0 <= 0.0 {
+            return position;
+        }
+        let x = position.x + rng.random_range(-self.0..=self.0);
+        let z = if is_3d {
+            let z = position.z + rng.random_range(-self.0..=self.0);
+            z.clamp(-config.z_half, config.z_half)
+        } else {
+            position.z
+        };
+        Vec3::new(x.clamp(config.x_min, config.x_max), position.y, z)
+    }
+}
+
+/// Where the timed spawner places balls. Cycled with `G`.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SpawnPattern {
+    /// Random X (and Z in 3D) from the seeded [`SpawnRng`], or a jittered
+    /// [`NozzleCount`] position when nozzles are set.
+    #[default]
+    Rain,
+    /// Regular lattice across the spawn width (and depth in 3D), filled in order,
+    /// so balls land in neat layers that build tall stacks.
+    Grid,
+    /// Every ball dropped at the centre of the spawn range.
+    Column,
+    /// Chains of [`CHAIN_LINKS`] balls joined end to end, dropped from rain positions.
+    /// They fall and pile as flexible ropes, loading each engine's joint solver.
+    Chain,
+    /// Rain positions, but each ball is small ([`BALL_RADIUS`]) or large per
+    /// [`BiDisperse`], so the broad phase and solver see mixed contact radii.
+    BiDisperse,
+}
+
+impl SpawnPattern {
+    pub fn label(self) -> &'static str {
+        match self {
+            SpawnPattern::Rain => "Rain",
+            SpawnPattern::Grid => "Grid",
+            SpawnPattern::Column => "Column",
+            SpawnPattern::Chain => "Chain",
+            SpawnPattern::BiDisperse => "Bi-disperse",
+        }
+    }
+
+    /// Cycle to the next pattern: Rain → Grid → Column → Chain → BiDisperse → Rain.
+    pub fn next(self) -> Self {
+        match self {
+            SpawnPattern::Rain => SpawnPattern::Grid,
+            SpawnPattern::Grid => SpawnPattern::Column,
+            SpawnPattern::Column => SpawnPattern::Chain,
+            SpawnPattern::Chain => SpawnPattern::BiDisperse,
+            SpawnPattern::BiDisperse => SpawnPattern::Rain,
+        }
+    }
+}
+
+/// Default for [`BiDisperse::large_radius`] when `--large-radius` is not given.
+const DEFAULT_LARGE_RADIUS: f32 = 20.0;
+
+/// Default for [`BiDisperse::large_fraction`] when `--large-fraction` is not given.
+const DEFAULT_LARGE_FRACTION: f64 = 0.2;
+
+/// Sizes for the [`SpawnPattern::BiDisperse`] pattern, set with `--large-radius` and
+/// `--large-fraction`. Small balls keep [`BALL_RADIUS`].
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct BiDisperse {
+    pub large_radius: f32,
+    /// Share of balls drawn large, from `0` to `1`.
+    pub large_fraction: f64,
+}
+
+impl Default for BiDisperse {
+    fn default() -> Self {
+        Self {
+            large_radius: DEFAULT_LARGE_RADIUS,
+            large_fraction: DEFAULT_LARGE_FRACTION,
+        }
+    }
+}
+
+impl BiDisperse {
+    fn from_args() -> Self {
+        Self {
+            large_radius: cli::parsed("--large-radius")
+                .unwrap_or(DEFAULT_LARGE_RADIUS)
+                .max(RadiusRange::MIN),
+            large_fraction: cli::parsed("--large-fraction")
+                .unwrap_or(DEFAULT_LARGE_FRACTION)
+                .clamp(0.0, 1.0),
+        }
+    }
+
+    /// Whether the next ball is large, drawn with the seeded spawn RNG.
+    fn sample(self, rng: &mut StdRng) -> bool {
+        rng.random_bool(self.large_fraction)
+    }
+}
+
+/// Marks a ball spawned large by the [`SpawnPattern::BiDisperse`] pattern.
+#[derive(Component)]
+pub struct LargeBall;
+
+/// Small and large balls of the active mode, counted while the
+/// [`SpawnPattern::BiDisperse`] pattern is selected.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SizeMix {
+    pub small: usize,
+    pub large: usize,
+}
+
+/// Balls per [`SpawnPattern::Chain`] chain.
+pub const CHAIN_LINKS: usize = 8;
+
+/// Balls placed by the timed spawner since the last `OnEnter`; drives the
+/// deterministic patterns so every backend fills the lattice in the same order.
+#[derive(Resource, Default)]
+pub(crate) struct SpawnIndex(pub(crate) usize);
+
+/// Set once a run has latched its target-FPS milestone; the timed spawner stops so
+/// the ball count stays at that number. Cleared on every `OnEnter`.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SpawnFrozen(pub bool);
+
+/// Toggled with `F8`: the timed spawner adds no balls while physics keeps running,
+/// so the pile can settle at a chosen ball count. Unlike [`SpawnFrozen`] it is the
+/// user's to clear, and it carries over mode switches.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SpawnPaused(pub bool);
+
+/// Balls `--prefill` lays out in the lower half of the pool on every `OnEnter`, so
+/// a run starts from a dense pile instead of minutes of rain. Zero for none.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Prefill {
+    pub count: usize,
+    /// Set on `OnEnter`; the balls are placed the next frame, once the mode's own
+    /// `OnEnter` systems have reset the ball count.
+    pending: bool,
+}
+
+/// Range of the initial spin about the Z axis, in rad/s (positive is
+/// counter-clockwise), from `--spin-min` / `--spin-max`. Spinning balls exercise
+/// friction and rolling differently from dead drops. Zero by default, so existing
+/// comparisons are unaffected.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Default)]
+pub struct InitialSpin(pub f32, pub f32);
+
+impl InitialSpin {
+    fn from_args() -> Self {
+        let min = cli::parsed("--spin-min").unwrap_or(0.0);
+        let max = cli::parsed("--spin-max").unwrap_or(min).max(min);
+        Self(min, max)
+    }
+
+    /// Middle of the range; used by click spawns, like [`RadiusRange::midpoint`].
+    fn midpoint(self) -> f32 {
+        (self.0 + self.1) / 2.0
+    }
+
+    /// Draws from the seeded RNG only for a real range, so the default leaves the
+    /// spawn sequence exactly as it was.
+    fn sample(self, rng: &mut StdRng) -> f32 {
+        if self.0 < self.1 {
+            rng.random_range(self.0..=self.1)
+        } else {
+            self.0
+        }
+    }
+}
+
+/// Horizontal spread of [`InitialSpeed`] launches, as a fraction of the speed.
+const LAUNCH_SPREAD: f32 = 0.1;
+
+/// Downward speed new balls are launched with, in px/s, from `--initial-speed`.
+/// A dead drop from the top of the pool lands slowly; fast impacts are where tunnelling and
+/// the CCD toggle start to matter. Zero by default, so existing comparisons are
+/// unaffected.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Default)]
+pub struct InitialSpeed(pub f32);
+
+impl InitialSpeed {
+    /// Straight down; used by click spawns, like [`RadiusRange::midpoint`].
+    fn velocity(self) -> Vec3 {
+        Vec3::new(0.0, -self.0, 0.0)
+    }
+
+    /// Adds a horizontal component of up to [`LAUNCH_SPREAD`] × speed, drawn from the
+    /// seeded RNG only when there is a speed, so the default leaves the spawn
+    /// sequence exactly as it was.
+    fn sample(self, rng: &mut StdRng) -> Vec3 {
+        if self.0 <= 0.0 {
+            return self.velocity();
+        }
+        let spread = self.0 * LAUNCH_SPREAD;
+        Vec3::new(
+            rng.random_range(-spread..=spread),
+            -self.0,
+            rng.random_range(-spread..=spread),
+        )
+    }
+}
+
+/// Highest accepted [`Temperature`], in px/s: fast enough to keep any pile the pool
+/// holds fluidized without launching balls through the walls in one step.
+const MAX_TEMPERATURE: f32 = 2000.0;
+
+/// Speed of a random-direction velocity added to every new ball, in px/s, from
+/// `--temperature`. The "thermal" energy keeps the pile fluidized instead of letting
+/// it settle and sleep, so the contact count stays at its worst case for sustained
+/// benchmarks. Zero by default, which draws nothing; clamped to [`MAX_TEMPERATURE`].
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Default)]
+pub struct Temperature(pub f32);
+
+impl Temperature {
+    fn from_args() -> Self {
+        match cli::parsed::<f32>("--temperature") {
+            Some(value) if value.is_finite() => Self(value.clamp(0.0, MAX_TEMPERATURE)),
+            Some(value) => {
+                warn!("Ignoring --temperature {value}: must be a finite speed");
+                Self::default()
+            }
+            None => Self::default(),
+        }
+    }
+
+    /// A velocity of this speed in a direction drawn with the seeded RNG: uniformly
+    /// over the sphere in 3D, and over the circle in 2D, so 2D balls get the full
+    /// speed rather than the in-plane part of a 3D one.
+    fn sample(self, is_3d: bool, rng: &mut StdRng) -> Vec3 {
+        if self.0 <= 0.0 {
+            return Vec3::ZERO;
+        }
+        let angle = rng.random_range(0.0..std::f32::consts::TAU);
+        if !is_3d {
+            return Vec2::from_angle(angle).extend(0.0) * self.0;
+        }
+        let z: f32 = rng.random_range(-1.0..=1.0);
+        let planar = Vec2::from_angle(angle) * (1.0 - z * z).sqrt();
+        planar.extend(z) * self.0
+    }
+}
+
+/// Whether newly spawned balls use continuous collision detection. Toggled with `X`.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct BallCcd(pub bool);
+
+/// Whether balls are drawn. Toggled with `M`; off, balls keep only their physics
+/// components, so the FPS difference isolates draw cost from physics cost.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RenderBalls(pub bool);
+
+impl Default for RenderBalls {
+    fn default() -> Self {
+        Self(true)
+    }
+}
+
+/// Read access to every runtime setting that shapes a newly spawned ball.
+#[derive(SystemParam)]
+pub struct BallConfig<'w> {
+    restitution: Res<'w, BallRestitution>,
+    friction: Res<'w, BallFriction>,
+    linear_damping: Res<'w, BallLinearDamping>,
+    angular_damping: Res<'w, BallAngularDamping>,
+    shape: Res<'w, SpawnShape>,
+    ccd: Res<'w, BallCcd>,
+    render: Res<'w, RenderBalls>,
+    spin: Res<'w, InitialSpin>,
+    speed: Res<'w, InitialSpeed>,
+    temperature: Res<'w, Temperature>,
+    density: Res<'w, BallDensity>,
+    mode: Res<'w, State<PhysicsMode>>,
+    collision_events: Res<'w, CollisionEvents>,
+}
+
+impl BallConfig<'_> {
+    /// Contact material shared by balls and walls.
+    pub fn surface(&self) -> Surface {
+        Surface {
+            restitution: self.restitution.0,
+            friction: self.friction.0,
+        }
+    }
+
+    /// Parameters for a ball of `radius`, spinning at the middle of [`InitialSpin`]
+    /// and launched straight down at [`InitialSpeed`].
+    pub fn params(&self, radius: f32) -> BallParams {
+        BallParams {
+            radius,
+            shape: *self.shape,
+            surface: self.surface(),
+            damping: Damping {
+                linear: self.linear_damping.0,
+                angular: self.angular_damping.0,
+            },
+            ccd: self.ccd.0,
+            render: self.render.0,
+            spin: self.spin.midpoint(),
+            velocity: self.speed.velocity(),
+            density: self.density.0,
+            collision_events: self.collision_events.0,
+        }
+    }
+
+    /// Parameters for a timed, burst or bulk spawn: radius, spin and launch velocity,
+    /// [`Temperature`] included, are drawn with the seeded spawn RNG.
+    fn sample(&self, radius_range: RadiusRange, rng: &mut StdRng) -> BallParams {
+        let radius = radius_range.sample(rng);
+        let is_3d = matches!(
+            self.mode.get(),
+            PhysicsMode::Avian3d | PhysicsMode::Rapier3d
+        );
+        BallParams {
+            spin: self.spin.sample(rng),
+            velocity: self.speed.sample(rng) + self.temperature.sample(is_3d, rng),
+            ..self.params(radius)
+        }
     }
 }
 
@@ -44,11 +654,186 @@ impl Default for BallsPerTick {
 struct SpawnTimer(Timer);
 
 pub fn plugin(app: &mut App) {
-    app.insert_resource(SpawnTimer(Timer::new(SPAWN_INTERVAL, TimerMode::Repeating)));
+    let seed = spawn_seed();
+    info!("Spawn RNG seed: {seed}");
+
+    let pool = PoolConfig::from_args(*app.world().resource::<LengthUnit>());
+    let (interval, _) = SpawnRate::default().schedule();
+    app.insert_resource(SpawnTimer(Timer::new(interval, TimerMode::Repeating)));
+    let radius_range = RadiusRange::from_args();
+    app.insert_resource(SpawnConfig::for_pool(pool, radius_range.1));
+    app.insert_resource(pool);
     app.insert_resource(BallCount::default());
-    app.insert_resource(BallsPerTick::default());
+    app.init_resource::<SpawnRate>();
+    app.init_resource::<BallRestitution>();
+    app.init_resource::<BallFriction>();
+    app.init_resource::<BallLinearDamping>();
+    app.init_resource::<BallAngularDamping>();
+    app.init_resource::<SpawnShape>();
+    app.insert_resource(NgonSides::from_args());
+    app.init_resource::<BallCcd>();
+    app.init_resource::<RenderBalls>();
+    app.insert_resource(cli::parsed("--density").map_or_else(BallDensity::default, BallDensity));
+    app.init_resource::<SpawnPattern>();
+    app.init_resource::<NozzleCount>();
+    app.init_resource::<SpawnIndex>();
+    app.init_resource::<SpawnFrozen>();
+    app.init_resource::<SpawnPaused>();
+    app.insert_resource(radius_range);
+    app.insert_resource(BiDisperse::from_args());
+    app.init_resource::<SizeMix>();
+    app.insert_resource(InitialSpin::from_args());
+    app.insert_resource(
+        cli::parsed("--initial-speed").map_or_else(InitialSpeed::default, InitialSpeed),
+    );
+    app.insert_resource(Temperature::from_args());
+    // Registered here rather than in `main` so the headless app, which never seals, has it too.
+    app.init_resource::<TopWall>();
+    app.insert_resource(FloorThickness::from_args(pool));
+    app.insert_resource(SpawnRng::new(seed));
+    app.insert_resource(MaxBalls(
+        cli::parsed("--max-balls").unwrap_or(DEFAULT_MAX_BALLS),
+    ));
+    app.insert_resource(cli::parsed("--burst-size").map_or_else(BurstSize::default, BurstSize));
+    app.insert_resource(
+        cli::parsed("--spawn-jitter").map_or_else(SpawnJitter::default, SpawnJitter),
+    );
+    app.insert_resource(
+        cli::parsed("--target-in-flight").map_or_else(TargetInFlight::default, TargetInFlight),
+    );
+    app.init_resource::<LastBurst>();
+    app.insert_resource(Prefill {
+        count: cli::parsed("--prefill").unwrap_or(0),
+        pending: false,
+    });
     app.add_systems(Startup, setup_ball_assets);
-    app.add_systems(Update, spawn_balls);
+    let timed_spawning = (
+        apply_spawn_rate
+            .run_if(resource_changed::<SpawnRate>)
+            .before(spawn_balls),
+        spawn_balls,
+    );
+    // Under `--fixed-dt` the spawn timer ticks on `Time<Fixed>`, in lockstep with physics.
+    if backend::fixed_dt() {
+        app.add_systems(FixedUpdate, timed_spawning);
+    } else {
+        app.add_systems(Update, timed_spawning);
+    }
+    app.add_systems(
+        Update,
+        (
+            handle_click_spawn,
+            handle_bulk_spawn,
+            handle_burst_spawn,
+            handle_spawn_pause,
+            spawn_prefill.run_if(|prefill: Res<Prefill>| prefill.pending),
+            apply_render_balls.run_if(resource_changed::<RenderBalls>),
+            apply_ball_density.run_if(resource_changed::<BallDensity>),
+            count_ball_sizes.run_if(resource_equals(SpawnPattern::BiDisperse)),
+        ),
+    );
+    for mode in PhysicsMode::ALL {
+        app.add_systems(
+            OnEnter(mode),
+            (
+                reset_spawn_sequence,
+                unfreeze_spawning,
+                tint_ball_materials,
+                queue_prefill,
+            ),
+        );
+    }
+}
+
+/// Ball counts that `Shift` + [`KeyBindings::bulk_fill`] fills the pool up to.
+const BULK_PRESETS: [usize; 5] = [1_000, 2_000, 5_000, 10_000, 20_000];
+
+/// Clearance between neighbouring bulk-spawned balls.
+const BULK_GAP: f32 = 1.0;
+
+/// Seed from `--seed`, then `AVR_SEED`, then [`DEFAULT_SEED`].
+fn spawn_seed() -> u64 {
+    cli::parsed("--seed")
+        .or_else(|| std::env::var(SEED_ENV).ok()?.parse().ok())
+        .unwrap_or(DEFAULT_SEED)
+}
+
+/// Strips the mesh and material from every ball when rendering is switched off, and
+/// gives them back when it is switched on again. Physics is untouched either way.
+fn apply_render_balls(
+    mut commands: Commands,
+    render: Res<RenderBalls>,
+    ball_assets: Res<BallAssets>,
+    state: Res<State<PhysicsMode>>,
+    drawn: Query<Entity, (With<Ball>, Or<(With<Mesh2d>, With<Mesh3d>)>)>,
+    hidden: Query<(Entity, &SpawnShape), (With<Ball>, Without<Mesh2d>, Without<Mesh3d>)>,
+) {
+    if render.0 {
+        for (entity, shape) in &hidden {
+            backend::insert_ball_visuals(
+                &mut commands.entity(entity),
+                *state.get(),
+                *shape,
+                &ball_assets,
+            );
+        }
+    } else {
+        for entity in &drawn {
+            commands.entity(entity).remove::<(
+                Mesh2d,
+                MeshMaterial2d<ColorMaterial>,
+                Mesh3d,
+                MeshMaterial3d<StandardMaterial>,
+            )>();
+        }
+    }
+}
+
+/// Pushes a new [`BallDensity`] into every existing ball, in the engine that owns it.
+pub(crate) fn apply_ball_density(
+    mut commands: Commands,
+    density: Res<BallDensity>,
+    balls: Query<(Entity, &BodyOwner), With<Ball>>,
+) {
+    for (entity, owner) in &balls {
+        backend::insert_ball_density(&mut commands.entity(entity), owner.0, density.0);
+    }
+}
+
+/// Recolours the shared ball materials with the entered mode's colour, so
+/// screenshots of different backends can be told apart at a glance.
+/// The initial `OnEnter` runs before `Startup` creates the assets; they start out
+/// in the starting mode's colour instead.
+fn tint_ball_materials(
+    state: Res<State<PhysicsMode>>,
+    ball_assets: Option<Res<BallAssets>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut color_materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let Some(ball_assets) = ball_assets else {
+        return;
+    };
+    let color = state.get().color();
+    if let Some(material) = color_materials.get_mut(&ball_assets.mat2d) {
+        material.color = color;
+    }
+    if let Some(material) = materials.get_mut(&ball_assets.mat3d) {
+        material.base_color = color;
+    }
+}
+
+/// Restarts both the random and the index-based drop sequences.
+pub(crate) fn reset_spawn_sequence(mut rng: ResMut<SpawnRng>, mut index: ResMut<SpawnIndex>) {
+    rng.reset();
+    index.0 = 0;
+}
+
+pub(crate) fn unfreeze_spawning(mut frozen: ResMut<SpawnFrozen>) {
+    frozen.0 = false;
+}
+
+pub(crate) fn queue_prefill(mut prefill: ResMut<Prefill>) {
+    prefill.pending = prefill.count > 0;
 }
 
 fn setup_ball_assets(
@@ -56,47 +841,927 @@ fn setup_ball_assets(
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     mut color_materials: ResMut<Assets<ColorMaterial>>,
+    state: Res<State<PhysicsMode>>,
 ) {
+    let color = state.get().color();
+    // Box side, capsule segment length and prism depth are all 2 * radius, matching
+    // the colliders.
+    let side = 2.0 * BALL_RADIUS;
     commands.insert_resource(BallAssets {
-        mesh2d: meshes.add(Circle::new(BALL_RADIUS)),
-        mat2d: color_materials.add(ColorMaterial::from_color(BALL_COLOR)),
-        mesh3d: meshes.add(Sphere::new(BALL_RADIUS)),
+        radius: BALL_RADIUS,
+        meshes2d: ShapeMeshes {
+            circle: meshes.add(Circle::new(BALL_RADIUS)),
+            cuboid: meshes.add(Rectangle::new(side, side)),
+            capsule: meshes.add(Capsule2d::new(BALL_RADIUS, side)),
+            ngons: (NgonSides::MIN..=NgonSides::MAX)
+                .map(|sides| meshes.add(RegularPolygon::new(BALL_RADIUS, u32::from(sides))))
+                .collect(),
+        },
+        mat2d: color_materials.add(ColorMaterial::from_color(color)),
+        meshes3d: ShapeMeshes {
+            circle: meshes.add(Sphere::new(BALL_RADIUS)),
+            cuboid: meshes.add(Cuboid::new(side, side, side)),
+            capsule: meshes.add(Capsule3d::new(BALL_RADIUS, side)),
+            ngons: (NgonSides::MIN..=NgonSides::MAX)
+                .map(|sides| {
+                    let polygon = RegularPolygon::new(BALL_RADIUS, u32::from(sides));
+                    meshes.add(Extrusion::new(polygon, side))
+                })
+                .collect(),
+        },
         mat3d: materials.add(StandardMaterial {
-            base_color: BALL_COLOR,
+            base_color: color,
             ..default()
         }),
     });
 }
 
+fn apply_spawn_rate(rate: Res<SpawnRate>, mut timer: ResMut<SpawnTimer>) {
+    timer.0.set_duration(rate.schedule().0);
+}
+
 fn spawn_balls(
     mut commands: Commands,
     ball_assets: Res<BallAssets>,
     time: Res<Time>,
+    spawn_config: Res<SpawnConfig>,
     mut timer: ResMut<SpawnTimer>,
     mut ball_count: ResMut<BallCount>,
-    balls_per_tick: Res<BallsPerTick>,
+    max_balls: Res<MaxBalls>,
+    rate: Res<SpawnRate>,
+    config: BallConfig,
+    (pattern, jitter): (Res<SpawnPattern>, Res<SpawnJitter>),
+    nozzles: Res<NozzleCount>,
+    mut index: ResMut<SpawnIndex>,
+    mut rng: ResMut<SpawnRng>,
     mode: Res<State<PhysicsMode>>,
+    (top_wall, frozen, paused, view, target, in_flight): (
+        Res<TopWall>,
+        Res<SpawnFrozen>,
+        Res<SpawnPaused>,
+        Option<Res<PartnerView>>,
+        Res<TargetInFlight>,
+        Res<InFlight>,
+    ),
+    (radius_range, bi_disperse): (Res<RadiusRange>, Res<BiDisperse>),
 ) {
+    // A sealed pool has no opening to drop balls through.
+    if top_wall.0 || frozen.0 || paused.0 {
+        return;
+    }
     timer.0.tick(time.delta());
 
     let mode = *mode.get();
+    let is_3d = matches!(mode, PhysicsMode::Avian3d | PhysicsMode::Rapier3d);
     let ticks = timer.0.times_finished_this_tick();
+    let (_, per_tick) = rate.schedule();
+    let partners = view.map(|view| view.partners(mode)).unwrap_or_default();
+    let mut budget = target.budget(*in_flight);
 
     for _ in 0..ticks {
-        let mut rng = rand::rng();
-        for _ in 0..balls_per_tick.0 {
-            let x = rng.random_range(SPAWN_X_MIN..=SPAWN_X_MAX);
-            let z = match mode {
-                PhysicsMode::Avian3d | PhysicsMode::Rapier3d => {
-                    let half = POOL_DEPTH / 2.0 - 40.0;
-                    rng.random_range(-half..=half)
+        for slot in 0..per_tick {
+            if max_balls.reached(ball_count.0) || budget == 0 {
+                return;
+            }
+            budget -= 1;
+            if *pattern == SpawnPattern::Chain {
+                // Links accrue one per slot and drop as a whole chain once there are
+                // enough, so the spawn rate stays in balls per second.
+                index.0 += 1;
+                if index.0 % CHAIN_LINKS != 0 {
+                    continue;
+                }
+                if max_balls.reached(ball_count.0 + CHAIN_LINKS - 1) {
+                    return;
                 }
-                _ => 0.0,
+                let top = spawn_position(
+                    *pattern,
+                    *nozzles,
+                    index.0 / CHAIN_LINKS,
+                    0,
+                    &spawn_config,
+                    is_3d,
+                    &mut rng.rng,
+                );
+                let top = jitter.apply(top, &spawn_config, is_3d, &mut rng.rng);
+                spawn_chain(
+                    &mut commands,
+                    mode,
+                    top,
+                    4.0 * radius_range.1,
+                    || config.sample(*radius_range, &mut rng.rng),
+                    &ball_assets,
+                    &partners,
+                );
+                ball_count.0 += CHAIN_LINKS;
+                continue;
+            }
+            let position = spawn_position(
+                *pattern,
+                *nozzles,
+                index.0,
+                slot,
+                &spawn_config,
+                is_3d,
+                &mut rng.rng,
+            );
+            let position = jitter.apply(position, &spawn_config, is_3d, &mut rng.rng);
+            let large = *pattern == SpawnPattern::BiDisperse && bi_disperse.sample(&mut rng.rng);
+            // The range only leaves room for `--radius-range` balls.
+            let position = if large {
+                let inset = (bi_disperse.large_radius - radius_range.1).max(0.0);
+                spawn_config.inset(position, inset)
+            } else {
+                position
+            };
+            let params = if *pattern == SpawnPattern::BiDisperse {
+                let radius = if large {
+                    bi_disperse.large_radius
+                } else {
+                    BALL_RADIUS
+                };
+                config.sample(RadiusRange(radius, radius), &mut rng.rng)
+            } else {
+                config.sample(*radius_range, &mut rng.rng)
             };
-            let position = Vec3::new(x, SPAWN_Y, z);
 
-            backend::spawn_ball(&mut commands, mode, position, BALL_RADIUS, &ball_assets);
+            let ball = backend::spawn_ball(&mut commands, mode, position, params, &ball_assets);
+            if large {
+                commands.entity(ball).insert(LargeBall);
+            }
+            for &partner in &partners {
+                split::spawn_partner_ball(&mut commands, partner, position, params, &ball_assets);
+            }
             ball_count.0 += 1;
+            index.0 += 1;
+        }
+    }
+}
+
+/// Drops one chain hanging down from `top`, links `spacing` apart and each joined to
+/// the one above it (see [`backend::spawn_chain_joint`]); mirrored in the world of
+/// every mode in `partners` (see [`PartnerView::partners`]).
+fn spawn_chain(
+    commands: &mut Commands,
+    mode: PhysicsMode,
+    top: Vec3,
+    spacing: f32,
+    mut params: impl FnMut() -> BallParams,
+    assets: &BallAssets,
+    partners: &[PhysicsMode],
+) {
+    let mut above: Option<(Entity, Vec<Entity>)> = None;
+    for link in 0..CHAIN_LINKS {
+        let position = top - Vec3::Y * link as f32 * spacing;
+        let params = params();
+        let ball = backend::spawn_ball(commands, mode, position, params, assets);
+        let twins: Vec<Entity> = partners
+            .iter()
+            .map(|&partner| split::spawn_partner_ball(commands, partner, position, params, assets))
+            .collect();
+        if let Some((ball_above, twins_above)) = above {
+            backend::spawn_chain_joint(commands, mode, [ball_above, ball], spacing);
+            for ((&partner, twin_above), &twin) in partners.iter().zip(twins_above).zip(&twins) {
+                backend::spawn_chain_joint(commands, partner, [twin_above, twin], spacing);
+            }
+        }
+        above = Some((ball, twins));
+    }
+}
+
+/// Counts the active mode's small and large balls; partner balls are left out.
+fn count_ball_sizes(
+    state: Res<State<PhysicsMode>>,
+    mut mix: ResMut<SizeMix>,
+    balls: Query<(&BodyOwner, Has<LargeBall>), (With<Ball>, Without<SplitPartner>)>,
+) {
+    let mut counted = SizeMix::default();
+    for (owner, large) in &balls {
+        if owner.0 != *state.get() {
+            continue;
+        }
+        if large {
+            counted.large += 1;
+        } else {
+            counted.small += 1;
+        }
+    }
+    mix.set_if_neq(counted);
+}
+
+/// Moves a ball that fell out of a [`NoWalls`](crate::walls::NoWalls) pool back up to
+/// the drop line at rest, so the ball count stays constant.
+pub fn recycle_ball(
+    commands: &mut Commands,
+    ball: Entity,
+    mode: PhysicsMode,
+    transform: &Transform,
+    config: &SpawnConfig,
+) {
+    let mut ball = commands.entity(ball);
+    ball.insert(Transform {
+        translation: transform.translation.with_y(config.y),
+        ..*transform
+    });
+    backend::insert_ball_velocity(&mut ball, mode, Vec3::ZERO);
+}
+
+/// Position of the `index`-th timed ball for `pattern`. `slot` is the ball's place
+/// within the current tick: balls that would share a spawn point in the same tick
+/// are stacked `GRID_SPACING` apart instead of overlapping.
+fn spawn_position(
+    pattern: SpawnPattern,
+    nozzles: NozzleCount,
+    index: usize,
+    slot: usize,
+    config: &SpawnConfig,
+    is_3d: bool,
+    rng: &mut StdRng,
+) -> Vec3 {
+    match pattern {
+        SpawnPattern::Rain | SpawnPattern::Chain | SpawnPattern::BiDisperse => {
+            let x = if nozzles.0 == 0 {
+                rng.random_range(config.x_min..=config.x_max)
+            } else {
+                let jitter = rng.random_range(-NOZZLE_JITTER..=NOZZLE_JITTER);
+                (nozzles.x(index % nozzles.0, config) + jitter).clamp(config.x_min, config.x_max)
+            };
+            let z = if is_3d {
+                rng.random_range(-config.z_half..=config.z_half)
+            } else {
+                0.0
+            };
+            Vec3::new(x, config.y, z)
+        }
+        SpawnPattern::Grid => {
+            let columns = lattice_points(config.x_max - config.x_min);
+            let rows = if is_3d {
+                lattice_points(2.0 * config.z_half)
+            } else {
+                1
+            };
+            let cells = columns * rows;
+            let cell = index % cells;
+            let x = config.x_min + (cell % columns) as f32 * GRID_SPACING;
+            let z = if is_3d {
+                -config.z_half + (cell / columns) as f32 * GRID_SPACING
+            } else {
+                0.0
+            };
+            Vec3::new(x, config.y + (slot / cells) as f32 * GRID_SPACING, z)
+        }
+        SpawnPattern::Column => {
+            let x = (config.x_min + config.x_max) / 2.0;
+            Vec3::new(x, config.y + slot as f32 * GRID_SPACING, 0.0)
+        }
+    }
+}
+
+/// Number of lattice points that fit in `extent`, including both ends.
+fn lattice_points(extent: f32) -> usize {
+    (extent / GRID_SPACING).floor() as usize + 1
+}
+
+/// Height of the band below the drop line that burst balls are scattered over.
+const BURST_DEPTH: f32 = 200.0;
+
+fn handle_spawn_pause(
+    input: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut paused: ResMut<SpawnPaused>,
+) {
+    if input.just_pressed(bindings.spawn_pause) {
+        paused.0 = !paused.0;
+        info!("Spawning {}", if paused.0 { "paused" } else { "resumed" });
+    }
+}
+
+/// `F2` drops [`BurstSize`] balls in a single frame at random positions across the
+/// top of the pool. Unlike the per-tick spawner this lands a large batch of new
+/// contacts at once, exposing each engine's transient frame-time spike.
+fn handle_burst_spawn(
+    mut commands: Commands,
+    input: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    time: Res<Time<Real>>,
+    ball_assets: Res<BallAssets>,
+    (spawn_config, pool): (Res<SpawnConfig>, Res<PoolConfig>),
+    burst_size: Res<BurstSize>,
+    mut ball_count: ResMut<BallCount>,
+    max_balls: Res<MaxBalls>,
+    config: BallConfig,
+    radius_range: Res<RadiusRange>,
+    mut rng: ResMut<SpawnRng>,
+    mode: Res<State<PhysicsMode>>,
+    mut last_burst: ResMut<LastBurst>,
+) {
+    if !input.just_pressed(bindings.burst) {
+        return;
+    }
+    let count = if max_balls.0 == 0 {
+        burst_size.0
+    } else {
+        burst_size.0.min(max_balls.0.saturating_sub(ball_count.0))
+    };
+    if count == 0 {
+        return;
+    }
+
+    let mode = *mode.get();
+    let is_3d = matches!(mode, PhysicsMode::Avian3d | PhysicsMode::Rapier3d);
+    for _ in 0..count {
+        let position = burst_position(&spawn_config, BURST_DEPTH * pool.scale, is_3d, &mut rng.rng);
+        let params = config.sample(*radius_range, &mut rng.rng);
+        backend::spawn_ball(&mut commands, mode, position, params, &ball_assets);
+    }
+    ball_count.0 += count;
+    last_burst.0 = Some(time.elapsed());
+}
+
+/// Random point in the `depth` band below the drop line, across the spawn range
+/// (and the pool depth in 3D).
+fn burst_position(config: &SpawnConfig, depth: f32, is_3d: bool, rng: &mut StdRng) -> Vec3 {
+    let x = rng.random_range(config.x_min..=config.x_max);
+    let y = config.y - rng.random_range(0.0..=depth);
+    let z = if is_3d {
+        rng.random_range(-config.z_half..=config.z_half)
+    } else {
+        0.0
+    };
+    Vec3::new(x, y, z)
+}
+
+/// Lattice points for a bulk spawn, layer by layer from `bottom` up to `top`:
+/// rows across the spawn range, and in 3D also across the pool depth.
+fn bulk_positions(
+    bottom: f32,
+    top: f32,
+    spacing: f32,
+    config: SpawnConfig,
+    is_3d: bool,
+) -> impl Iterator<Item = Vec3> {
+    let columns = ((config.x_max - config.x_min) / spacing).floor() as usize + 1;
+    let (z_start, rows) = if is_3d {
+        (
+            -config.z_half,
+            (2.0 * config.z_half / spacing).floor() as usize + 1,
+        )
+    } else {
+        (0.0, 1)
+    };
+    let layers = if top >= bottom {
+        ((top - bottom) / spacing).floor() as usize + 1
+    } else {
+        0
+    };
+    let x_min = config.x_min;
+    (0..layers).flat_map(move |layer| {
+        (0..rows).flat_map(move |row| {
+            (0..columns).map(move |column| {
+                Vec3::new(
+                    x_min + column as f32 * spacing,
+                    bottom + layer as f32 * spacing,
+                    z_start + row as f32 * spacing,
+                )
+            })
+        })
+    })
+}
+
+/// `Shift` + `1`–`5` fills the pool up to a preset ball count in one frame.
+/// The new balls are laid out in a non-overlapping lattice starting above the
+/// highest existing ball and ending at the drop line; whatever does not fit
+/// there, or would exceed [`MaxBalls`], is skipped with a log message.
+fn handle_bulk_spawn(
+    mut commands: Commands,
+    input: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    ball_assets: Res<BallAssets>,
+    spawn_config: Res<SpawnConfig>,
+    pool: Res<PoolConfig>,
+    mut ball_count: ResMut<BallCount>,
+    max_balls: Res<MaxBalls>,
+    config: BallConfig,
+    radius_range: Res<RadiusRange>,
+    mut rng: ResMut<SpawnRng>,
+    mode: Res<State<PhysicsMode>>,
+    floor: Res<FloorThickness>,
+    balls: Query<&Transform, With<Ball>>,
+) {
+    if !input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]) {
+        return;
+    }
+    let Some((_, &target)) = bindings
+        .bulk_fill
+        .iter()
+        .zip(&BULK_PRESETS)
+        .find(|(key, _)| input.just_pressed(**key))
+    else {
+        return;
+    };
+    let target = if max_balls.0 == 0 {
+        target
+    } else {
+        target.min(max_balls.0)
+    };
+    let wanted = target.saturating_sub(ball_count.0);
+    if wanted == 0 {
+        return;
+    }
+
+    let mode = *mode.get();
+    let is_3d = matches!(mode, PhysicsMode::Avian3d | PhysicsMode::Rapier3d);
+    let max_radius = radius_range.1;
+    let spacing = 2.0 * max_radius + BULK_GAP;
+    let highest = balls
+        .iter()
+        .map(|t| t.translation.y + max_radius)
+        .fold(floor.top(*pool), f32::max);
+    let bottom = highest + max_radius + BULK_GAP;
+
+    let mut spawned = 0;
+    for position in
+        bulk_positions(bottom, spawn_config.y, spacing, *spawn_config, is_3d).take(wanted)
+    {
+        let params = config.sample(*radius_range, &mut rng.rng);
+        backend::spawn_ball(&mut commands, mode, position, params, &ball_assets);
+        spawned += 1;
+    }
+    ball_count.0 += spawned;
+    if spawned < wanted {
+        info!("Bulk spawn: only {spawned} of {wanted} balls fit below the drop line");
+    }
+}
+
+/// Lattice points for balls up to `max_radius` over the lower half of the pool,
+/// kept [`BULK_GAP`] clear of the walls and every obstacle.
+fn prefill_positions(
+    pool: PoolConfig,
+    max_radius: f32,
+    floor: FloorThickness,
+    is_3d: bool,
+    obstacles: &ObstacleLayout,
+) -> impl Iterator<Item = Vec3> + '_ {
+    let clearance = max_radius + BULK_GAP;
+    let half_width = pool.width / 2.0 - pool.thickness - clearance;
+    let range = SpawnConfig {
+        x_min: -half_width,
+        x_max: half_width,
+        ..SpawnConfig::for_pool(pool, max_radius)
+    };
+    bulk_positions(
+        floor.top(pool) + clearance,
+        -clearance,
+        2.0 * max_radius + BULK_GAP,
+        range,
+        is_3d,
+    )
+    .filter(move |position| !obstacles.blocks(position.truncate(), clearance))
+}
+
+/// Places the [`Prefill`] balls in a non-overlapping lattice over the lower half of
+/// the pool: across its full width, and in 3D its depth, clear of the walls and any
+/// obstacle. They start at rest and settle from there. Whatever does not fit, or
+/// would exceed [`MaxBalls`], is skipped with a log message.
+fn spawn_prefill(
+    mut commands: Commands,
+    mut prefill: ResMut<Prefill>,
+    ball_assets: Res<BallAssets>,
+    mut ball_count: ResMut<BallCount>,
+    max_balls: Res<MaxBalls>,
+    config: BallConfig,
+    radius_range: Res<RadiusRange>,
+    mut rng: ResMut<SpawnRng>,
+    mode: Res<State<PhysicsMode>>,
+    pool: Res<PoolConfig>,
+    (obstacles, floor): (Res<ObstacleLayout>, Res<FloorThickness>),
+) {
+    prefill.pending = false;
+    let wanted = if max_balls.0 == 0 {
+        prefill.count
+    } else {
+        prefill.count.min(max_balls.0)
+    };
+
+    let mode = *mode.get();
+    let is_3d = matches!(mode, PhysicsMode::Avian3d | PhysicsMode::Rapier3d);
+    let mut spawned = 0;
+    for position in prefill_positions(*pool, radius_range.1, *floor, is_3d, &obstacles).take(wanted)
+    {
+        let mut params = config.sample(*radius_range, &mut rng.rng);
+        params.velocity = Vec3::ZERO;
+        params.spin = 0.0;
+        backend::spawn_ball(&mut commands, mode, position, params, &ball_assets);
+        spawned += 1;
+    }
+    ball_count.0 += spawned;
+    info!("Prefilled {spawned} balls");
+    if spawned < wanted {
+        info!("Prefill: only {spawned} of {wanted} balls fit in the lower half of the pool");
+    }
+}
+
+/// Left click on empty space drops a single ball at the cursor, clamped to the inside of the pool
+/// (and below the top wall while sealed, so click spawning still works then).
+pub(crate) fn handle_click_spawn(
+    mut commands: Commands,
+    mouse: Res<ButtonInput<MouseButton>>,
+    drag: Option<Res<DragState>>,
+    windows: Query<&Window, With<PrimaryWindow>>,
+    cameras: Query<(&Camera, &GlobalTransform), (Without<SplitPartner>, Without<PerfGraphCamera>)>,
+    ball_assets: Res<BallAssets>,
+    spawn_config: Res<SpawnConfig>,
+    pool: Res<PoolConfig>,
+    mut ball_count: ResMut<BallCount>,
+    max_balls: Res<MaxBalls>,
+    config: BallConfig,
+    mode: Res<State<PhysicsMode>>,
+    top_wall: Res<TopWall>,
+    radius_range: Res<RadiusRange>,
+) {
+    if !mouse.just_pressed(MouseButton::Left) || max_balls.reached(ball_count.0) {
+        return;
+    }
+    // The press picked up a ball instead.
+    if drag.is_some_and(|drag| drag.is_dragging()) {
+        return;
+    }
+    let mode = *mode.get();
+    let Some(point) = cursor_world_position(mode, &windows, &cameras) else {
+        return;
+    };
+    let radius = radius_range.midpoint();
+    let floor_y = -pool.height / 2.0 + radius;
+    let top_y = if top_wall.0 {
+        spawn_config.y.min(pool.lid_bottom() - radius)
+    } else {
+        spawn_config.y
+    };
+    let position = Vec3::new(
+        point.x.clamp(spawn_config.x_min, spawn_config.x_max),
+        point.y.clamp(floor_y, top_y),
+        point.z,
+    );
+
+    backend::spawn_ball(
+        &mut commands,
+        mode,
+        position,
+        config.params(radius),
+        &ball_assets,
+    );
+    ball_count.0 += 1;
+}
+
+/// World point under the cursor in the primary window, if any. See [`cursor_to_pool`].
+pub fn cursor_world_position(
+    mode: PhysicsMode,
+    windows: &Query<&Window, With<PrimaryWindow>>,
+    cameras: &Query<(&Camera, &GlobalTransform), (Without<SplitPartner>, Without<PerfGraphCamera>)>,
+) -> Option<Vec3> {
+    let cursor = windows.single().ok()?.cursor_position()?;
+    let (camera, camera_transform) = cameras.single().ok()?;
+    cursor_to_pool(mode, camera, camera_transform, cursor)
+}
+
+/// World point under the cursor. The 2D camera is orthographic, so the cursor maps
+/// straight onto the z = 0 plane. The 3D camera looks down into the pool at an angle,
+/// so its cursor ray is intersected with the pool's mid-depth plane (z = 0).
+fn cursor_to_pool(
+    mode: PhysicsMode,
+    camera: &Camera,
+    camera_transform: &GlobalTransform,
+    cursor: Vec2,
+) -> Option<Vec3> {
+    match mode {
+        PhysicsMode::Avian2d | PhysicsMode::Rapier2d => camera
+            .viewport_to_world_2d(camera_transform, cursor)
+            .ok()
+            .map(|point| point.extend(0.0)),
+        PhysicsMode::Avian3d | PhysicsMode::Rapier3d => {
+            let ray = camera.viewport_to_world(camera_transform, cursor).ok()?;
+            let distance = ray.intersect_plane(Vec3::ZERO, InfinitePlane3d::new(Vec3::Z))?;
+            Some(ray.get_point(distance))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rng() -> StdRng {
+        StdRng::seed_from_u64(DEFAULT_SEED)
+    }
+
+    #[test]
+    fn spawn_rate_batches_only_above_the_minimum_interval() {
+        let ms = |rate: f32| {
+            let (interval, per_tick) = SpawnRate(rate).schedule();
+            ((interval.as_secs_f64() * 1000.0).round() as u64, per_tick)
+        };
+        assert_eq!(ms(DEFAULT_SPAWN_RATE), (50, 1));
+        assert_eq!(ms(2.0), (500, 1));
+        assert_eq!(ms(1000.0), (20, 20));
+        assert_eq!(ms(1250.0), (20, 25));
+        // Out-of-range rates are clamped rather than producing a zero interval.
+        assert_eq!(
+            SpawnRate(0.0).schedule(),
+            SpawnRate(SpawnRate::MIN).schedule()
+        );
+    }
+
+    #[test]
+    fn initial_speed_launches_down_within_the_spread() {
+        let mut untouched = rng();
+        assert_eq!(InitialSpeed::default().sample(&mut untouched), Vec3::ZERO);
+        assert_eq!(untouched.random::<u64>(), rng().random::<u64>());
+
+        let mut sampled = rng();
+        for _ in 0..100 {
+            let velocity = InitialSpeed(500.0).sample(&mut sampled);
+            assert_eq!(velocity.y, -500.0);
+            assert!(velocity.x.abs() <= 50.0 && velocity.z.abs() <= 50.0);
+        }
+    }
+
+    #[test]
+    fn temperature_draws_a_velocity_of_its_speed() {
+        let mut untouched = rng();
+        assert_eq!(
+            Temperature::default().sample(true, &mut untouched),
+            Vec3::ZERO
+        );
+        assert_eq!(untouched.random::<u64>(), rng().random::<u64>());
+
+        let mut sampled = rng();
+        for _ in 0..100 {
+            let velocity = Temperature(300.0).sample(true, &mut sampled);
+            assert!((velocity.length() - 300.0).abs() < 0.01);
+            // In 2D the whole speed stays in the plane.
+            let velocity = Temperature(300.0).sample(false, &mut sampled);
+            assert_eq!(velocity.z, 0.0);
+            assert!((velocity.truncate().length() - 300.0).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn target_in_flight_tops_up_to_the_target() {
+        assert_eq!(TargetInFlight(0).budget(InFlight(500)), usize::MAX);
+        assert_eq!(TargetInFlight(50).budget(InFlight(38)), 12);
+        assert_eq!(TargetInFlight(50).budget(InFlight(60)), 0);
+    }
+
+    #[test]
+    fn spawn_jitter_stays_in_range_and_off_draws_nothing() {
+        let config = SpawnConfig::default();
+        let edge = Vec3::new(config.x_max, config.y, 0.0);
+        let mut jittered = rng();
+        for _ in 0..100 {
+            let p = SpawnJitter(2.0).apply(edge, &config, false, &mut jittered);
+            assert!(p.x >= config.x_max - 2.0 && p.x <= config.x_max);
+            assert_eq!((p.y, p.z), (config.y, 0.0));
         }
+
+        let mut untouched = rng();
+        assert_eq!(
+            SpawnJitter(0.0).apply(edge, &config, true, &mut untouched),
+            edge
+        );
+        assert_eq!(untouched.random::<u64>(), rng().random::<u64>());
+    }
+
+    #[test]
+    fn spawn_range_keeps_the_largest_ball_off_thick_walls() {
+        let pool = PoolConfig {
+            thickness: 60.0,
+            ..default()
+        };
+        let radius = 12.0;
+        let config = SpawnConfig::for_pool(pool, radius);
+        assert!(config.x_max + radius <= pool.width / 2.0 - pool.thickness);
+        assert!(config.x_min - radius >= -(pool.width / 2.0 - pool.thickness));
+        assert!(config.z_half + radius <= pool.depth / 2.0 - pool.thickness);
+    }
+
+    #[test]
+    fn inset_keeps_large_balls_off_the_walls() {
+        let config = SpawnConfig::default();
+        let edge = Vec3::new(config.x_max, config.y, -config.z_half);
+        let inset = DEFAULT_LARGE_RADIUS - BALL_RADIUS;
+        assert_eq!(
+            config.inset(edge, inset),
+            Vec3::new(config.x_max - inset, config.y, -config.z_half + inset)
+        );
+        let centre = Vec3::new(0.0, config.y, 0.0);
+        assert_eq!(config.inset(centre, inset), centre);
+    }
+
+    #[test]
+    fn grid_fills_a_row_before_wrapping() {
+        let config = SpawnConfig::default();
+        let columns = lattice_points(config.x_max - config.x_min);
+        let first = spawn_position(
+            SpawnPattern::Grid,
+            NozzleCount(0),
+            0,
+            0,
+            &config,
+            false,
+            &mut rng(),
+        );
+        let second = spawn_position(
+            SpawnPattern::Grid,
+            NozzleCount(0),
+            1,
+            0,
+            &config,
+            false,
+            &mut rng(),
+        );
+        let wrapped = spawn_position(
+            SpawnPattern::Grid,
+            NozzleCount(0),
+            columns,
+            0,
+            &config,
+            false,
+            &mut rng(),
+        );
+
+        assert_eq!(first, Vec3::new(config.x_min, config.y, 0.0));
+        assert_eq!(second.x - first.x, GRID_SPACING);
+        assert_eq!(wrapped, first);
+        assert!(second.x <= config.x_max);
+    }
+
+    #[test]
+    fn radius_range_stays_in_bounds_and_fixed_size_draws_nothing() {
+        let mut sampled = rng();
+        let range = RadiusRange(3.0, 9.0);
+        for _ in 0..100 {
+            let r = range.sample(&mut sampled);
+            assert!((3.0..=9.0).contains(&r));
+        }
+
+        let mut untouched = rng();
+        assert_eq!(RadiusRange::default().sample(&mut untouched), BALL_RADIUS);
+        assert_eq!(untouched.random::<u64>(), rng().random::<u64>());
+    }
+
+    #[test]
+    fn bi_disperse_draws_large_balls_at_the_configured_fraction() {
+        let mix = |large_fraction| BiDisperse {
+            large_fraction,
+            ..BiDisperse::default()
+        };
+        let mut sampled = rng();
+        assert!((0..100).all(|_| !mix(0.0).sample(&mut sampled)));
+        assert!((0..100).all(|_| mix(1.0).sample(&mut sampled)));
+        let large = (0..10_000)
+            .filter(|_| mix(0.2).sample(&mut sampled))
+            .count();
+        assert!((1_800..2_200).contains(&large));
+    }
+
+    #[test]
+    fn default_spin_is_zero_and_draws_nothing() {
+        let mut untouched = rng();
+        assert_eq!(InitialSpin::default().sample(&mut untouched), 0.0);
+        assert_eq!(untouched.random::<u64>(), rng().random::<u64>());
+
+        let spin = InitialSpin(-4.0, 2.0);
+        assert_eq!(spin.midpoint(), -1.0);
+        let mut sampled = rng();
+        assert!((0..100).all(|_| (-4.0..=2.0).contains(&spin.sample(&mut sampled))));
+    }
+
+    #[test]
+    fn bulk_lattice_fills_layers_without_overlap() {
+        let config = SpawnConfig::default();
+        let spacing = 2.0 * BALL_RADIUS + BULK_GAP;
+        let points: Vec<Vec3> =
+            bulk_positions(0.0, 2.5 * spacing, spacing, config, false).collect();
+        let columns = ((config.x_max - config.x_min) / spacing).floor() as usize + 1;
+
+        assert_eq!(points.len(), 3 * columns);
+        assert_eq!(points[0], Vec3::new(config.x_min, 0.0, 0.0));
+        assert_eq!(points[columns].y, spacing);
+        assert!(points.iter().all(|p| p.x <= config.x_max));
+        assert_eq!(bulk_positions(1.0, 0.0, spacing, config, true).count(), 0);
+    }
+
+    #[test]
+    fn prefill_stays_in_the_lower_half_clear_of_the_walls() {
+        let pool = PoolConfig::default();
+        let empty = ObstacleLayout::default();
+        let floor = FloorThickness::default();
+        let inner = pool.width / 2.0 - pool.thickness - BALL_RADIUS;
+        for is_3d in [false, true] {
+            let points: Vec<Vec3> =
+                prefill_positions(pool, BALL_RADIUS, floor, is_3d, &empty).collect();
+            assert!(!points.is_empty());
+            assert!(points.iter().all(|p| p.x.abs() < inner
+                && p.y - BALL_RADIUS > floor.top(pool)
+                && p.y + BALL_RADIUS < 0.0
+                && p.z.abs() + BALL_RADIUS < pool.depth / 2.0 - pool.thickness));
+        }
+
+        let funnel = ObstacleLayout::funnel(pool);
+        let count = prefill_positions(pool, BALL_RADIUS, floor, false, &empty).count();
+        assert!(prefill_positions(pool, BALL_RADIUS, floor, false, &funnel).count() < count);
+        let thick = FloorThickness(100.0);
+        assert!(prefill_positions(pool, BALL_RADIUS, thick, false, &empty).count() < count);
+    }
+
+    #[test]
+    fn burst_positions_stay_in_the_band_below_the_drop_line() {
+        let config = SpawnConfig::default();
+        let mut rng = rng();
+        for _ in 0..100 {
+            let p = burst_position(&config, BURST_DEPTH, true, &mut rng);
+            assert!((config.x_min..=config.x_max).contains(&p.x));
+            assert!((config.y - BURST_DEPTH..=config.y).contains(&p.y));
+            assert!(p.z.abs() <= config.z_half);
+        }
+        assert_eq!(burst_position(&config, BURST_DEPTH, false, &mut rng).z, 0.0);
+    }
+
+    #[test]
+    fn chains_drop_from_rain_positions() {
+        let config = SpawnConfig::default();
+        let (mut chain_rng, mut rain_rng) = (rng(), rng());
+        for index in 0..10 {
+            assert_eq!(
+                spawn_position(
+                    SpawnPattern::Chain,
+                    NozzleCount(3),
+                    index,
+                    0,
+                    &config,
+                    true,
+                    &mut chain_rng
+                ),
+                spawn_position(
+                    SpawnPattern::Rain,
+                    NozzleCount(3),
+                    index,
+                    0,
+                    &config,
+                    true,
+                    &mut rain_rng
+                ),
+            );
+        }
+        assert_eq!(SpawnPattern::Column.next(), SpawnPattern::Chain);
+        assert_eq!(SpawnPattern::Chain.next(), SpawnPattern::BiDisperse);
+        assert_eq!(SpawnPattern::BiDisperse.next(), SpawnPattern::Rain);
+    }
+
+    #[test]
+    fn nozzles_take_turns_at_evenly_spaced_points() {
+        let config = SpawnConfig::default();
+        let nozzles = NozzleCount(4);
+        let width = (config.x_max - config.x_min) / 4.0;
+        for index in 0..8 {
+            let p = spawn_position(
+                SpawnPattern::Rain,
+                nozzles,
+                index,
+                0,
+                &config,
+                false,
+                &mut rng(),
+            );
+            let centre = config.x_min + ((index % 4) as f32 + 0.5) * width;
+            assert!((p.x - centre).abs() <= NOZZLE_JITTER);
+        }
+    }
+
+    #[test]
+    fn column_stacks_balls_spawned_in_the_same_tick() {
+        let config = SpawnConfig::default();
+        let a = spawn_position(
+            SpawnPattern::Column,
+            NozzleCount(0),
+            7,
+            0,
+            &config,
+            true,
+            &mut rng(),
+        );
+        let b = spawn_position(
+            SpawnPattern::Column,
+            NozzleCount(0),
+            8,
+            1,
+            &config,
+            true,
+            &mut rng(),
+        );
+
+        assert_eq!(a.x, b.x);
+        assert_eq!(a.z, 0.0);
+        assert_eq!(b.y - a.y, GRID_SPACING);
     }
 }
@@ -0,0 +1,372 @@
+//! Speed heat-map — while [`ColorBySpeed`] is on (`V`), balls are tinted from blue
+//! (at rest) to red (fast) so settling and energy flow through the pile are visible.
+//! [`ColorByMass`] (`Shift + V`) instead tints them from pale yellow (lightest) to
+//! dark purple (heaviest), to check that heavy balls sink through the pile.
+//! [`ColorBySleep`] (`F7`) keeps the shared material on awake balls and dims the
+//! sleeping ones, so the pile can be watched going to sleep.
+//!
+//! Speeds and masses are quantised into a small palette of materials rather than one
+//! material per ball, but this still splits the shared [`BallAssets`] material into
+//! [`PALETTE_SIZE`] batches and rewrites material handles every frame, so expect a
+//! noticeable FPS cost at high ball counts. Benchmark numbers should be taken with
+//! it off; turning it off restores the shared material and batching resumes.
+
+use bevy::prelude::*;
+
+use crate::backend::{BallAssets, PhysicsMode};
+use crate::spawner::Ball;
+use crate::split::Ghost;
+
+/// Number of colour steps between the slowest and fastest tint.
+const PALETTE_SIZE: usize = 16;
+
+/// Speed (world units per second) at and above which a ball is fully red.
+const MAX_COLOR_SPEED: f32 = 500.0;
+
+const SLOW_COLOR: Color = Color::srgb(0.2, 0.3, 0.9);
+const FAST_COLOR: Color = Color::srgb(0.9, 0.2, 0.1);
+
+const LIGHT_COLOR: Color = Color::srgb(0.95, 0.9, 0.5);
+const HEAVY_COLOR: Color = Color::srgb(0.4, 0.1, 0.5);
+
+/// How far sleeping balls are mixed from the mode's colour towards black.
+const SLEEP_DIM: f32 = 0.6;
+
+/// Whether balls are coloured by speed. Toggled with `V`.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ColorBySpeed(pub bool);
+
+/// Whether balls are coloured by mass. Toggled with `Shift + V`; never on together
+/// with [`ColorBySpeed`].
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ColorByMass(pub bool);
+
+/// Whether sleeping balls are dimmed. Toggled with `F7`; never on together with
+/// [`ColorBySpeed`] or [`ColorByMass`].
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ColorBySleep(pub bool);
+
+/// Materials along one gradient, indexed by quantised value.
+struct Palette {
+    mats2d: Vec<Handle<ColorMaterial>>,
+    mats3d: Vec<Handle<StandardMaterial>>,
+}
+
+impl Palette {
+    fn new(
+        from: Color,
+        to: Color,
+        materials: &mut Assets<StandardMaterial>,
+        color_materials: &mut Assets<ColorMaterial>,
+    ) -> Self {
+        let colors = (0..PALETTE_SIZE).map(|i| {
+            let t = i as f32 / (PALETTE_SIZE - 1) as f32;
+            from.mix(&to, t)
+        });
+        let (mats2d, mats3d) = colors
+            .map(|color| {
+                (
+                    color_materials.add(ColorMaterial::from_color(color)),
+                    materials.add(StandardMaterial {
+                        base_color: color,
+                        ..default()
+                    }),
+                )
+            })
+            .unzip();
+        Self { mats2d, mats3d }
+    }
+}
+
+/// Blue→red by speed, yellow→purple by mass, and the dimmed mode colour for
+/// sleeping balls.
+#[derive(Resource)]
+struct Palettes {
+    speed: Palette,
+    mass: Palette,
+    asleep2d: Handle<ColorMaterial>,
+    asleep3d: Handle<StandardMaterial>,
+}
+
+pub fn plugin(app: &mut App) {
+    app.init_resource::<ColorBySpeed>();
+    app.init_resource::<ColorByMass>();
+    app.init_resource::<ColorBySleep>();
+    app.add_systems(Startup, setup_palettes);
+    app.add_systems(
+        Update,
+        (
+            restore_shared_material.run_if(
+                resource_changed::<ColorBySpeed>
+                    .or(resource_changed::<ColorByMass>)
+                    .or(resource_changed::<ColorBySleep>),
+            ),
+            update_ball_color.run_if(|on: Res<ColorBySpeed>| on.0),
+            update_mass_color.run_if(|on: Res<ColorByMass>| on.0),
+            tint_sleep_material.run_if(state_changed::<PhysicsMode>),
+            update_sleep_color.run_if(|on: Res<ColorBySleep>| on.0),
+        ),
+    );
+}
+
+fn setup_palettes(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut color_materials: ResMut<Assets<ColorMaterial>>,
+) {
+    commands.insert_resource(Palettes {
+        speed: Palette::new(SLOW_COLOR, FAST_COLOR, &mut materials, &mut color_materials),
+        mass: Palette::new(
+            LIGHT_COLOR,
+            HEAVY_COLOR,
+            &mut materials,
+            &mut color_materials,
+        ),
+        asleep2d: color_materials.add(ColorMaterial::default()),
+        asleep3d: materials.add(StandardMaterial::default()),
+    });
+}
+
+/// Dims the active mode's ball colour for the sleeping-ball materials.
+fn tint_sleep_material(
+    state: Res<State<PhysicsMode>>,
+    palettes: Res<Palettes>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut color_materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let color = state.get().color().mix(&Color::BLACK, SLEEP_DIM);
+    if let Some(material) = color_materials.get_mut(&palettes.asleep2d) {
+        material.color = color;
+    }
+    if let Some(material) = materials.get_mut(&palettes.asleep3d) {
+        material.base_color = color;
+    }
+}
+
+/// Palette index for a ball moving at `speed`.
+fn palette_index(speed: f32) -> usize {
+    let t = (speed / MAX_COLOR_SPEED).clamp(0.0, 1.0);
+    (t * (PALETTE_SIZE - 1) as f32).round() as usize
+}
+
+/// Palette index for a ball of `mass` among balls from `min` to `max`. Equal masses
+/// all get the middle of the palette.
+fn mass_index(mass: f32, min: f32, max: f32) -> usize {
+    if max <= min {
+        return PALETTE_SIZE / 2;
+    }
+    let t = ((mass - min) / (max - min)).clamp(0.0, 1.0);
+    (t * (PALETTE_SIZE - 1) as f32).round() as usize
+}
+
+/// Reads each ball's speed from the active backend (`LinearVelocity` on Avian,
+/// `Velocity` on Rapier) and swaps in the matching palette material.
+fn update_ball_color(
+    state: Res<State<PhysicsMode>>,
+    palettes: Res<Palettes>,
+    avian2d_balls: Query<(Entity, &avian2d::prelude::LinearVelocity), With<Ball>>,
+    avian3d_balls: Query<(Entity, &avian3d::prelude::LinearVelocity), With<Ball>>,
+    rapier2d_balls: Query<(Entity, &bevy_rapier2d::prelude::Velocity), With<Ball>>,
+    rapier3d_balls: Query<(Entity, &bevy_rapier3d::prelude::Velocity), With<Ball>>,
+    mut mats2d: Query<&mut MeshMaterial2d<ColorMaterial>, With<Ball>>,
+    mut mats3d: Query<&mut MeshMaterial3d<StandardMaterial>, With<Ball>>,
+) {
+    let palette = &palettes.speed;
+    let mut tint2d = |entity: Entity, speed: f32| {
+        if let Ok(mut material) = mats2d.get_mut(entity) {
+            let handle = &palette.mats2d[palette_index(speed)];
+            if material.0 != *handle {
+                material.0 = handle.clone();
+            }
+        }
+    };
+    match state.get() {
+        PhysicsMode::Avian2d => {
+            for (entity, velocity) in &avian2d_balls {
+                tint2d(entity, velocity.length());
+            }
+        }
+        PhysicsMode::Rapier2d => {
+            for (entity, velocity) in &rapier2d_balls {
+                tint2d(entity, velocity.linvel.length());
+            }
+        }
+        PhysicsMode::Avian3d | PhysicsMode::Rapier3d => {}
+    }
+
+    let mut tint3d = |entity: Entity, speed: f32| {
+        if let Ok(mut material) = mats3d.get_mut(entity) {
+            let handle = &palette.mats3d[palette_index(speed)];
+            if material.0 != *handle {
+                material.0 = handle.clone();
+            }
+        }
+    };
+    match state.get() {
+        PhysicsMode::Avian3d => {
+            for (entity, velocity) in &avian3d_balls {
+                tint3d(entity, velocity.length());
+            }
+        }
+        PhysicsMode::Rapier3d => {
+            for (entity, velocity) in &rapier3d_balls {
+                tint3d(entity, velocity.linvel.length());
+            }
+        }
+        PhysicsMode::Avian2d | PhysicsMode::Rapier2d => {}
+    }
+}
+
+/// Reads each ball's mass from the active backend (`ComputedMass` on Avian,
+/// `ReadMassProperties` on Rapier) and swaps in the matching palette material, the
+/// gradient spanning the lightest to the heaviest ball in the pool.
+fn update_mass_color(
+    state: Res<State<PhysicsMode>>,
+    palettes: Res<Palettes>,
+    avian2d_balls: Query<(Entity, &avian2d::prelude::ComputedMass), With<Ball>>,
+    avian3d_balls: Query<(Entity, &avian3d::prelude::ComputedMass), With<Ball>>,
+    rapier2d_balls: Query<(Entity, &bevy_rapier2d::prelude::ReadMassProperties), With<Ball>>,
+    rapier3d_balls: Query<(Entity, &bevy_rapier3d::prelude::ReadMassProperties), With<Ball>>,
+    mut mats2d: Query<&mut MeshMaterial2d<ColorMaterial>, With<Ball>>,
+    mut mats3d: Query<&mut MeshMaterial3d<StandardMaterial>, With<Ball>>,
+) {
+    let masses: Vec<(Entity, f32)> = match state.get() {
+        PhysicsMode::Avian2d => avian2d_balls.iter().map(|(e, m)| (e, m.value())).collect(),
+        PhysicsMode::Avian3d => avian3d_balls.iter().map(|(e, m)| (e, m.value())).collect(),
+        PhysicsMode::Rapier2d => rapier2d_balls.iter().map(|(e, m)| (e, m.mass)).collect(),
+        PhysicsMode::Rapier3d => rapier3d_balls.iter().map(|(e, m)| (e, m.mass)).collect(),
+    };
+    let (min, max) = masses.iter().fold(
+        (f32::INFINITY, f32::NEG_INFINITY),
+        |(min, max), &(_, mass)| (min.min(mass), max.max(mass)),
+    );
+
+    let palette = &palettes.mass;
+    let is_3d = matches!(state.get(), PhysicsMode::Avian3d | PhysicsMode::Rapier3d);
+    for (entity, mass) in masses {
+        let index = mass_index(mass, min, max);
+        if is_3d {
+            if let Ok(mut material) = mats3d.get_mut(entity) {
+                let handle = &palette.mats3d[index];
+                if material.0 != *handle {
+                    material.0 = handle.clone();
+                }
+            }
+        } else if let Ok(mut material) = mats2d.get_mut(entity) {
+            let handle = &palette.mats2d[index];
+            if material.0 != *handle {
+                material.0 = handle.clone();
+            }
+        }
+    }
+}
+
+/// Reads each ball's sleeping state from the active backend (the `Sleeping`
+/// component on Avian, the rigid body in `RapierRigidBodySet` on Rapier) and gives
+/// sleeping balls the dimmed material and awake ones the shared material back.
+fn update_sleep_color(
+    state: Res<State<PhysicsMode>>,
+    palettes: Res<Palettes>,
+    assets: Res<BallAssets>,
+    avian2d_balls: Query<
+        (Entity, Has<avian2d::prelude::Sleeping>),
+        (With<Ball>, With<avian2d::prelude::RigidBody>),
+    >,
+    avian3d_balls: Query<
+        (Entity, Has<avian3d::prelude::Sleeping>),
+        (With<Ball>, With<avian3d::prelude::RigidBody>),
+    >,
+    rapier2d_sets: Query<&bevy_rapier2d::plugin::context::RapierRigidBodySet>,
+    rapier3d_sets: Query<&bevy_rapier3d::plugin::context::RapierRigidBodySet>,
+    mut mats2d: Query<&mut MeshMaterial2d<ColorMaterial>, (With<Ball>, Without<Ghost>)>,
+    mut mats3d: Query<&mut MeshMaterial3d<StandardMaterial>, (With<Ball>, Without<Ghost>)>,
+) {
+    let balls: Vec<(Entity, bool)> = match state.get() {
+        PhysicsMode::Avian2d => avian2d_balls.iter().collect(),
+        PhysicsMode::Avian3d => avian3d_balls.iter().collect(),
+        PhysicsMode::Rapier2d => rapier2d_sets
+            .iter()
+            .flat_map(|set| {
+                set.entity2body()
+                    .iter()
+                    .filter_map(|(&entity, &handle)| Some((entity, set.bodies.get(handle)?)))
+                    .map(|(entity, body)| (entity, body.is_sleeping()))
+            })
+            .collect(),
+        PhysicsMode::Rapier3d => rapier3d_sets
+            .iter()
+            .flat_map(|set| {
+                set.entity2body()
+                    .iter()
+                    .filter_map(|(&entity, &handle)| Some((entity, set.bodies.get(handle)?)))
+                    .map(|(entity, body)| (entity, body.is_sleeping()))
+            })
+            .collect(),
+    };
+
+    let is_3d = matches!(state.get(), PhysicsMode::Avian3d | PhysicsMode::Rapier3d);
+    for (entity, asleep) in balls {
+        if is_3d {
+            if let Ok(mut material) = mats3d.get_mut(entity) {
+                let handle = if asleep {
+                    &palettes.asleep3d
+                } else {
+                    &assets.mat3d
+                };
+                if material.0 != *handle {
+                    material.0 = handle.clone();
+                }
+            }
+        } else if let Ok(mut material) = mats2d.get_mut(entity) {
+            let handle = if asleep {
+                &palettes.asleep2d
+            } else {
+                &assets.mat2d
+            };
+            if material.0 != *handle {
+                material.0 = handle.clone();
+            }
+        }
+    }
+}
+
+/// Puts every ball back on the shared material once colouring is switched off.
+/// Ghosts keep their translucent one.
+fn restore_shared_material(
+    color_by_speed: Res<ColorBySpeed>,
+    color_by_mass: Res<ColorByMass>,
+    color_by_sleep: Res<ColorBySleep>,
+    assets: Res<BallAssets>,
+    mut mats2d: Query<&mut MeshMaterial2d<ColorMaterial>, (With<Ball>, Without<Ghost>)>,
+    mut mats3d: Query<&mut MeshMaterial3d<StandardMaterial>, (With<Ball>, Without<Ghost>)>,
+) {
+    if color_by_speed.0 || color_by_mass.0 || color_by_sleep.0 {
+        return;
+    }
+    for mut material in &mut mats2d {
+        material.0 = assets.mat2d.clone();
+    }
+    for mut material in &mut mats3d {
+        material.0 = assets.mat3d.clone();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn palette_index_spans_palette_and_saturates() {
+        assert_eq!(palette_index(0.0), 0);
+        assert_eq!(palette_index(MAX_COLOR_SPEED), PALETTE_SIZE - 1);
+        assert_eq!(palette_index(10.0 * MAX_COLOR_SPEED), PALETTE_SIZE - 1);
+        assert!(palette_index(MAX_COLOR_SPEED / 2.0) < PALETTE_SIZE - 1);
+    }
+
+    #[test]
+    fn mass_index_spans_the_pool_range() {
+        assert_eq!(mass_index(1.0, 1.0, 4.0), 0);
+        assert_eq!(mass_index(4.0, 1.0, 4.0), PALETTE_SIZE - 1);
+        assert_eq!(mass_index(2.0, 2.0, 2.0), PALETTE_SIZE / 2);
+    }
+}
@@ -0,0 +1,988 @@
+//! Split screen — Avian and Rapier side by side on identical spawns. `J` (or
+//! `--split`) adds the active mode's counterpart in the same dimension: the left
+//! half shows the active mode, the right half its counterpart.
+//!
+//! Both engines are always registered, so the counterpart just needs bodies: a
+//! second pool is built with `WallConfig`, and `spawner::spawn_balls` drops a twin
+//! of every timed ball into it at the same position with the same parameters. The
+//! two pools overlap in world space but never interact, since each engine only
+//! simulates its own bodies. They are told apart for rendering with render layers:
+//! everything owned by the counterpart is on its [`partner_layer`], which only the
+//! right-hand camera sees.
+//!
+//! The active mode's HUD, ball count and clipped counter are unchanged; the
+//! counterpart's are tracked in [`PartnerStats`] and shown above the right half.
+//! Click, bulk, burst and prefill spawns stay single-sided.
+//!
+//! The ghost overlay (`F6` or `--ghost`) runs the counterpart the same way but keeps
+//! a single view: its balls are drawn as translucent [`Ghost`]s over the active
+//! mode's and its pool is hidden, so wherever the two engines diverge the ghosts
+//! drift away from the solid balls.
+//!
+//! Four windows (`F12` or `--four-windows`) run all four modes at once: the main
+//! window keeps the active mode, and three more OS windows each show one of the
+//! other modes, so they can be spread over a grid of monitors. Every other mode gets
+//! its own pool and twins of the timed balls, on its own render layer.
+
+use bevy::{
+    prelude::*,
+    window::{PrimaryWindow, WindowRef},
+};
+use std::collections::HashMap;
+
+// Bevy 0.17 moved cameras and render layers out of `bevy::render` into `bevy::camera`.
+#[cfg(not(feature = "legacy_state_scoped"))]
+use bevy::camera::{ClearColorConfig, RenderTarget, Viewport, visibility::RenderLayers};
+#[cfg(feature = "legacy_state_scoped")]
+use bevy::render::{
+    camera::{ClearColorConfig, RenderTarget, Viewport},
+    view::RenderLayers,
+};
+
+use crate::backend::{self, BallAssets, BallParams, BodyOwner, PhysicsMode};
+use crate::keys::KeyBindings;
+use crate::perf_graph::PerfGraphCamera;
+use crate::spawner::{self, Ball, BallConfig, SpawnConfig};
+use crate::walls::{self, PoolConfig, WallConfig};
+use crate::{
+    CLIP_MARGIN, Cam3dOrbit, CameraLock, TopLight, cam3d_pos_for_window, cli,
+    ortho_scale_for_window,
+};
+
+/// Render layer of a partner mode's pool, balls, camera and, in 3D, light. Layer 0
+/// is the active mode's.
+fn partner_layer(mode: PhysicsMode) -> usize {
+    1 + mode as usize
+}
+
+/// Opacity of ghost balls.
+const GHOST_ALPHA: f32 = 0.35;
+
+/// How the other modes are shown, if any run at all. `J` (or `--split`) toggles split
+/// screen, `F6` (or `--ghost`) the ghost overlay and `F12` (or `--four-windows`) the
+/// four windows; turning one on turns the others off.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PartnerView {
+    #[default]
+    Off,
+    Split,
+    Ghost,
+    Windows,
+}
+
+impl PartnerView {
+    fn from_args() -> Self {
+        if cli::flag("--split") {
+            PartnerView::Split
+        } else if cli::flag("--ghost") {
+            PartnerView::Ghost
+        } else if cli::flag("--four-windows") {
+            PartnerView::Windows
+        } else {
+            PartnerView::Off
+        }
+    }
+
+    /// Whether any other engine gets bodies.
+    pub fn is_running(self) -> bool {
+        self != PartnerView::Off
+    }
+
+    /// The modes that run alongside `active`: its counterpart in split screen and the
+    /// ghost overlay, every other mode in the four windows.
+    pub fn partners(self, active: PhysicsMode) -> Vec<PhysicsMode> {
+        match self {
+            PartnerView::Off => Vec::new(),
+            PartnerView::Split | PartnerView::Ghost => vec![active.counterpart()],
+            PartnerView::Windows => PhysicsMode::ALL
+                .into_iter()
+                .filter(|&mode| mode != active)
+                .collect(),
+        }
+    }
+
+    /// The view after pressing the key for `view`: on, or off if it already was.
+    fn toggled(self, view: PartnerView) -> Self {
+        if self == view { PartnerView::Off } else { view }
+    }
+}
+
+/// Whether another engine has run alongside the active mode since it was entered (or
+/// reset). Such a run times two or four engines in one frame, so its milestones,
+/// benchmark history and CSV rows are not recorded; see [`solo_run`].
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PartnerRan(pub bool);
+
+/// Run condition: the active mode has run alone since it was entered. Always true
+/// without the split plugin, as in the headless app.
+pub fn solo_run(ran: Option<Res<PartnerRan>>) -> bool {
+    !ran.is_some_and(|ran| ran.0)
+}
+
+/// Starts a new run's [`PartnerRan`] from whether a partner is running now.
+pub(crate) fn restart_partner_ran(view: Res<PartnerView>, mut ran: ResMut<PartnerRan>) {
+    ran.0 = view.is_running();
+}
+
+fn mark_partner_ran(view: Res<PartnerView>, mut ran: ResMut<PartnerRan>) {
+    if view.is_running() && !ran.0 {
+        ran.0 = true;
+    }
+}
+
+/// Counters of a mode running alongside the active one, the equivalents of `BallCount`
+/// and `ClippedBallCount`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SplitStats {
+    pub balls: usize,
+    pub clipped: usize,
+}
+
+/// [`SplitStats`] of every mode running alongside the active one.
+#[derive(Resource, Debug, Clone, PartialEq, Eq, Default)]
+pub struct PartnerStats(pub HashMap<PhysicsMode, SplitStats>);
+
+impl PartnerStats {
+    fn get(&self, mode: PhysicsMode) -> SplitStats {
+        self.0.get(&mode).copied().unwrap_or_default()
+    }
+}
+
+/// Marker for everything split screen adds — the counterpart's bodies, its camera,
+/// the full-window UI camera and the right-hand label — and likewise for the ghost
+/// overlay and the four windows, so it can all be removed together and kept out of
+/// the active mode's ball queries.
+#[derive(Component)]
+pub struct SplitPartner;
+
+/// A counterpart ball drawn translucent in the ghost overlay. Colouring by speed
+/// or mass leaves it alone.
+#[derive(Component)]
+pub struct Ghost;
+
+/// Translucent materials for ghost balls, in the counterpart's colour.
+#[derive(Resource)]
+struct GhostMaterials {
+    mat2d: Handle<ColorMaterial>,
+    mat3d: Handle<StandardMaterial>,
+}
+
+#[derive(Component)]
+struct PartnerCamera;
+
+#[derive(Component)]
+struct SplitLabel;
+
+/// One of the three extra OS windows of the four-window view, by slot in
+/// [`PartnerView::partners`]. It is not a [`SplitPartner`]: it stays open across mode
+/// switches, and only the camera drawing into it is rebuilt.
+#[derive(Component)]
+struct PartnerWindow(usize);
+
+/// The camera drawing into a [`PartnerWindow`].
+#[derive(Component)]
+struct WindowCamera(Entity);
+
+/// Marker for the cameras and lights of the four windows, which the other views'
+/// layout leaves alone.
+#[derive(Component)]
+struct WindowView;
+
+pub fn plugin(app: &mut App) {
+    app.insert_resource(PartnerView::from_args());
+    app.init_resource::<PartnerStats>();
+    app.init_resource::<PartnerRan>();
+    app.add_systems(Startup, setup_ghost_materials);
+    app.add_systems(
+        Update,
+        (
+            (handle_partner_view, mark_partner_ran).chain(),
+            close_partner_view,
+            color_ghosts.run_if(state_changed::<PhysicsMode>),
+            layout_split.after(crate::fit_camera_to_pool),
+            (manage_partner_windows, layout_partner_windows)
+                .chain()
+                .after(crate::fit_camera_to_pool),
+            (maintain_partner_pool, tag_partner_bodies).chain(),
+            sync_partner_camera.after(layout_split),
+            clip_partner_balls,
+            (update_split_label, update_window_titles),
+        ),
+    );
+    // After every schedule that spawns balls, so new ones are styled before rendering.
+    app.add_systems(PostUpdate, style_partner_bodies);
+    for mode in PhysicsMode::ALL {
+        app.add_systems(OnExit(mode), despawn_split);
+        app.add_systems(OnEnter(mode), restart_partner_ran);
+    }
+}
+
+/// Spawns `partner`'s twin of a timed ball. Called by `spawner::spawn_balls` right
+/// after the active mode's ball, so every engine sees the same drop sequence; a 2D
+/// twin of a 3D ball drops in the plane. [`style_partner_bodies`] puts it on the
+/// right layer or turns it into a ghost.
+pub fn spawn_partner_ball(
+    commands: &mut Commands,
+    partner: PhysicsMode,
+    position: Vec3,
+    params: BallParams,
+    assets: &BallAssets,
+) -> Entity {
+    let position = match partner {
+        PhysicsMode::Avian2d | PhysicsMode::Rapier2d => position.with_z(0.0),
+        PhysicsMode::Avian3d | PhysicsMode::Rapier3d => position,
+    };
+    let ball = backend::spawn_ball(commands, partner, position, params, assets);
+    commands.entity(ball).insert(SplitPartner);
+    ball
+}
+
+fn handle_partner_view(
+    input: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut view: ResMut<PartnerView>,
+) {
+    if input.just_pressed(bindings.split_screen) {
+        let toggled = view.toggled(PartnerView::Split);
+        view.set_if_neq(toggled);
+    }
+    if input.just_pressed(bindings.ghost) {
+        let toggled = view.toggled(PartnerView::Ghost);
+        view.set_if_neq(toggled);
+    }
+    if input.just_pressed(bindings.four_windows) {
+        let toggled = view.toggled(PartnerView::Windows);
+        view.set_if_neq(toggled);
+    }
+}
+
+/// Closing one of the four windows by hand turns the other modes off, like `F12`.
+fn close_partner_view(mut closed: RemovedComponents<PartnerWindow>, mut view: ResMut<PartnerView>) {
+    if closed.read().count() > 0 && *view == PartnerView::Windows {
+        *view = PartnerView::Off;
+    }
+}
+
+/// Opens the three extra windows, at the size of the main one, when the four-window
+/// view is switched on, and closes them, with their cameras and lights, when it is
+/// switched to anything else.
+fn manage_partner_windows(
+    mut commands: Commands,
+    view: Res<PartnerView>,
+    primary: Query<&Window, With<PrimaryWindow>>,
+    partner_windows: Query<Entity, With<PartnerWindow>>,
+    window_views: Query<Entity, With<WindowView>>,
+) {
+    if !view.is_changed() {
+        return;
+    }
+    if *view != PartnerView::Windows {
+        for entity in partner_windows.iter().chain(&window_views) {
+            commands.entity(entity).despawn();
+        }
+        return;
+    }
+    let Ok(primary) = primary.single() else {
+        return;
+    };
+    if partner_windows.is_empty() {
+        for slot in 0..PhysicsMode::ALL.len() - 1 {
+            commands.spawn((
+                Name::new("Partner Window"),
+                PartnerWindow(slot),
+                Window {
+                    resolution: primary.resolution.clone(),
+                    ..default()
+                },
+            ));
+        }
+    }
+}
+
+/// Gives each partner window a camera on its mode's layer, and in 3D a light, when
+/// the window opens or the active mode changes. Every window is fitted to the pool
+/// on its own each frame; the camera lock, pans and orbit stay with the main window.
+fn layout_partner_windows(
+    mut commands: Commands,
+    view: Res<PartnerView>,
+    state: Res<State<PhysicsMode>>,
+    pool: Res<PoolConfig>,
+    windows: Query<(Entity, &Window, &PartnerWindow)>,
+    mut cameras: Query<(&WindowCamera, &mut Projection, &mut Transform)>,
+) {
+    if *view != PartnerView::Windows {
+        return;
+    }
+    let partners = view.partners(*state.get());
+    for (window, size, slot) in &windows {
+        let Some(&mode) = partners.get(slot.0) else {
+            continue;
+        };
+        if cameras.iter().any(|(camera, ..)| camera.0 == window) {
+            continue;
+        }
+        let is_3d = matches!(mode, PhysicsMode::Avian3d | PhysicsMode::Rapier3d);
+        let mut projection = if is_3d {
+            Projection::default()
+        } else {
+            Projection::Orthographic(OrthographicProjection::default_2d())
+        };
+        let mut transform = Transform::default();
+        fit_window_camera(*pool, size, &mut projection, &mut transform);
+        let target = RenderTarget::Window(WindowRef::Entity(window));
+        #[cfg(not(feature = "legacy_state_scoped"))]
+        let camera = (Camera::default(), target);
+        #[cfg(feature = "legacy_state_scoped")]
+        let camera = Camera {
+            target,
+            ..default()
+        };
+        let window_camera = (
+            Name::new("Window Camera"),
+            SplitPartner,
+            WindowView,
+            WindowCamera(window),
+            camera,
+            projection,
+            transform,
+            RenderLayers::layer(partner_layer(mode)),
+        );
+        if is_3d {
+            commands.spawn((window_camera, Camera3d::default()));
+            commands.spawn((
+                Name::new("Window Light"),
+                SplitPartner,
+                WindowView,
+                crate::top_light(),
+                RenderLayers::layer(partner_layer(mode)),
+            ));
+        } else {
+            commands.spawn((window_camera, Camera2d));
+        }
+    }
+    for (camera, mut projection, mut transform) in &mut cameras {
+        if let Ok((_, size, _)) = windows.get(camera.0) {
+            fit_window_camera(*pool, size, &mut projection, &mut transform);
+        }
+    }
+}
+
+/// Frames a partner window's camera like the main camera's fitted view of `pool` in
+/// a window of that size. The main camera's manual framing touches every camera, so
+/// this also undoes it.
+fn fit_window_camera(
+    pool: PoolConfig,
+    window: &Window,
+    projection: &mut Projection,
+    transform: &mut Transform,
+) {
+    if let Projection::Orthographic(ortho) = projection {
+        ortho.scale = ortho_scale_for_window(pool, window.width(), window.height());
+        *transform = Transform::default();
+    } else {
+        *transform = Cam3dOrbit::default().transform(pool, window.width(), window.height());
+    }
+}
+
+fn setup_ghost_materials(
+    mut commands: Commands,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut color_materials: ResMut<Assets<ColorMaterial>>,
+) {
+    commands.insert_resource(GhostMaterials {
+        mat2d: color_materials.add(ColorMaterial::from_color(Color::NONE)),
+        mat3d: materials.add(StandardMaterial {
+            base_color: Color::NONE,
+            alpha_mode: AlphaMode::Blend,
+            ..default()
+        }),
+    });
+}
+
+/// Tints the ghosts in the colour of the new mode's counterpart.
+fn color_ghosts(
+    state: Res<State<PhysicsMode>>,
+    ghost_materials: Res<GhostMaterials>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut color_materials: ResMut<Assets<ColorMaterial>>,
+) {
+    let color = state.get().counterpart().color().with_alpha(GHOST_ALPHA);
+    if let Some(material) = color_materials.get_mut(&ghost_materials.mat2d) {
+        material.color = color;
+    }
+    if let Some(material) = materials.get_mut(&ghost_materials.mat3d) {
+        material.base_color = color;
+    }
+}
+
+/// Left and right halves of a window of `size` physical pixels.
+fn half_viewports(size: UVec2) -> (Viewport, Viewport) {
+    let half = UVec2::new(size.x / 2, size.y);
+    let left = Viewport {
+        physical_position: UVec2::ZERO,
+        physical_size: half,
+        ..default()
+    };
+    let right = Viewport {
+        physical_position: UVec2::new(half.x, 0),
+        physical_size: UVec2::new(size.x - half.x, size.y),
+        ..default()
+    };
+    (left, right)
+}
+
+/// Splits the view when split screen is switched on, a mode's camera is spawned or
+/// the window or camera lock changes, and restores the single full-window view when it is off.
+/// Each half is fitted to the pool on its own, unless the camera is locked.
+fn layout_split(
+    mut commands: Commands,
+    view: Res<PartnerView>,
+    state: Res<State<PhysicsMode>>,
+    lock: Res<CameraLock>,
+    pool: Res<PoolConfig>,
+    windows: Query<Ref<Window>, With<PrimaryWindow>>,
+    mut main_camera: Query<
+        (
+            Entity,
+            &mut Camera,
+            &mut Projection,
+            &mut Transform,
+            Has<Camera3d>,
+        ),
+        (Without<SplitPartner>, Without<PerfGraphCamera>),
+    >,
+    mut partner_camera: Query<&mut Camera, (With<PartnerCamera>, With<SplitPartner>)>,
+    partners: Query<(Entity, Has<BodyOwner>), (With<SplitPartner>, Without<WindowView>)>,
+    lights: Query<Entity, With<TopLight>>,
+) {
+    let Ok(window) = windows.single() else {
+        return;
+    };
+    if !(view.is_changed() || state.is_changed() || lock.is_changed() || window.is_changed()) {
+        return;
+    }
+    let split = *view == PartnerView::Split;
+    let Ok((main, mut camera, mut projection, mut transform, is_3d)) = main_camera.single_mut()
+    else {
+        return;
+    };
+
+    let width = if split {
+        window.width() / 2.0
+    } else {
+        window.width()
+    };
+    if !lock.0 {
+        match *projection {
+            Projection::Orthographic(ref mut ortho) => {
+                ortho.scale = ortho_scale_for_window(*pool, width, window.height());
+            }
+            _ if is_3d => {
+                transform.translation = cam3d_pos_for_window(*pool, width, window.height());
+            }
+            _ => {}
+        }
+    }
+
+    if !split {
+        camera.viewport = None;
+        commands.entity(main).insert(IsDefaultUiCamera);
+        // The ghost overlay keeps the counterpart's bodies, only the extra cameras
+        // and the label go.
+        for (entity, is_body) in &partners {
+            if !is_body || !view.is_running() {
+                commands.entity(entity).despawn();
+            }
+        }
+        return;
+    }
+
+    let (left, right) = half_viewports(window.physical_size());
+    camera.viewport = Some(left);
+    if let Ok(mut partner) = partner_camera.single_mut() {
+        partner.viewport = Some(right);
+        return;
+    }
+
+    // First layout for this mode: add the right-hand camera, a full-window camera
+    // for the HUD (which would otherwise be squeezed into the left half) and the
+    // right-hand label.
+    commands.entity(main).remove::<IsDefaultUiCamera>();
+    let partner_camera = (
+        Name::new("Split Camera"),
+        SplitPartner,
+        PartnerCamera,
+        Camera {
+            order: 1,
+            viewport: Some(right),
+            ..default()
+        },
+        projection.clone(),
+        *transform,
+        RenderLayers::layer(partner_layer(state.get().counterpart())),
+    );
+    if is_3d {
+        commands.spawn((partner_camera, Camera3d::default()));
+        // The counterpart's bodies are only lit by lights on their layer.
+        for light in &lights {
+            commands.entity(light).insert(RenderLayers::from_layers(&[
+                0,
+                partner_layer(state.get().counterpart()),
+            ]));
+        }
+    } else {
+        commands.spawn((partner_camera, Camera2d));
+    }
+    commands.spawn((
+        Name::new("Split UI Camera"),
+        SplitPartner,
+        Camera2d,
+        Camera {
+            order: 2,
+            clear_color: ClearColorConfig::None,
+            ..default()
+        },
+        RenderLayers::none(),
+        IsDefaultUiCamera,
+    ));
+    commands.spawn((
+        Name::new("Split Label"),
+        SplitPartner,
+        SplitLabel,
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Percent(50.0),
+            bottom: Val::Px(8.0),
+            padding: UiRect::horizontal(Val::Px(12.0)),
+            ..default()
+        },
+        Text::new(split_label(
+            state.get().counterpart(),
+            SplitStats::default(),
+        )),
+        TextFont {
+            font_size: 20.0,
+            ..default()
+        },
+        TextColor(state.get().counterpart().color()),
+    ));
+}
+
+/// Keeps the right-hand camera framed exactly like the left one, including manual
+/// pans and zooms while the camera is locked.
+fn sync_partner_camera(
+    main_camera: Query<
+        (&Projection, &Transform),
+        (
+            Without<SplitPartner>,
+            Without<PerfGraphCamera>,
+            With<Camera>,
+        ),
+    >,
+    mut partner_camera: Query<
+        (&mut Projection, &mut Transform),
+        (With<PartnerCamera>, With<SplitPartner>),
+    >,
+) {
+    let Ok((projection, transform)) = main_camera.single() else {
+        return;
+    };
+    for (mut partner_projection, mut partner_transform) in &mut partner_camera {
+        *partner_projection = projection.clone();
+        *partner_transform = *transform;
+    }
+}
+
+/// Builds each partner's pool while it runs, and rebuilds them when the active
+/// mode's pool changes shape: main's drain, lid and surface handlers only respawn
+/// the active mode's walls, but they remove the partners' floors or lids along with
+/// their own. Switching from the four windows to a single partner removes the
+/// bodies of the modes that stop running.
+fn maintain_partner_pool(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    view: Res<PartnerView>,
+    state: Res<State<PhysicsMode>>,
+    ball_config: BallConfig,
+    wall_config: WallConfig,
+    (drain, floor, top_wall): (
+        Res<walls::FloorDrain>,
+        Res<walls::FloorThickness>,
+        Res<walls::TopWall>,
+    ),
+    partner_bodies: Query<
+        (Entity, &BodyOwner, Has<walls::Wall>, Has<walls::Floor>),
+        With<SplitPartner>,
+    >,
+) {
+    if !view.is_running() {
+        return;
+    }
+    let partners = view.partners(*state.get());
+    if view.is_changed() {
+        for (entity, owner, ..) in &partner_bodies {
+            if !partners.contains(&owner.0) {
+                commands.entity(entity).despawn();
+            }
+        }
+    }
+    let reshaped = drain.is_changed() || floor.is_changed() || top_wall.is_changed();
+    for partner in partners {
+        let walls: Vec<_> = partner_bodies
+            .iter()
+            .filter(|&(_, owner, is_wall, _)| is_wall && owner.0 == partner)
+            .map(|(wall, _, _, is_floor)| (wall, is_floor))
+            .collect();
+        // A pool without a floor counts as built once any of its walls is.
+        let built = walls
+            .iter()
+            .any(|&(_, is_floor)| is_floor || !wall_config.has_floor());
+        if built && !reshaped {
+            continue;
+        }
+        // The handler that changed the pool may have queued some of these already.
+        for (wall, _) in walls {
+            commands.entity(wall).try_despawn();
+        }
+        wall_config.spawn(
+            &mut commands,
+            &mut meshes,
+            &mut materials,
+            partner,
+            ball_config.surface(),
+        );
+    }
+}
+
+/// Tags the partners' freshly built walls and joints as [`SplitPartner`]. Balls are
+/// tagged as they are spawned (see [`spawn_partner_ball`]).
+fn tag_partner_bodies(
+    mut commands: Commands,
+    view: Res<PartnerView>,
+    state: Res<State<PhysicsMode>>,
+    bodies: Query<(Entity, &BodyOwner), Without<SplitPartner>>,
+) {
+    let partners = view.partners(*state.get());
+    for (entity, owner) in &bodies {
+        if partners.contains(&owner.0) {
+            commands.entity(entity).insert(SplitPartner);
+        }
+    }
+}
+
+/// A partner body, its mode and whether it is a ball, drawn in 2D or drawn in 3D.
+type PartnerBody = (
+    Entity,
+    &'static BodyOwner,
+    Has<Ball>,
+    Has<Mesh2d>,
+    Has<Mesh3d>,
+);
+
+/// Puts the partners' bodies on their [`partner_layer`] under split screen and in the
+/// four windows. Under the ghost overlay they stay on the main camera's layer
+/// instead, with the pool hidden behind the active mode's and the balls swapped to
+/// the translucent ghost material. Runs for new bodies, and for all of them when the
+/// view changes.
+fn style_partner_bodies(
+    mut commands: Commands,
+    view: Res<PartnerView>,
+    assets: Res<BallAssets>,
+    ghost_materials: Res<GhostMaterials>,
+    bodies: Query<PartnerBody, With<SplitPartner>>,
+    new_bodies: Query<PartnerBody, Added<SplitPartner>>,
+) {
+    let styled: Vec<_> = if view.is_changed() {
+        bodies.iter().collect()
+    } else {
+        new_bodies.iter().collect()
+    };
+    for (entity, owner, is_ball, has_mesh2d, has_mesh3d) in styled {
+        let mut body = commands.entity(entity);
+        match *view {
+            PartnerView::Ghost => {
+                body.remove::<RenderLayers>();
+                if !is_ball {
+                    body.insert(Visibility::Hidden);
+                    continue;
+                }
+                body.insert(Ghost);
+                if has_mesh2d {
+                    body.insert(MeshMaterial2d(ghost_materials.mat2d.clone()));
+                }
+                if has_mesh3d {
+                    body.insert(MeshMaterial3d(ghost_materials.mat3d.clone()));
+                }
+            }
+            PartnerView::Split | PartnerView::Windows | PartnerView::Off => {
+                let layer = RenderLayers::layer(partner_layer(owner.0));
+                body.insert((layer, Visibility::Inherited));
+                if !is_ball {
+                    continue;
+                }
+                body.remove::<Ghost>();
+                if has_mesh2d {
+                    body.insert(MeshMaterial2d(assets.mat2d.clone()));
+                }
+                if has_mesh3d {
+                    body.insert(MeshMaterial3d(assets.mat3d.clone()));
+                }
+            }
+        }
+    }
+}
+
+/// The partners' version of `detect_clipped_balls`: counts each partner mode's balls
+/// and removes (or, without walls, recycles) the ones that escaped the pool. Balls
+/// that exploded are always removed, and counted as clipped on their side.
+fn clip_partner_balls(
+    mut commands: Commands,
+    view: Res<PartnerView>,
+    state: Res<State<PhysicsMode>>,
+    (pool, spawn_config): (Res<PoolConfig>, Res<SpawnConfig>),
+    no_walls: Res<walls::NoWalls>,
+    balls: Query<(Entity, &Transform, &BodyOwner), (With<Ball>, With<SplitPartner>)>,
+    mut stats: ResMut<PartnerStats>,
+) {
+    let floor_y = -pool.height / 2.0 - CLIP_MARGIN * pool.scale;
+    let max_z = pool.depth / 2.0 + CLIP_MARGIN * pool.scale;
+
+    let mut counted: HashMap<_, _> = view
+        .partners(*state.get())
+        .into_iter()
+        .map(|partner| {
+            let clipped = stats.get(partner).clipped;
+            (partner, SplitStats { balls: 0, clipped })
+        })
+        .collect();
+    for (entity, transform, owner) in &balls {
+        let Some(counted) = counted.get_mut(&owner.0) else {
+            continue;
+        };
+        let is_3d = matches!(owner.0, PhysicsMode::Avian3d | PhysicsMode::Rapier3d);
+        let pos = transform.translation;
+        let blown_up = crate::exploded(pos, *pool);
+        if blown_up {
+            warn!("{} ball exploded to {pos}", owner.0.label());
+        }
+        let escaped = blown_up || pos.y < floor_y || (is_3d && pos.z.abs() > max_z);
+        if escaped && no_walls.0 && !blown_up {
+            spawner::recycle_ball(&mut commands, entity, owner.0, transform, &spawn_config);
+        } else if escaped {
+            commands.entity(entity).despawn();
+            counted.clipped += 1;
+            continue;
+        }
+        counted.balls += 1;
+    }
+    if stats.0 != counted {
+        stats.0 = counted;
+    }
+}
+
+fn split_label(partner: PhysicsMode, stats: SplitStats) -> String {
+    format!(
+        "{}  |  Balls: {}  |  Clipped: {}",
+        partner.label(),
+        stats.balls,
+        stats.clipped
+    )
+}
+
+fn update_split_label(
+    stats: Res<PartnerStats>,
+    state: Res<State<PhysicsMode>>,
+    mut query: Query<&mut Text, With<SplitLabel>>,
+) {
+    let partner = state.get().counterpart();
+    for mut text in &mut query {
+        let label = split_label(partner, stats.get(partner));
+        if **text != label {
+            **text = label;
+        }
+    }
+}
+
+/// The four windows have no HUD of their own, so each title bar carries its mode's
+/// ball and clipped counts.
+fn update_window_titles(
+    view: Res<PartnerView>,
+    stats: Res<PartnerStats>,
+    state: Res<State<PhysicsMode>>,
+    mut windows: Query<(&mut Window, &PartnerWindow)>,
+) {
+    let partners = view.partners(*state.get());
+    for (mut window, slot) in &mut windows {
+        let Some(&mode) = partners.get(slot.0) else {
+            continue;
+        };
+        let title = split_label(mode, stats.get(mode));
+        if window.title != title {
+            window.title = title;
+        }
+    }
+}
+
+/// Leaving a mode removes everything split screen added for it; the next mode's
+/// counterpart is set up again by [`layout_split`] and [`maintain_partner_pool`].
+fn despawn_split(
+    mut commands: Commands,
+    partners: Query<Entity, With<SplitPartner>>,
+    mut stats: ResMut<PartnerStats>,
+) {
+    for entity in &partners {
+        commands.entity(entity).despawn();
+    }
+    *stats = PartnerStats::default();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn half_viewports_cover_the_window() {
+        let (left, right) = half_viewports(UVec2::new(1921, 1080));
+        assert_eq!(left.physical_position, UVec2::ZERO);
+        assert_eq!(left.physical_size, UVec2::new(960, 1080));
+        assert_eq!(right.physical_position, UVec2::new(960, 0));
+        assert_eq!(right.physical_size, UVec2::new(961, 1080));
+    }
+
+    #[test]
+    fn partner_view_keys_switch_between_views() {
+        assert_eq!(
+            PartnerView::Off.toggled(PartnerView::Ghost),
+            PartnerView::Ghost
+        );
+        assert_eq!(
+            PartnerView::Ghost.toggled(PartnerView::Split),
+            PartnerView::Split
+        );
+        assert_eq!(
+            PartnerView::Split.toggled(PartnerView::Split),
+            PartnerView::Off
+        );
+        assert!(!PartnerView::Off.is_running());
+    }
+
+    #[test]
+    fn four_windows_run_every_other_mode() {
+        for active in PhysicsMode::ALL {
+            let partners = PartnerView::Windows.partners(active);
+            assert_eq!(partners.len(), 3);
+            assert!(!partners.contains(&active));
+            assert_eq!(PartnerView::Split.partners(active), [active.counterpart()]);
+        }
+        assert!(PartnerView::Off.partners(PhysicsMode::Avian2d).is_empty());
+        // Layer 0 stays the active mode's.
+        assert_eq!(PhysicsMode::ALL.map(partner_layer), [1, 2, 3, 4]);
+    }
+
+    /// The four windows' key handling and lifecycle, without a renderer.
+    fn make_window_test_app() -> App {
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, bevy::state::app::StatesPlugin));
+        app.insert_state(PhysicsMode::Avian2d);
+        app.init_resource::<ButtonInput<KeyCode>>()
+            .init_resource::<KeyBindings>()
+            .init_resource::<PartnerView>()
+            .init_resource::<PartnerRan>()
+            .init_resource::<PoolConfig>();
+        app.add_systems(
+            Update,
+            (
+                handle_partner_view,
+                mark_partner_ran,
+                close_partner_view,
+                manage_partner_windows,
+                layout_partner_windows,
+            )
+                .chain(),
+        );
+        app.world_mut().spawn((Window::default(), PrimaryWindow));
+        app
+    }
+
+    fn count<C: Component>(app: &mut App) -> usize {
+        let world = app.world_mut();
+        world.query_filtered::<(), With<C>>().iter(world).count()
+    }
+
+    fn press_four_windows(app: &mut App) {
+        press(app, KeyBindings::default().four_windows);
+    }
+
+    fn press(app: &mut App, key: KeyCode) {
+        app.world_mut()
+            .resource_mut::<ButtonInput<KeyCode>>()
+            .press(key);
+        app.update();
+        let mut input = app.world_mut().resource_mut::<ButtonInput<KeyCode>>();
+        input.release(key);
+        input.clear();
+    }
+
+    #[test]
+    fn four_windows_open_and_close() {
+        let mut app = make_window_test_app();
+        app.update();
+        assert!(!app.world().resource::<PartnerRan>().0);
+
+        press_four_windows(&mut app);
+        assert_eq!(*app.world().resource::<PartnerView>(), PartnerView::Windows);
+        assert_eq!(count::<PartnerWindow>(&mut app), 3);
+        assert_eq!(count::<WindowCamera>(&mut app), 3);
+        // Avian 3D and Rapier 3D each get a light on their layer.
+        assert_eq!(count::<PointLight>(&mut app), 2);
+
+        press_four_windows(&mut app);
+        assert_eq!(*app.world().resource::<PartnerView>(), PartnerView::Off);
+        assert_eq!(count::<PartnerWindow>(&mut app), 0);
+        assert_eq!(count::<WindowView>(&mut app), 0);
+        // The run stays marked as shared until the next mode starts a new one.
+        assert!(app.world().resource::<PartnerRan>().0);
+
+        // Closing one window by hand takes the other two with it.
+        press_four_windows(&mut app);
+        let world = app.world_mut();
+        let closed = world
+            .query_filtered::<Entity, With<PartnerWindow>>()
+            .iter(world)
+            .next()
+            .unwrap();
+        world.despawn(closed);
+        app.update();
+        assert_eq!(*app.world().resource::<PartnerView>(), PartnerView::Off);
+        assert_eq!(count::<PartnerWindow>(&mut app), 0);
+        assert_eq!(count::<WindowView>(&mut app), 0);
+    }
+
+    #[test]
+    fn ghost_overlay_keeps_the_run_out_of_the_benchmark() {
+        let mut app = make_window_test_app();
+        app.add_systems(OnEnter(PhysicsMode::Rapier2d), restart_partner_ran);
+        let ghost = KeyBindings::default().ghost;
+
+        press(&mut app, ghost);
+        press(&mut app, ghost);
+        assert_eq!(*app.world().resource::<PartnerView>(), PartnerView::Off);
+        assert!(app.world().resource::<PartnerRan>().0);
+
+        // Entering a mode with the overlay off starts a solo run again.
+        app.world_mut()
+            .resource_mut::<NextState<PhysicsMode>>()
+            .set(PhysicsMode::Rapier2d);
+        app.update();
+        assert!(!app.world().resource::<PartnerRan>().0);
+    }
+
+    #[test]
+    fn counterparts_pair_engines_within_a_dimension() {
+        for mode in PhysicsMode::ALL {
+            assert_eq!(mode.counterpart().counterpart(), mode);
+        }
+        assert_eq!(PhysicsMode::Avian3d.counterpart(), PhysicsMode::Rapier3d);
+    }
+}
@@ -0,0 +1,408 @@
+//! Physics stage timing — `F10` shows a panel splitting the active engine's step
+//! time into its stages, heaviest first, so a gap in FPS comes with the part of the
+//! pipeline that causes it:
+//!
+//! ```text
+//! Physics step, Rapier 3D: 6.12 ms
+//!   Solver + integrate   3.40 ms  56 %
+//!   Narrow phase         1.71 ms  28 %
+//!   ...
+//! Process: CPU 131 %, memory 0.41 GiB
+//! ```
+//!
+//! Avian runs its step as system sets in its own schedule, so the stages are timed
+//! by marker systems between the broad phase, narrow phase and solver sets; the
+//! solver set includes integration. Rapier steps in a single call and times its own
+//! stages in the pipeline's `Counters`, which are switched on only while the panel
+//! is shown so the comparison is not skewed by their overhead. Whatever the named
+//! stages don't cover of [`PhysicsStepTime`] is listed as "Other".
+//!
+//! With `--process-stats`, Bevy's `SystemInformationDiagnosticsPlugin` adds the
+//! process CPU and memory use. Its background task polls the OS for as long as the
+//! app runs and has no switch to pause it while the panel is hidden, so unlike the
+//! counters it stays out of ordinary runs.
+
+use bevy::{
+    diagnostic::{DiagnosticsStore, SystemInformationDiagnosticsPlugin},
+    platform::time::Instant,
+    prelude::*,
+};
+
+use crate::backend::{self, PhysicsMode};
+use crate::cli;
+use crate::keys::KeyBindings;
+use crate::step_time::PhysicsStepTime;
+
+// Avian 0.4 (Bevy 0.17) pluralised its system set names (`PhysicsStepSet` → `PhysicsStepSystems`).
+#[cfg(feature = "legacy_state_scoped")]
+use avian2d::prelude::PhysicsStepSet as Avian2dStepSystems;
+#[cfg(not(feature = "legacy_state_scoped"))]
+use avian2d::prelude::PhysicsStepSystems as Avian2dStepSystems;
+#[cfg(feature = "legacy_state_scoped")]
+use avian3d::prelude::PhysicsStepSet as Avian3dStepSystems;
+#[cfg(not(feature = "legacy_state_scoped"))]
+use avian3d::prelude::PhysicsStepSystems as Avian3dStepSystems;
+
+const AVIAN_STAGES: &[&str] = &["Broad phase", "Narrow phase", "Solver + integrate"];
+
+const RAPIER_STAGES: &[&str] = &[
+    "Broad phase",
+    "Narrow phase",
+    "Islands + sleeping",
+    "Solver + integrate",
+    "CCD",
+];
+
+/// Most stages either engine reports.
+const MAX_STAGES: usize = 5;
+
+/// Weight of the newest frame in the smoothed timings.
+const SMOOTHING: f64 = 0.05;
+
+/// Per-stage time of the active engine, in milliseconds.
+#[derive(Resource, Default)]
+struct StageTimes {
+    /// Summed over the steps of the current frame.
+    current: [f64; MAX_STAGES],
+    /// The previous frame's sums, published in `First`.
+    last: [f64; MAX_STAGES],
+    /// Exponential moving average of `last`, and of the whole step.
+    smoothed: [f64; MAX_STAGES],
+    smoothed_step: f64,
+    /// End of the previous Avian stage within the running step.
+    mark: Option<Instant>,
+}
+
+impl StageTimes {
+    /// Adds the time since the last mark to `stage` and moves the mark to now.
+    fn lap(&mut self, stage: usize) {
+        let now = Instant::now();
+        if let Some(mark) = self.mark.replace(now) {
+            self.current[stage] += (now - mark).as_secs_f64() * 1000.0;
+        }
+    }
+}
+
+/// Whether `--process-stats` added the system information plugin.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+struct ProcessStats(bool);
+
+#[derive(Component)]
+struct StagePanel;
+
+#[derive(Component)]
+struct StageText;
+
+pub fn plugin(app: &mut App) {
+    let process_stats = cli::flag("--process-stats");
+    if process_stats {
+        app.add_plugins(SystemInformationDiagnosticsPlugin);
+    }
+    app.insert_resource(ProcessStats(process_stats));
+    app.init_resource::<StageTimes>();
+    app.add_systems(Startup, spawn_stage_panel);
+    app.add_systems(First, publish_stage_times);
+    app.add_systems(
+        Update,
+        (
+            toggle_stage_panel,
+            switch_rapier_counters,
+            update_stage_panel,
+        )
+            .chain(),
+    );
+    app.add_systems(OnExit(PhysicsMode::Avian2d), clear_stage_times);
+    app.add_systems(OnExit(PhysicsMode::Avian3d), clear_stage_times);
+    app.add_systems(OnExit(PhysicsMode::Rapier2d), clear_stage_times);
+    app.add_systems(OnExit(PhysicsMode::Rapier3d), clear_stage_times);
+
+    app.add_systems(
+        avian2d::prelude::PhysicsSchedule,
+        (
+            start_avian_step.before(Avian2dStepSystems::BroadPhase),
+            lap_broad_phase
+                .after(Avian2dStepSystems::BroadPhase)
+                .before(Avian2dStepSystems::NarrowPhase),
+            lap_narrow_phase
+                .after(Avian2dStepSystems::NarrowPhase)
+                .before(Avian2dStepSystems::Solver),
+            lap_solver
+                .after(Avian2dStepSystems::Solver)
+                .before(Avian2dStepSystems::Last),
+        )
+            .run_if(in_state(PhysicsMode::Avian2d).and(panel_shown)),
+    );
+    app.add_systems(
+        avian3d::prelude::PhysicsSchedule,
+        (
+            start_avian_step.before(Avian3dStepSystems::BroadPhase),
+            lap_broad_phase
+                .after(Avian3dStepSystems::BroadPhase)
+                .before(Avian3dStepSystems::NarrowPhase),
+            lap_narrow_phase
+                .after(Avian3dStepSystems::NarrowPhase)
+                .before(Avian3dStepSystems::Solver),
+            lap_solver
+                .after(Avian3dStepSystems::Solver)
+                .before(Avian3dStepSystems::Last),
+        )
+            .run_if(in_state(PhysicsMode::Avian3d).and(panel_shown)),
+    );
+    app.add_systems(
+        backend::rapier_schedule(),
+        (
+            read_rapier2d_counters
+                .after(bevy_rapier2d::plugin::PhysicsSet::StepSimulation)
+                .run_if(in_state(PhysicsMode::Rapier2d)),
+            read_rapier3d_counters
+                .after(bevy_rapier3d::plugin::PhysicsSet::StepSimulation)
+                .run_if(in_state(PhysicsMode::Rapier3d)),
+        )
+            .run_if(panel_shown),
+    );
+}
+
+fn panel_shown(query: Query<&Visibility, With<StagePanel>>) -> bool {
+    query
+        .iter()
+        .any(|visibility| *visibility != Visibility::Hidden)
+}
+
+fn start_avian_step(mut times: ResMut<StageTimes>) {
+    times.mark = Some(Instant::now());
+}
+
+fn lap_broad_phase(mut times: ResMut<StageTimes>) {
+    times.lap(0);
+}
+
+fn lap_narrow_phase(mut times: ResMut<StageTimes>) {
+    times.lap(1);
+}
+
+fn lap_solver(mut times: ResMut<StageTimes>) {
+    times.lap(2);
+    times.mark = None;
+}
+
+/// Rapier resets its counters at the start of every step, so each step's stages are
+/// read right after it.
+fn read_rapier2d_counters(
+    sims: Query<&bevy_rapier2d::plugin::context::RapierContextSimulation>,
+    mut times: ResMut<StageTimes>,
+) {
+    for sim in &sims {
+        let counters = &sim.pipeline.counters;
+        add_stages(
+            &mut times,
+            [
+                counters.broad_phase_time_ms(),
+                counters.narrow_phase_time_ms(),
+                counters.island_construction_time_ms(),
+                counters.solver_time_ms(),
+                counters.ccd_time_ms(),
+            ],
+        );
+    }
+}
+
+fn read_rapier3d_counters(
+    sims: Query<&bevy_rapier3d::plugin::context::RapierContextSimulation>,
+    mut times: ResMut<StageTimes>,
+) {
+    for sim in &sims {
+        let counters = &sim.pipeline.counters;
+        add_stages(
+            &mut times,
+            [
+                counters.broad_phase_time_ms(),
+                counters.narrow_phase_time_ms(),
+                counters.island_construction_time_ms(),
+                counters.solver_time_ms(),
+                counters.ccd_time_ms(),
+            ],
+        );
+    }
+}
+
+fn add_stages(times: &mut StageTimes, stages: [f64; MAX_STAGES]) {
+    for (current, stage) in times.current.iter_mut().zip(stages) {
+        *current += stage;
+    }
+}
+
+/// Turns Rapier's counters on while the panel is shown and off again when hidden.
+fn switch_rapier_counters(
+    query: Query<&Visibility, With<StagePanel>>,
+    mut sims_2d: Query<&mut bevy_rapier2d::plugin::context::RapierContextSimulation>,
+    mut sims_3d: Query<&mut bevy_rapier3d::plugin::context::RapierContextSimulation>,
+) {
+    let shown = panel_shown(query);
+    for mut sim in &mut sims_2d {
+        if sim.pipeline.counters.enabled() != shown {
+            let counters = &mut sim.pipeline.counters;
+            if shown {
+                counters.enable()
+            } else {
+                counters.disable()
+            }
+        }
+    }
+    for mut sim in &mut sims_3d {
+        if sim.pipeline.counters.enabled() != shown {
+            let counters = &mut sim.pipeline.counters;
+            if shown {
+                counters.enable()
+            } else {
+                counters.disable()
+            }
+        }
+    }
+}
+
+fn publish_stage_times(mut times: ResMut<StageTimes>) {
+    times.last = std::mem::take(&mut times.current);
+}
+
+/// Starts the next mode's averages afresh.
+fn clear_stage_times(mut times: ResMut<StageTimes>) {
+    *times = StageTimes::default();
+}
+
+fn spawn_stage_panel(mut commands: Commands) {
+    commands
+        .spawn((
+            Name::new("Stage Timing Panel"),
+            StagePanel,
+            Node {
+                position_type: PositionType::Absolute,
+                bottom: Val::Px(40.0),
+                right: Val::Px(10.0),
+                padding: UiRect::all(Val::Px(8.0)),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(0.0, 0.0, 0.0, 0.6)),
+            Visibility::Hidden,
+        ))
+        .with_children(|panel| {
+            panel.spawn((
+                Name::new("Stage Timing Table"),
+                StageText,
+                Node::default(),
+                Text::new(""),
+                TextFont {
+                    font_size: 16.0,
+                    ..default()
+                },
+                TextColor(Color::WHITE),
+            ));
+        });
+}
+
+/// `F10` shows or hides the stage timing panel.
+fn toggle_stage_panel(
+    input: Res<ButtonInput<KeyCode>>,
+    bindings: Res<KeyBindings>,
+    mut query: Query<&mut Visibility, With<StagePanel>>,
+) {
+    if !input.just_pressed(bindings.stage_timing) {
+        return;
+    }
+
+    for mut visibility in &mut query {
+        *visibility = match *visibility {
+            Visibility::Hidden => Visibility::Inherited,
+            _ => Visibility::Hidden,
+        };
+    }
+}
+
+/// The table: the step total, then each stage and "Other" with its share of the
+/// step, heaviest first.
+fn stage_table(mode: PhysicsMode, step_ms: f64, names: &[&str], stages: &[f64]) -> String {
+    let covered: f64 = stages.iter().sum();
+    let mut rows: Vec<(&str, f64)> = names.iter().copied().zip(stages.iter().copied()).collect();
+    rows.push(("Other", (step_ms - covered).max(0.0)));
+    rows.sort_by(|(_, a), (_, b)| b.total_cmp(a));
+
+    let mut table = format!("Physics step, {}: {step_ms:.2} ms", mode.label());
+    for (name, ms) in rows {
+        let share = if step_ms > 0.0 {
+            ms / step_ms * 100.0
+        } else {
+            0.0
+        };
+        table.push_str(&format!("\n  {name:<20} {ms:>6.2} ms {share:>3.0} %"));
+    }
+    table
+}
+
+fn update_stage_panel(
+    mut times: ResMut<StageTimes>,
+    step_time: Res<PhysicsStepTime>,
+    state: Res<State<PhysicsMode>>,
+    diagnostics: Res<DiagnosticsStore>,
+    process_stats: Res<ProcessStats>,
+    panels: Query<&Visibility, With<StagePanel>>,
+    mut query: Query<&mut Text, With<StageText>>,
+) {
+    if !panel_shown(panels) {
+        return;
+    }
+
+    let last = times.last;
+    let step = step_time.last.as_secs_f64() * 1000.0;
+    let times = &mut *times;
+    for (smoothed, last) in times.smoothed.iter_mut().zip(last) {
+        *smoothed += SMOOTHING * (last - *smoothed);
+    }
+    times.smoothed_step += SMOOTHING * (step - times.smoothed_step);
+
+    let mode = *state.get();
+    let names = match mode {
+        PhysicsMode::Avian2d | PhysicsMode::Avian3d => AVIAN_STAGES,
+        PhysicsMode::Rapier2d | PhysicsMode::Rapier3d => RAPIER_STAGES,
+    };
+    let mut table = stage_table(
+        mode,
+        times.smoothed_step,
+        names,
+        &times.smoothed[..names.len()],
+    );
+    let value = |path| {
+        diagnostics
+            .get(path)
+            .and_then(|diagnostic| diagnostic.smoothed())
+    };
+    if !process_stats.0 {
+        table.push_str("\nProcess: off (--process-stats)");
+    } else if let (Some(cpu), Some(memory)) = (
+        value(&SystemInformationDiagnosticsPlugin::PROCESS_CPU_USAGE),
+        value(&SystemInformationDiagnosticsPlugin::PROCESS_MEM_USAGE),
+    ) {
+        table.push_str(&format!(
+            "\nProcess: CPU {cpu:.0} %, memory {memory:.2} GiB"
+        ));
+    }
+    for mut text in &mut query {
+        **text = table.clone();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stage_table_sorts_stages_and_adds_the_remainder() {
+        let table = stage_table(PhysicsMode::Avian2d, 4.0, AVIAN_STAGES, &[0.4, 1.0, 2.0]);
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(lines[0], "Physics step, Avian 2D: 4.00 ms");
+        assert!(lines[1].starts_with("  Solver + integrate"));
+        assert!(lines[1].ends_with("2.00 ms  50 %"));
+        assert!(lines[2].starts_with("  Narrow phase"));
+        assert!(lines[3].starts_with("  Other"));
+        assert!(lines[3].ends_with("0.60 ms  15 %"));
+        assert!(lines[4].starts_with("  Broad phase"));
+    }
+}
@@ -0,0 +1,82 @@
+//! Physics-step timing — wall-clock time spent inside the active engine's
+//! simulation step, separate from frame time (render cost is the same for every
+//! backend, so this is the number that actually differs between engines).
+//!
+//! Timing systems bracket each engine's `StepSimulation` set: Avian steps in
+//! `FixedPostUpdate` (possibly several times per frame), Rapier in `PostUpdate`
+//! (or `FixedUpdate` under `--fixed-dt`).
+//! Each frame's steps are summed and published in `First` of the next frame.
+
+use bevy::{platform::time::Instant, prelude::*};
+use std::time::Duration;
+
+use crate::backend::{self, PhysicsMode};
+
+// Avian 0.4 (Bevy 0.17) pluralised its system set names (`PhysicsSet` → `PhysicsSystems`).
+#[cfg(feature = "legacy_state_scoped")]
+use avian2d::prelude::PhysicsSet as Avian2dPhysicsSystems;
+#[cfg(not(feature = "legacy_state_scoped"))]
+use avian2d::prelude::PhysicsSystems as Avian2dPhysicsSystems;
+#[cfg(feature = "legacy_state_scoped")]
+use avian3d::prelude::PhysicsSet as Avian3dPhysicsSystems;
+#[cfg(not(feature = "legacy_state_scoped"))]
+use avian3d::prelude::PhysicsSystems as Avian3dPhysicsSystems;
+
+/// Time the active engine spent stepping during the previous frame.
+#[derive(Resource, Default)]
+pub struct PhysicsStepTime {
+    pub last: Duration,
+    current: Duration,
+    started: Option<Instant>,
+}
+
+pub fn plugin(app: &mut App) {
+    app.init_resource::<PhysicsStepTime>();
+    app.add_systems(First, publish_step_time);
+    app.add_systems(
+        FixedPostUpdate,
+        (
+            start_step.before(Avian2dPhysicsSystems::StepSimulation),
+            stop_step.after(Avian2dPhysicsSystems::StepSimulation),
+        )
+            .run_if(in_state(PhysicsMode::Avian2d)),
+    );
+    app.add_systems(
+        FixedPostUpdate,
+        (
+            start_step.before(Avian3dPhysicsSystems::StepSimulation),
+            stop_step.after(Avian3dPhysicsSystems::StepSimulation),
+        )
+            .run_if(in_state(PhysicsMode::Avian3d)),
+    );
+    app.add_systems(
+        backend::rapier_schedule(),
+        (
+            start_step.before(bevy_rapier2d::plugin::PhysicsSet::StepSimulation),
+            stop_step.after(bevy_rapier2d::plugin::PhysicsSet::StepSimulation),
+        )
+            .run_if(in_state(PhysicsMode::Rapier2d)),
+    );
+    app.add_systems(
+        backend::rapier_schedule(),
+        (
+            start_step.before(bevy_rapier3d::plugin::PhysicsSet::StepSimulation),
+            stop_step.after(bevy_rapier3d::plugin::PhysicsSet::StepSimulation),
+        )
+            .run_if(in_state(PhysicsMode::Rapier3d)),
+    );
+}
+
+fn start_step(mut step_time: ResMut<PhysicsStepTime>) {
+    step_time.started = Some(Instant::now());
+}
+
+fn stop_step(mut step_time: ResMut<PhysicsStepTime>) {
+    if let Some(started) = step_time.started.take() {
+        step_time.current += started.elapsed();
+    }
+}
+
+fn publish_step_time(mut step_time: ResMut<PhysicsStepTime>) {
+    step_time.last = std::mem::take(&mut step_time.current);
+}
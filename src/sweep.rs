@@ -0,0 +1,372 @@
+//! Scripted restitution/friction/density sweep — `--sweep` runs every step in a
+//! [`SweepPlan`] in the current mode and appends one CSV row per step.
+//!
+//! Each step clears the balls, rebuilds the walls with the step's [`Surface`],
+//! sets the step's [`BallDensity`], refills to a fixed ball count with the normal
+//! spawner, then lets the pile settle for a fixed real-time period. The row
+//! records the average FPS over that period and the deepest ball–ball overlap at
+//! its end.
+//!
+//! The file path is read from the `AVR_SWEEP_CSV` environment variable
+//! (default `sweep.csv`). Once the last step is recorded the simulation pauses.
+
+use bevy::prelude::*;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Write;
+use std::time::Duration;
+
+use crate::backend::{BallAssets, PhysicsMode, Surface};
+use crate::spawner::{
+    self, Ball, BallCount, BallDensity, BallFriction, BallRestitution, MaxBalls, SpawnIndex,
+    SpawnRng,
+};
+use crate::walls::{Wall, WallConfig};
+use crate::{benchmark, cli};
+
+/// Environment variable that overrides the sweep CSV output path.
+const CSV_PATH_ENV: &str = "AVR_SWEEP_CSV";
+const DEFAULT_CSV_PATH: &str = "sweep.csv";
+
+const CSV_HEADER: &str =
+    "mode,restitution,friction,density,balls,settle_secs,avg_fps,max_penetration";
+
+/// Restitution and friction values combined into the default plan's grid.
+const DEFAULT_RESTITUTIONS: [f32; 3] = [0.0, 0.5, 0.9];
+const DEFAULT_FRICTIONS: [f32; 3] = [0.0, 0.5, 1.0];
+
+/// Default ball count per step (`--sweep-balls`).
+const DEFAULT_BALLS: usize = 2000;
+
+/// Default settle time per step in seconds (`--sweep-settle-secs`).
+const DEFAULT_SETTLE_SECS: f32 = 10.0;
+
+/// One measured run of the sweep.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SweepStep {
+    pub restitution: f32,
+    pub friction: f32,
+    pub density: f32,
+}
+
+/// The steps to run, in order, and how each is measured.
+#[derive(Resource, Debug, Clone, PartialEq)]
+pub struct SweepPlan {
+    pub steps: Vec<SweepStep>,
+    pub balls: usize,
+    pub settle: Duration,
+}
+
+impl SweepPlan {
+    /// Runs every `(restitution, friction)` pair once at each of `densities`,
+    /// densities in the outer loop so rows for one density stay together.
+    fn cross(pairs: &[(f32, f32)], densities: &[f32]) -> Vec<SweepStep> {
+        densities
+            .iter()
+            .flat_map(|&density| {
+                pairs.iter().map(move |&(restitution, friction)| SweepStep {
+                    restitution,
+                    friction,
+                    density,
+                })
+            })
+            .collect()
+    }
+}
+
+impl Default for SweepPlan {
+    fn default() -> Self {
+        let pairs: Vec<(f32, f32)> = DEFAULT_RESTITUTIONS
+            .iter()
+            .flat_map(|&r| DEFAULT_FRICTIONS.iter().map(move |&f| (r, f)))
+            .collect();
+        Self {
+            steps: Self::cross(&pairs, &[BallDensity::default().0]),
+            balls: DEFAULT_BALLS,
+            settle: Duration::from_secs_f32(DEFAULT_SETTLE_SECS),
+        }
+    }
+}
+
+/// Progress through the [`SweepPlan`]. Removed once the last step is recorded.
+#[derive(Resource, Default)]
+struct SweepRun {
+    index: usize,
+    phase: SweepPhase,
+}
+
+#[derive(Default)]
+enum SweepPhase {
+    /// The step's surface has not been applied yet.
+    #[default]
+    Setup,
+    /// Waiting for the spawner to reach [`SweepPlan::balls`].
+    Filling,
+    /// Counting frames until the settle period ends.
+    Settling { timer: Timer, frames: u32 },
+}
+
+/// Open handle to the sweep CSV. Only inserted if the file could be opened.
+#[derive(Resource)]
+struct SweepLog {
+    file: File,
+}
+
+pub fn plugin(app: &mut App) {
+    if !cli::flag("--sweep") {
+        return;
+    }
+
+    let mut plan = SweepPlan::default();
+    let mut pairs: Vec<(f32, f32)> = plan
+        .steps
+        .iter()
+        .map(|step| (step.restitution, step.friction))
+        .collect();
+    if let Some(raw) = cli::value("--sweep-plan") {
+        match parse_list(&raw, |pair| {
+            let (r, f) = pair.split_once(':')?;
+            Some((r.trim().parse().ok()?, f.trim().parse().ok()?))
+        }) {
+            Some(parsed) => pairs = parsed,
+            None => warn!("Ignoring invalid --sweep-plan {raw:?}; expected e.g. 0.2:0.5,0.8:0.1"),
+        }
+    }
+    let mut densities = vec![cli::parsed("--density").unwrap_or(BallDensity::default().0)];
+    if let Some(raw) = cli::value("--sweep-densities") {
+        match parse_list(&raw, |density| density.trim().parse().ok()) {
+            Some(parsed) => densities = parsed,
+            None => warn!("Ignoring invalid --sweep-densities {raw:?}; expected e.g. 0.5,1,4"),
+        }
+    }
+    plan.steps = SweepPlan::cross(&pairs, &densities);
+    if let Some(balls) = cli::parsed("--sweep-balls") {
+        plan.balls = balls;
+    }
+    if let Some(secs) = cli::parsed::<f32>("--sweep-settle-secs") {
+        plan.settle = Duration::from_secs_f32(secs.max(0.0));
+    }
+    info!(
+        "Sweep: {} steps, {} balls, {:.1} s settle",
+        plan.steps.len(),
+        plan.balls,
+        plan.settle.as_secs_f32()
+    );
+
+    app.insert_resource(MaxBalls(plan.balls));
+    app.insert_resource(plan);
+    app.init_resource::<SweepRun>();
+    app.add_systems(Startup, open_sweep_log);
+    // After the density push, so a step's new density reaches its refilled balls
+    // next frame instead of the balls this step just despawned.
+    app.add_systems(
+        Update,
+        run_sweep
+            .after(spawner::apply_ball_density)
+            .run_if(resource_exists::<SweepRun>),
+    );
+}
+
+fn open_sweep_log(mut commands: Commands) {
+    if let Some(file) = benchmark::open_csv(CSV_PATH_ENV, DEFAULT_CSV_PATH, CSV_HEADER) {
+        commands.insert_resource(SweepLog { file });
+    }
+}
+
+/// Parses a comma-separated list with `item`, rejecting it if any entry fails.
+fn parse_list<T>(raw: &str, item: impl Fn(&str) -> Option<T>) -> Option<Vec<T>> {
+    raw.split(',').map(item).collect()
+}
+
+/// Advances the sweep by one phase per frame at most: applies the next step,
+/// waits for the refill, measures the settle period, then logs the row.
+fn run_sweep(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    time: Res<Time<Real>>,
+    mut vtime: ResMut<Time<Virtual>>,
+    state: Res<State<PhysicsMode>>,
+    plan: Res<SweepPlan>,
+    mut run: ResMut<SweepRun>,
+    (mut restitution, mut friction, mut density): (
+        ResMut<BallRestitution>,
+        ResMut<BallFriction>,
+        ResMut<BallDensity>,
+    ),
+    mut ball_count: ResMut<BallCount>,
+    (mut rng, mut index): (ResMut<SpawnRng>, ResMut<SpawnIndex>),
+    wall_config: WallConfig,
+    ball_assets: Res<BallAssets>,
+    balls: Query<(Entity, &Transform), With<Ball>>,
+    wall_entities: Query<Entity, With<Wall>>,
+    log: Option<ResMut<SweepLog>>,
+) {
+    let Some(&step) = plan.steps.get(run.index) else {
+        info!("Sweep finished");
+        vtime.pause();
+        commands.remove_resource::<SweepRun>();
+        return;
+    };
+
+    match &mut run.phase {
+        SweepPhase::Setup => {
+            restitution.0 = step.restitution;
+            friction.0 = step.friction;
+            density.set_if_neq(BallDensity(step.density));
+            for (ball, _) in &balls {
+                commands.entity(ball).despawn();
+            }
+            ball_count.0 = 0;
+            rng.reset();
+            index.0 = 0;
+            for wall in &wall_entities {
+                commands.entity(wall).despawn();
+            }
+            wall_config.spawn(
+                &mut commands,
+                &mut meshes,
+                &mut materials,
+                *state.get(),
+                Surface {
+                    restitution: step.restitution,
+                    friction: step.friction,
+                },
+            );
+            vtime.unpause();
+            run.phase = SweepPhase::Filling;
+        }
+        SweepPhase::Filling => {
+            if ball_count.0 >= plan.balls {
+                run.phase = SweepPhase::Settling {
+                    timer: Timer::new(plan.settle, TimerMode::Once),
+                    frames: 0,
+                };
+            }
+        }
+        SweepPhase::Settling { timer, frames } => {
+            timer.tick(time.delta());
+            *frames += 1;
+            if timer.elapsed() < timer.duration() {
+                return;
+            }
+
+            let settle_secs = timer.elapsed_secs();
+            let avg_fps = if settle_secs > 0.0 {
+                *frames as f32 / settle_secs
+            } else {
+                0.0
+            };
+            // Balls are scaled from the shared mesh, so the scale gives each radius.
+            let circles: Vec<(Vec3, f32)> = balls
+                .iter()
+                .map(|(_, t)| (t.translation, t.scale.x * ball_assets.radius))
+                .collect();
+            let penetration = max_overlap(&circles);
+            let row = format!(
+                "{},{},{},{},{},{settle_secs:.1},{avg_fps:.1},{penetration:.3}",
+                state.get().label(),
+                step.restitution,
+                step.friction,
+                step.density,
+                ball_count.0,
+            );
+            info!("Sweep {}/{}: {row}", run.index + 1, plan.steps.len());
+            if let Some(mut log) = log
+                && let Err(err) = writeln!(log.file, "{row}")
+            {
+                warn!("Failed to write sweep log row: {err}");
+            }
+
+            run.index += 1;
+            run.phase = SweepPhase::Setup;
+        }
+    }
+}
+
+/// Deepest overlap between any two balls, each given as a centre and radius and
+/// treated as a circle (2D) or sphere (3D); box and capsule shapes are
+/// approximated by it too. Neighbours are found through a uniform grid whose
+/// cells are one diameter of the largest ball.
+fn max_overlap(circles: &[(Vec3, f32)]) -> f32 {
+    let cell = 2.0 * circles.iter().map(|&(_, r)| r).fold(0.0, f32::max);
+    if cell <= 0.0 {
+        return 0.0;
+    }
+    let cell_of = |p: Vec3| (p / cell).floor().as_ivec3();
+    let mut grid: HashMap<IVec3, Vec<usize>> = HashMap::new();
+    for (i, &(p, _)) in circles.iter().enumerate() {
+        grid.entry(cell_of(p)).or_default().push(i);
+    }
+
+    let mut deepest = 0.0_f32;
+    for (i, &(p, r)) in circles.iter().enumerate() {
+        let cell = cell_of(p);
+        for dx in -1..=1 {
+            for dy in -1..=1 {
+                for dz in -1..=1 {
+                    let Some(others) = grid.get(&(cell + IVec3::new(dx, dy, dz))) else {
+                        continue;
+                    };
+                    for &j in others.iter().filter(|&&j| j > i) {
+                        let (q, s) = circles[j];
+                        deepest = deepest.max(r + s - p.distance(q));
+                    }
+                }
+            }
+        }
+    }
+    deepest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_list_accepts_list_and_rejects_garbage() {
+        let densities = |raw| parse_list(raw, |d: &str| d.trim().parse::<f32>().ok());
+        assert_eq!(densities("0.5, 1,4"), Some(vec![0.5, 1.0, 4.0]));
+        assert_eq!(densities("0.5,x"), None);
+        assert_eq!(densities(""), None);
+    }
+
+    #[test]
+    fn cross_runs_every_pair_at_every_density() {
+        let steps = SweepPlan::cross(&[(0.2, 0.5), (0.8, 0.1)], &[1.0, 4.0]);
+        assert_eq!(steps.len(), 4);
+        assert_eq!(
+            steps[1],
+            SweepStep {
+                restitution: 0.8,
+                friction: 0.1,
+                density: 1.0,
+            }
+        );
+        assert!(steps[2..].iter().all(|step| step.density == 4.0));
+    }
+
+    #[test]
+    fn max_overlap_finds_deepest_pair() {
+        let circles = [
+            (Vec3::new(0.0, 0.0, 0.0), 6.0),
+            (Vec3::new(11.0, 0.0, 0.0), 6.0),
+            (Vec3::new(100.0, 0.0, 0.0), 6.0),
+            (Vec3::new(100.0, 9.0, 0.0), 6.0),
+        ];
+        assert!((max_overlap(&circles) - 3.0).abs() < 1e-4);
+        assert_eq!(max_overlap(&circles[..1]), 0.0);
+    }
+
+    #[test]
+    fn max_overlap_uses_each_balls_own_radius() {
+        // A large and a small ball 20 apart overlap by 4; at a shared radius of
+        // 6 they would not touch at all.
+        let circles = [
+            (Vec3::new(0.0, 0.0, 0.0), 18.0),
+            (Vec3::new(20.0, 0.0, 0.0), 6.0),
+            (Vec3::new(200.0, 0.0, 0.0), 6.0),
+        ];
+        assert!((max_overlap(&circles) - 4.0).abs() < 1e-4);
+    }
+}
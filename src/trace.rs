@@ -0,0 +1,118 @@
+//! FPS trace — `--trace <path>` appends a row to a CSV every [`SAMPLE_INTERVAL`] of
+//! real time while the simulation runs, for plotting the full FPS-vs-ball-count
+//! curve in a spreadsheet instead of reading it off the live graph:
+//!
+//! ```text
+//! elapsed_s,mode,ball_count,fps,avg_fps,contacts
+//! 12.400,Avian 2D,2480,58.9,59.6,6120
+//! ```
+//!
+//! Samples follow `Time<Real>`, so they stay evenly spaced when a slow simulation
+//! drags the frame rate down, and skip while the simulation is paused.
+
+use bevy::diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin};
+use bevy::prelude::*;
+use std::fs::File;
+use std::io::Write;
+use std::time::Duration;
+
+use crate::backend::{ContactCount, PhysicsMode};
+use crate::benchmark;
+use crate::cli;
+use crate::spawner::BallCount;
+
+/// Real time between samples.
+const SAMPLE_INTERVAL: Duration = Duration::from_millis(100);
+
+const CSV_HEADER: &str = "elapsed_s,mode,ball_count,fps,avg_fps,contacts";
+
+/// Open trace file and the sampling timer, from `--trace`.
+#[derive(Resource)]
+struct Trace {
+    file: File,
+    timer: Timer,
+}
+
+pub fn plugin(app: &mut App) {
+    let Some(path) = cli::value("--trace") else {
+        return;
+    };
+    let Some(file) = benchmark::open_csv_at(&path, CSV_HEADER) else {
+        return;
+    };
+    info!("Tracing FPS to {path}");
+    app.insert_resource(Trace {
+        file,
+        timer: Timer::new(SAMPLE_INTERVAL, TimerMode::Repeating),
+    });
+    app.add_systems(Update, sample_trace);
+}
+
+/// One sample. FPS values the diagnostics don't have yet are left empty.
+fn trace_row(
+    elapsed: Duration,
+    mode: PhysicsMode,
+    balls: usize,
+    fps: Option<f64>,
+    avg_fps: Option<f64>,
+    contacts: usize,
+) -> String {
+    let cell = |fps: Option<f64>| fps.map_or_else(String::new, |fps| format!("{fps:.1}"));
+    format!(
+        "{:.3},{},{balls},{},{},{contacts}",
+        elapsed.as_secs_f64(),
+        mode.label(),
+        cell(fps),
+        cell(avg_fps),
+    )
+}
+
+fn sample_trace(
+    real: Res<Time<Real>>,
+    vtime: Res<Time<Virtual>>,
+    diagnostics: Res<DiagnosticsStore>,
+    state: Res<State<PhysicsMode>>,
+    ball_count: Res<BallCount>,
+    contacts: Res<ContactCount>,
+    mut trace: ResMut<Trace>,
+) {
+    if vtime.is_paused() {
+        return;
+    }
+    trace.timer.tick(real.delta());
+    if !trace.timer.just_finished() {
+        return;
+    }
+
+    let diag = diagnostics.get(&FrameTimeDiagnosticsPlugin::FPS);
+    let row = trace_row(
+        real.elapsed(),
+        *state.get(),
+        ball_count.0,
+        diag.and_then(|d| d.value()),
+        diag.and_then(|d| d.average()),
+        contacts.0,
+    );
+    if let Err(err) = writeln!(trace.file, "{row}") {
+        warn!("Failed to write trace row: {err}");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trace_row_matches_header() {
+        let row = trace_row(
+            Duration::from_millis(12_400),
+            PhysicsMode::Avian2d,
+            2480,
+            Some(58.94),
+            None,
+            6120,
+        );
+        assert_eq!(row, "12.400,Avian 2D,2480,58.9,,6120");
+        assert_eq!(row.split(',').count(), CSV_HEADER.split(',').count());
+    }
+}
@@ -0,0 +1,91 @@
+//! Motion trails — every [`TRACK_EVERY`]th spawned ball (at most [`MAX_TRACKED`] at a
+//! time) is tagged [`TrackedBall`] and its recent positions are drawn as a gizmo
+//! line, so differences in how each engine integrates motion are visible frame to frame.
+
+use bevy::prelude::*;
+use std::collections::VecDeque;
+
+use crate::backend::PhysicsMode;
+use crate::spawner::Ball;
+
+/// Every Nth spawned ball is tracked.
+const TRACK_EVERY: usize = 50;
+
+/// Upper bound on simultaneously tracked balls, to keep gizmo drawing cheap.
+const MAX_TRACKED: usize = 20;
+
+/// Number of positions kept per trail (one per frame).
+const TRAIL_LENGTH: usize = 60;
+
+const TRAIL_COLOR: Color = Color::srgb(1.0, 0.85, 0.2);
+
+/// Marks a ball whose recent positions are recorded in its ring buffer.
+#[derive(Component, Default)]
+pub struct TrackedBall {
+    points: VecDeque<Vec3>,
+}
+
+impl TrackedBall {
+    /// Appends `point`, dropping the oldest once [`TRAIL_LENGTH`] are stored.
+    fn push(&mut self, point: Vec3) {
+        if self.points.len() == TRAIL_LENGTH {
+            self.points.pop_front();
+        }
+        self.points.push_back(point);
+    }
+}
+
+pub fn plugin(app: &mut App) {
+    app.add_systems(Update, (tag_tracked_balls, draw_trails).chain());
+}
+
+/// Tags every [`TRACK_EVERY`]th new ball while fewer than [`MAX_TRACKED`] are tracked.
+/// Despawned tracked balls free their slot for a later ball.
+fn tag_tracked_balls(
+    mut commands: Commands,
+    new_balls: Query<Entity, Added<Ball>>,
+    tracked: Query<(), With<TrackedBall>>,
+    mut spawned: Local<usize>,
+) {
+    let mut tracked_count = tracked.iter().count();
+    for ball in &new_balls {
+        *spawned += 1;
+        if *spawned % TRACK_EVERY == 0 && tracked_count < MAX_TRACKED {
+            commands.entity(ball).insert(TrackedBall::default());
+            tracked_count += 1;
+        }
+    }
+}
+
+/// Records each tracked ball's position and draws its trail, in the XY plane
+/// for 2D modes and in world space for 3D modes.
+fn draw_trails(
+    state: Res<State<PhysicsMode>>,
+    mut gizmos: Gizmos,
+    mut tracked: Query<(&Transform, &mut TrackedBall)>,
+) {
+    let is_3d = matches!(state.get(), PhysicsMode::Avian3d | PhysicsMode::Rapier3d);
+    for (transform, mut trail) in &mut tracked {
+        trail.push(transform.translation);
+        if is_3d {
+            gizmos.linestrip(trail.points.iter().copied(), TRAIL_COLOR);
+        } else {
+            gizmos.linestrip_2d(trail.points.iter().map(|p| p.truncate()), TRAIL_COLOR);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn trail_keeps_only_the_latest_points() {
+        let mut trail = TrackedBall::default();
+        for i in 0..TRAIL_LENGTH + 5 {
+            trail.push(Vec3::splat(i as f32));
+        }
+        assert_eq!(trail.points.len(), TRAIL_LENGTH);
+        assert_eq!(trail.points.front(), Some(&Vec3::splat(5.0)));
+    }
+}
@@ -0,0 +1,26 @@
+//! Versions of Bevy and the physics crates this binary was built with, captured
+//! from `Cargo.lock` by `build.rs`. Shown in the HUD (toggle `I`) so screenshots
+//! and copied numbers record exactly which engine versions produced them.
+
+/// Version string set by `build.rs`, or `unknown` when built without it.
+macro_rules! captured {
+    ($var:literal) => {
+        match option_env!($var) {
+            Some(version) => version,
+            None => "unknown",
+        }
+    };
+}
+
+pub const BEVY: &str = captured!("AVR_VERSION_BEVY");
+pub const AVIAN2D: &str = captured!("AVR_VERSION_AVIAN2D");
+pub const AVIAN3D: &str = captured!("AVR_VERSION_AVIAN3D");
+pub const RAPIER2D: &str = captured!("AVR_VERSION_RAPIER2D");
+pub const RAPIER3D: &str = captured!("AVR_VERSION_RAPIER3D");
+
+/// One-line summary for the HUD.
+pub fn summary() -> String {
+    format!(
+        "bevy {BEVY}  |  avian2d {AVIAN2D}  |  avian3d {AVIAN3D}  |  bevy_rapier2d {RAPIER2D}  |  bevy_rapier3d {RAPIER3D}"
+    )
+}
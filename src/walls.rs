@@ -1,46 +1,339 @@
 //! Floor, side walls, and (for 3D) front/back walls.
-//! No top wall so balls can drop in.
+//! No top wall by default so balls can drop in; `T` seals the pool with one.
 
-use bevy::prelude::*;
+use bevy::{ecs::system::SystemParam, prelude::*};
 
-use crate::backend::{self, POOL_DEPTH, PhysicsMode};
+use crate::backend::{self, LengthUnit, POOL_DEPTH, PhysicsMode, Surface};
+use crate::cli;
+use crate::obstacles::ObstacleLayout;
 
-const WIDTH: f32 = 1920.0;
-const HEIGHT: f32 = 1080.0;
+/// Default pool width, overridden with `--width`.
+pub const WIDTH: f32 = 1920.0;
+/// Default pool height, overridden with `--height`.
+pub const HEIGHT: f32 = 1080.0;
+/// Default wall and floor thickness, overridden with `--thickness`.
 const WALL_THICKNESS: f32 = 10.0;
+/// Neutral wall colour, used by every mode with `--mono-walls`.
+const WALL_COLOR: Color = Color::srgb(0.4, 0.4, 0.4);
+/// Tint of the 3D front wall's glass with `--mono-walls`.
+const GLASS_COLOR: Color = Color::srgba(0.5, 0.7, 1.0, 0.15);
+
+/// Whether every mode gets the neutral gray frame (`--mono-walls`). Otherwise the
+/// walls take the hue of the mode's balls, so a screenshot's frame names the backend.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MonoWalls(pub bool);
+
+impl MonoWalls {
+    pub fn from_args() -> Self {
+        Self(cli::flag("--mono-walls"))
+    }
+
+    /// Opaque wall colour for `mode`: its ball hue, muted so the balls stand out.
+    fn wall_color(self, mode: PhysicsMode) -> Color {
+        if self.0 {
+            return WALL_COLOR;
+        }
+        Color::hsl(Hsla::from(mode.color()).hue, 0.35, 0.4)
+    }
+
+    /// [`GLASS_COLOR`] hue-shifted to `mode`'s ball hue, keeping its lightness and alpha.
+    fn glass_color(self, mode: PhysicsMode) -> Color {
+        if self.0 {
+            return GLASS_COLOR;
+        }
+        Hsla::from(GLASS_COLOR)
+            .with_hue(Hsla::from(mode.color()).hue)
+            .into()
+    }
+}
+
+/// Width of the central opening in the floor while the floor drain is open.
+const DRAIN_WIDTH: f32 = 200.0;
+
+/// Pool dimensions in pixels, from `--width`, `--height`, `--depth` (3D only) and
+/// `--thickness`; the defaults give a 1920 × 1080 × 1920 pool with 10 px walls. The
+/// pool is centered on the origin, so the floor's underside is at `-height / 2`. The
+/// spawner's drop range and the camera fit are derived from it.
+///
+/// Sizes are given at the default [`LengthUnit`] and scaled with `--length-unit`, so
+/// the pool keeps its size in meters. `scale` is that factor; pixel lengths tied to
+/// the pool, like the drain opening and the spawn and clip margins, are multiplied
+/// by it too.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct PoolConfig {
+    pub width: f32,
+    pub height: f32,
+    pub depth: f32,
+    pub thickness: f32,
+    pub scale: f32,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self {
+            width: WIDTH,
+            height: HEIGHT,
+            depth: POOL_DEPTH,
+            thickness: WALL_THICKNESS,
+            scale: 1.0,
+        }
+    }
+}
+
+impl PoolConfig {
+    /// Smallest accepted width, height or depth: room for the drain and the spawn margins.
+    pub const MIN_EXTENT: f32 = 400.0;
+    /// Thickest accepted wall.
+    pub const MAX_THICKNESS: f32 = 100.0;
+
+    /// Sizes from the command line, scaled for `unit`.
+    pub fn from_args(unit: LengthUnit) -> Self {
+        let default = Self::default();
+        let extent = |name: &str, default: f32| {
+            cli::parsed::<f32>(name)
+                .filter(|&value| {
+                    let valid = value >= Self::MIN_EXTENT && value.is_finite();
+                    if !valid {
+                        warn!(
+                            "Ignoring {name} {value}: must be at least {}",
+                            Self::MIN_EXTENT
+                        );
+                    }
+                    valid
+                })
+                .unwrap_or(default)
+        };
+        let thickness = cli::parsed::<f32>("--thickness")
+            .filter(|&value| {
+                let valid = value > 0.0 && value <= Self::MAX_THICKNESS;
+                if !valid {
+                    warn!(
+                        "Ignoring --thickness {value}: must be above 0 and at most {}",
+                        Self::MAX_THICKNESS
+                    );
+                }
+                valid
+            })
+            .unwrap_or(default.thickness);
+        Self {
+            width: extent("--width", default.width),
+            height: extent("--height", default.height),
+            depth: extent("--depth", default.depth),
+            thickness,
+            scale: 1.0,
+        }
+        .scaled(unit.scale())
+    }
+
+    /// Every dimension multiplied by `factor`.
+    fn scaled(self, factor: f32) -> Self {
+        Self {
+            width: self.width * factor,
+            height: self.height * factor,
+            depth: self.depth * factor,
+            thickness: self.thickness * factor,
+            scale: self.scale * factor,
+        }
+    }
+
+    /// In 3D, walls that run front-to-back fit between the front and back walls
+    /// (no corner overlap), so they use the interior depth.
+    fn side_depth(self, is_3d: bool) -> f32 {
+        if is_3d {
+            self.depth - 2.0 * self.thickness
+        } else {
+            self.thickness
+        }
+    }
+
+    /// In 3D the floor and top wall are trimmed in X to fit inside the side walls as well.
+    fn floor_width(self, is_3d: bool) -> f32 {
+        if is_3d {
+            self.width - 2.0 * self.thickness
+        } else {
+            self.width
+        }
+    }
+
+    /// Underside of the top wall; click spawns in a sealed pool stay below it.
+    pub fn lid_bottom(self) -> f32 {
+        self.height / 2.0 - self.thickness
+    }
+}
+
+/// Whether the floor has a central drain opening. Toggled with `B`.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct FloorDrain(pub bool);
+
+/// Whether the pool is sealed with a top wall mirroring the floor. Toggled with `T`.
+/// While sealed the timed spawner stops dropping balls, since there is no opening.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct TopWall(pub bool);
+
+/// Thickness of the floor alone, from `--floor-thickness` (default the wall
+/// thickness) and halved or doubled with `PageDown` / `PageUp`. The floor grows up
+/// into the pool, so a thicker one costs pool volume, but fast balls need to travel
+/// further in a single step to tunnel through it without CCD.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct FloorThickness(pub f32);
+
+impl Default for FloorThickness {
+    fn default() -> Self {
+        Self(WALL_THICKNESS)
+    }
+}
+
+impl FloorThickness {
+    pub const MIN: f32 = 2.5;
+    pub const MAX: f32 = 320.0;
+
+    /// `--floor-thickness`, or the pool's wall thickness when not given.
+    pub fn from_args(pool: PoolConfig) -> Self {
+        cli::parsed("--floor-thickness").map_or(Self(pool.thickness), |thickness: f32| {
+            Self(thickness.clamp(Self::MIN, Self::MAX))
+        })
+    }
+
+    /// Half or twice as thick, within [`Self::MIN`]..=[`Self::MAX`].
+    pub fn step(self, thicker: bool) -> Self {
+        let factor = if thicker { 2.0 } else { 0.5 };
+        Self((self.0 * factor).clamp(Self::MIN, Self::MAX))
+    }
+
+    /// Top surface of the floor; bulk spawns and the prefill are stacked from here.
+    pub fn top(self, pool: PoolConfig) -> f32 {
+        -pool.height / 2.0 + self.0
+    }
+}
+
+/// Whether the pool is left out entirely, with `W` or `--no-walls`. Balls then fall
+/// forever and are recycled to the spawn line instead of clipped, so no contacts
+/// form and the frame time is the cost of integrating the bodies alone.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct NoWalls(pub bool);
+
+impl NoWalls {
+    pub fn from_args() -> Self {
+        Self(cli::flag("--no-walls"))
+    }
+}
+
+/// Whether the four vertical inside corners of the 3D pool are filled with
+/// cylinders (`--rounded-walls`). The side walls only touch the front/back walls
+/// along an edge there, the weakest seal in the pool; a post of radius
+/// the wall thickness covers the seam. No effect in 2D, where the walls overlap.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct RoundedWalls(pub bool);
+
+impl RoundedWalls {
+    pub fn from_args() -> Self {
+        Self(cli::flag("--rounded-walls"))
+    }
+}
+
+/// Read access to the runtime settings that shape the pool.
+#[derive(SystemParam)]
+pub struct WallConfig<'w> {
+    pool: Res<'w, PoolConfig>,
+    drain: Res<'w, FloorDrain>,
+    floor_thickness: Res<'w, FloorThickness>,
+    top_wall: Res<'w, TopWall>,
+    rounded: Res<'w, RoundedWalls>,
+    mono: Res<'w, MonoWalls>,
+    obstacles: Res<'w, ObstacleLayout>,
+    no_walls: Res<'w, NoWalls>,
+}
+
+impl WallConfig<'_> {
+    /// [`spawn_walls`] with the current pool size, floor, top wall, corner, colour and
+    /// obstacle settings; nothing with [`NoWalls`].
+    pub fn spawn(
+        &self,
+        commands: &mut Commands,
+        meshes: &mut Assets<Mesh>,
+        materials: &mut Assets<StandardMaterial>,
+        mode: PhysicsMode,
+        surface: Surface,
+    ) {
+        if self.no_walls.0 {
+            return;
+        }
+        spawn_walls(
+            commands,
+            meshes,
+            materials,
+            *self.pool,
+            mode,
+            surface,
+            self.drain.0,
+            *self.floor_thickness,
+            self.top_wall.0,
+            self.rounded.0,
+            *self.mono,
+            &self.obstacles,
+        );
+    }
+
+    /// Whether the pool is built with a floor: not with [`NoWalls`] or an obstacle
+    /// layout that leaves it open.
+    pub fn has_floor(&self) -> bool {
+        !self.no_walls.0 && !self.obstacles.open_floor
+    }
+}
+
+/// Marker for the top wall, so it can be added and removed on its own.
+#[derive(Component)]
+pub struct Lid;
+
+/// Marker for every wall and floor segment, so the pool can be rebuilt in place
+/// with a new [`Surface`].
+#[derive(Component)]
+pub struct Wall;
+
+/// Marker for the floor segments, so the floor can be rebuilt when the drain toggles.
+#[derive(Component)]
+pub struct Floor;
 
 pub fn spawn_walls(
     commands: &mut Commands,
     meshes: &mut Assets<Mesh>,
     materials: &mut Assets<StandardMaterial>,
+    pool: PoolConfig,
     mode: PhysicsMode,
+    surface: Surface,
+    floor_drain: bool,
+    floor_thickness: FloorThickness,
+    top_wall: bool,
+    rounded: bool,
+    mono: MonoWalls,
+    obstacles: &ObstacleLayout,
 ) {
-    let wall_color = Color::srgb(0.4, 0.4, 0.4);
     let is_3d = matches!(mode, PhysicsMode::Avian3d | PhysicsMode::Rapier3d);
+    let side_depth = pool.side_depth(is_3d);
+    let PoolConfig {
+        width,
+        height,
+        depth,
+        thickness,
+        ..
+    } = pool;
 
-    // In 3D, trim side walls so they fit between the front and back walls (no corner overlap).
-    // The front/back walls are WALL_THICKNESS deep at each end, so side walls use the interior depth.
-    let side_depth = if is_3d {
-        POOL_DEPTH - 2.0 * WALL_THICKNESS
-    } else {
-        WALL_THICKNESS
-    };
-
-    // Floor — in 3D trimmed in both X and Z to fit inside the side and front/back walls.
-    let floor_width = if is_3d {
-        WIDTH - 2.0 * WALL_THICKNESS
-    } else {
-        WIDTH
-    };
-    backend::spawn_wall(
-        commands,
-        meshes,
-        materials,
-        mode,
-        Vec3::new(0.0, -HEIGHT / 2.0 + WALL_THICKNESS / 2.0, 0.0),
-        Vec3::new(floor_width, WALL_THICKNESS, side_depth),
-        wall_color,
-    );
+    if !obstacles.open_floor {
+        spawn_floor(
+            commands,
+            meshes,
+            materials,
+            pool,
+            mode,
+            surface,
+            floor_drain,
+            floor_thickness,
+            mono,
+        );
+    }
+    if top_wall {
+        spawn_top_wall(commands, meshes, materials, pool, mode, surface, mono);
+    }
 
     // Left wall — full height at the left edge
     backend::spawn_wall(
@@ -48,9 +341,11 @@ pub fn spawn_walls(
         meshes,
         materials,
         mode,
-        Vec3::new(-WIDTH / 2.0 + WALL_THICKNESS / 2.0, 0.0, 0.0),
-        Vec3::new(WALL_THICKNESS, HEIGHT, side_depth),
-        wall_color,
+        Vec3::new(-width / 2.0 + thickness / 2.0, 0.0, 0.0),
+        Quat::IDENTITY,
+        Vec3::new(thickness, height, side_depth),
+        mono.wall_color(mode),
+        surface,
     );
 
     // Right wall — full height at the right edge
@@ -59,9 +354,11 @@ pub fn spawn_walls(
         meshes,
         materials,
         mode,
-        Vec3::new(WIDTH / 2.0 - WALL_THICKNESS / 2.0, 0.0, 0.0),
-        Vec3::new(WALL_THICKNESS, HEIGHT, side_depth),
-        wall_color,
+        Vec3::new(width / 2.0 - thickness / 2.0, 0.0, 0.0),
+        Quat::IDENTITY,
+        Vec3::new(thickness, height, side_depth),
+        mono.wall_color(mode),
+        surface,
     );
 
     if is_3d {
@@ -71,9 +368,11 @@ pub fn spawn_walls(
             meshes,
             materials,
             mode,
-            Vec3::new(0.0, 0.0, -POOL_DEPTH / 2.0 + WALL_THICKNESS / 2.0),
-            Vec3::new(WIDTH, HEIGHT, WALL_THICKNESS),
-            wall_color,
+            Vec3::new(0.0, 0.0, -depth / 2.0 + thickness / 2.0),
+            Quat::IDENTITY,
+            Vec3::new(width, height, thickness),
+            mono.wall_color(mode),
+            surface,
         );
 
         // Front wall (toward camera) — semi-transparent glass so we can see inside
@@ -82,9 +381,129 @@ pub fn spawn_walls(
             meshes,
             materials,
             mode,
-            Vec3::new(0.0, 0.0, POOL_DEPTH / 2.0 - WALL_THICKNESS / 2.0),
-            Vec3::new(WIDTH, HEIGHT, WALL_THICKNESS),
-            Color::srgba(0.5, 0.7, 1.0, 0.15),
+            Vec3::new(0.0, 0.0, depth / 2.0 - thickness / 2.0),
+            Quat::IDENTITY,
+            Vec3::new(width, height, thickness),
+            mono.glass_color(mode),
+            surface,
+        );
+
+        if rounded {
+            spawn_corner_posts(commands, meshes, materials, pool, mode, surface, mono);
+        }
+    }
+
+    obstacles.spawn(commands, meshes, materials, mode, surface, side_depth);
+}
+
+/// Inside corners of the 3D pool (x, z), where the side walls meet the front and
+/// back walls.
+fn inside_corners(pool: PoolConfig) -> [Vec2; 4] {
+    let x = pool.width / 2.0 - pool.thickness;
+    let z = pool.depth / 2.0 - pool.thickness;
+    [
+        Vec2::new(-x, -z),
+        Vec2::new(x, -z),
+        Vec2::new(-x, z),
+        Vec2::new(x, z),
+    ]
+}
+
+/// Full-height posts centred on each inside corner (see [`RoundedWalls`]).
+fn spawn_corner_posts(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+    pool: PoolConfig,
+    mode: PhysicsMode,
+    surface: Surface,
+    mono: MonoWalls,
+) {
+    for corner in inside_corners(pool) {
+        backend::spawn_corner_post(
+            commands,
+            meshes,
+            materials,
+            mode,
+            Vec3::new(corner.x, 0.0, corner.y),
+            pool.thickness,
+            pool.height,
+            mono.wall_color(mode),
+            surface,
+        );
+    }
+}
+
+/// Spawns the top wall, tagged [`Lid`]: the floor mirrored to the top edge.
+pub fn spawn_top_wall(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+    pool: PoolConfig,
+    mode: PhysicsMode,
+    surface: Surface,
+    mono: MonoWalls,
+) {
+    let is_3d = matches!(mode, PhysicsMode::Avian3d | PhysicsMode::Rapier3d);
+    let lid = backend::spawn_wall(
+        commands,
+        meshes,
+        materials,
+        mode,
+        Vec3::new(0.0, pool.height / 2.0 - pool.thickness / 2.0, 0.0),
+        Quat::IDENTITY,
+        Vec3::new(
+            pool.floor_width(is_3d),
+            pool.thickness,
+            pool.side_depth(is_3d),
+        ),
+        mono.wall_color(mode),
+        surface,
+    );
+    commands.entity(lid).insert(Lid);
+}
+
+/// Spawns the floor, tagged [`Floor`]. With `drain` open it is split into two
+/// segments around a central [`DRAIN_WIDTH`] gap, scaled with the pool, so balls
+/// fall out of the pool and are counted as clipped, measuring throughput instead of
+/// accumulation.
+pub fn spawn_floor(
+    commands: &mut Commands,
+    meshes: &mut Assets<Mesh>,
+    materials: &mut Assets<StandardMaterial>,
+    pool: PoolConfig,
+    mode: PhysicsMode,
+    surface: Surface,
+    drain: bool,
+    thickness: FloorThickness,
+    mono: MonoWalls,
+) {
+    let is_3d = matches!(mode, PhysicsMode::Avian3d | PhysicsMode::Rapier3d);
+    let y = -pool.height / 2.0 + thickness.0 / 2.0;
+    let depth = pool.side_depth(is_3d);
+    let floor_width = pool.floor_width(is_3d);
+
+    let segments = if drain {
+        let drain_width = DRAIN_WIDTH * pool.scale;
+        let segment_width = (floor_width - drain_width) / 2.0;
+        let offset = (drain_width + segment_width) / 2.0;
+        vec![(-offset, segment_width), (offset, segment_width)]
+    } else {
+        vec![(0.0, floor_width)]
+    };
+
+    for (x, width) in segments {
+        let floor = backend::spawn_wall(
+            commands,
+            meshes,
+            materials,
+            mode,
+            Vec3::new(x, y, 0.0),
+            Quat::IDENTITY,
+            Vec3::new(width, thickness.0, depth),
+            mono.wall_color(mode),
+            surface,
         );
+        commands.entity(floor).insert(Floor);
     }
 }
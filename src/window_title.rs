@@ -0,0 +1,75 @@
+//! Window title stats — once a second the title bar becomes e.g.
+//! "Avian 2D — 12,400 balls — 58 fps", so a screen recording carries the mode, ball
+//! count and frame rate even when the HUD is cropped out.
+
+use bevy::{
+    diagnostic::{DiagnosticsStore, FrameTimeDiagnosticsPlugin},
+    prelude::*,
+    window::PrimaryWindow,
+};
+use std::time::Duration;
+
+use crate::backend::PhysicsMode;
+use crate::spawner::BallCount;
+
+/// Real time between title updates.
+const UPDATE_INTERVAL: Duration = Duration::from_secs(1);
+
+#[derive(Resource)]
+struct TitleTimer(Timer);
+
+pub fn plugin(app: &mut App) {
+    app.insert_resource(TitleTimer(Timer::new(
+        UPDATE_INTERVAL,
+        TimerMode::Repeating,
+    )));
+    app.add_systems(Update, update_window_title);
+}
+
+/// Title for `mode` with `balls` live at `fps`; the FPS is left out until the
+/// diagnostics have a value.
+fn window_title(mode: PhysicsMode, balls: usize, fps: Option<f64>) -> String {
+    let mut title = format!("{} — {} balls", mode.label(), crate::thousands(balls));
+    if let Some(fps) = fps {
+        title.push_str(&format!(" — {fps:.0} fps"));
+    }
+    title
+}
+
+/// Uses the averaged FPS, which reads steadier than the last frame's in a recording.
+fn update_window_title(
+    real: Res<Time<Real>>,
+    mut timer: ResMut<TitleTimer>,
+    diagnostics: Res<DiagnosticsStore>,
+    state: Res<State<PhysicsMode>>,
+    ball_count: Res<BallCount>,
+    mut windows: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    if !timer.0.tick(real.delta()).just_finished() {
+        return;
+    }
+    let Ok(mut window) = windows.single_mut() else {
+        return;
+    };
+    let fps = diagnostics
+        .get(&FrameTimeDiagnosticsPlugin::FPS)
+        .and_then(|d| d.average());
+    window.title = window_title(*state.get(), ball_count.0, fps);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn window_title_groups_the_ball_count() {
+        assert_eq!(
+            window_title(PhysicsMode::Avian2d, 12_400, Some(58.4)),
+            "Avian 2D — 12,400 balls — 58 fps"
+        );
+        assert_eq!(
+            window_title(PhysicsMode::Rapier3d, 0, None),
+            "Rapier 3D — 0 balls"
+        );
+    }
+}